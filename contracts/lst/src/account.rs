@@ -15,6 +15,50 @@ pub struct Account {
     /// The minimum epoch height when the withdrawn is allowed.
     /// This changes after unstaking action, because the amount is still locked for 3 epochs.
     pub last_unstake_request_epoch_height: EpochHeight,
+    /// The part of the unstaked balance that was matched against new deposits,
+    /// which can be withdrawn right away regardless of the unstaking delay.
+    pub matched_unstaked: u128,
+    /// Where the last deferred unstake of the account ends in the stream of
+    /// deferred unstakes. Withdraw waits until the stream is settled up to it.
+    pub deferred_until: u128,
+}
+
+/// Account layout of v1.0.1, kept in `legacy_accounts` until the account is saved again
+#[near(serializers = [borsh])]
+#[derive(Clone)]
+pub struct AccountV0 {
+    pub unstaked: u128,
+    pub last_unstake_request_epoch_height: EpochHeight,
+}
+
+impl From<AccountV0> for Account {
+    fn from(account: AccountV0) -> Self {
+        Self {
+            unstaked: account.unstaked,
+            last_unstake_request_epoch_height: account.last_unstake_request_epoch_height,
+            matched_unstaked: 0,
+            deferred_until: 0,
+        }
+    }
+}
+
+#[near(serializers = [borsh])]
+pub enum VersionedAccount {
+    Current(Account),
+}
+
+impl From<Account> for VersionedAccount {
+    fn from(account: Account) -> Self {
+        VersionedAccount::Current(account)
+    }
+}
+
+impl From<&VersionedAccount> for Account {
+    fn from(value: &VersionedAccount) -> Self {
+        match value {
+            VersionedAccount::Current(account) => account.clone(),
+        }
+    }
 }
 
 #[near(serializers = [json])]
@@ -40,32 +84,82 @@ pub struct AccountDetailsView {
     pub last_unstake_request_epoch_height: EpochHeight,
     /// Whether the unstaked balance is available for withdrawal now.
     pub can_withdraw: bool,
+    /// The part of the unstaked balance that can be withdrawn right away.
+    pub matched_unstaked_balance: U128,
 }
 
 impl Contract {
     pub(crate) fn internal_get_account(&self, account_id: &AccountId) -> Account {
-        // self.data().accounts.get(account_id).clone().unwrap_or_default()
         if let Some(acc) = self.data().accounts.get(account_id) {
-            acc.clone()
+            acc.into()
+        } else if let Some(acc) = self.data().legacy_accounts.get(account_id) {
+            acc.clone().into()
         } else {
             Account::default()
         }
     }
 
+    pub(crate) fn internal_has_account(&self, account_id: &AccountId) -> bool {
+        self.data().accounts.contains_key(account_id)
+            || self.data().legacy_accounts.contains_key(account_id)
+    }
+
+    /// Accounts of the old layout move to the new one once they are saved.
     pub(crate) fn internal_save_account(&mut self, account_id: &AccountId, account: &Account) {
+        self.data_mut().legacy_accounts.remove(account_id);
         self.data_mut()
             .accounts
-            .insert(account_id.clone(), account.clone());
+            .insert(account_id.clone(), account.clone().into());
+    }
+
+    pub(crate) fn internal_remove_account(&mut self, account_id: &AccountId) -> Account {
+        let account = self.internal_get_account(account_id);
+        self.data_mut().legacy_accounts.remove(account_id);
+        self.data_mut().accounts.remove(account_id);
+        account
     }
 
-    #[pause]
+    /// Whether the unstaked balance of the account is settled by validators
+    pub(crate) fn is_unstaked_matured(&self, account: &Account) -> bool {
+        account.last_unstake_request_epoch_height <= self.data().last_settlement_epoch
+            && self
+                .data()
+                .flow_limits
+                .is_deferred_unstake_settled(account.deferred_until)
+    }
+
+    /// Whether the amount of the unstaked balance can be withdrawn now, either because
+    /// the unstaking delay has passed, or because the amount was matched.
+    pub(crate) fn is_unstaked_available(&self, account: &Account, amount: u128) -> bool {
+        self.is_unstaked_matured(account) || account.matched_unstaked >= amount
+    }
+
+    /// Set aside NEAR for withdrawals. NEAR already paid out ahead of the reserve is settled first.
+    pub(crate) fn internal_credit_withdrawal_reserve(&mut self, amount: u128) {
+        let settled_amount = min(amount, self.data().withdrawal_reserve_advance);
+        self.data_mut().withdrawal_reserve_advance -= settled_amount;
+        self.data_mut().withdrawal_reserve += amount - settled_amount;
+    }
+
+    /// Take NEAR that leaves the unstaked balances from the reserve. The part the reserve
+    /// doesn't hold yet, e.g. unstaked NEAR not withdrawn from validators, is paid ahead of it.
+    pub(crate) fn internal_debit_withdrawal_reserve(&mut self, amount: u128) {
+        let reserved_amount = min(amount, self.data().withdrawal_reserve);
+        self.data_mut().withdrawal_reserve -= reserved_amount;
+        self.data_mut().withdrawal_reserve_advance += amount - reserved_amount;
+    }
+
+    #[pause(name = "deposits")]
     pub(crate) fn internal_deposit(&mut self, amount: u128) {
+        self.assert_not_emergency_mode();
         require!(amount > 0, ERR_NON_POSITIVE_DEPOSIT_AMOUNT);
 
         let account_id = env::predecessor_account_id();
         let mut account = self.internal_get_account(&account_id);
         account.unstaked += amount;
         self.internal_save_account(&account_id, &account);
+        // deposited NEAR waits in the contract until it is staked or withdrawn
+        self.internal_credit_withdrawal_reserve(amount);
 
         Event::Deposit {
             account_id: &account_id,
@@ -83,30 +177,35 @@ impl Contract {
             account.unstaked >= amount,
             ERR_NO_ENOUGH_UNSTAKED_BALANCE_TO_WITHDRAW
         );
+        // before the unstaking delay passes, only the matched part can be withdrawn
         require!(
-            account.last_unstake_request_epoch_height <= self.data().last_settlement_epoch,
+            self.is_unstaked_available(&account, amount),
             ERR_UNSTAKED_BALANCE_NOT_AVAILABLE
         );
         // Make sure the contract has enough NEAR for user to withdraw,
         // Note that account locked balance should not be included.
         let available_balance = env::account_balance();
-        // at least 1 NEAR should be left to cover storage/gas.
+        // at least the min reserve balance should be left to cover storage/gas.
+        let min_reserve_balance = self.internal_config().contract_min_reserve_balance();
         require!(
-            available_balance.saturating_sub(CONTRACT_MIN_RESERVE_BALANCE)
+            available_balance.saturating_sub(min_reserve_balance)
                 >= NearToken::from_yoctonear(amount),
             ERR_NO_ENOUGH_CONTRACT_BALANCE
         );
     }
 
-    #[pause]
     pub(crate) fn internal_withdraw(&mut self, amount: u128) {
         let account_id = env::predecessor_account_id();
+        self.assert_withdrawals_not_paused(&self.internal_get_account(&account_id));
         self.assert_can_withdraw(&account_id, amount);
 
         let mut account = self.internal_get_account(&account_id);
         account.unstaked -= amount;
+        account.matched_unstaked = account.matched_unstaked.saturating_sub(amount);
         self.internal_save_account(&account_id, &account);
 
+        self.internal_debit_withdrawal_reserve(amount);
+
         Event::Withdraw {
             account_id: &account_id,
             amount: &U128(amount),
@@ -116,12 +215,13 @@ impl Contract {
         Promise::new(account_id).transfer(NearToken::from_yoctonear(amount));
     }
 
-    #[pause]
+    #[pause(name = "deposits")]
     pub(crate) fn internal_rnear_stake(
         &mut self,
         near_amount: u128,
         rnear_amount: u128,
     ) -> ShareBalance {
+        self.assert_not_emergency_mode();
         require!(near_amount > 0, ERR_NON_POSITIVE_STAKING_AMOUNT);
 
         let account_id = env::predecessor_account_id();
@@ -136,9 +236,11 @@ impl Contract {
             account.unstaked >= near_amount,
             ERR_NO_ENOUGH_UNSTAKED_BALANCE
         );
+        self.internal_check_deposit_limits(near_amount);
         account.unstaked -= near_amount;
         self.mint_lst(&account_id, num_shares, Some("stake"));
         self.internal_save_account(&account_id, &account);
+        self.internal_debit_withdrawal_reserve(near_amount);
         self.data_mut().total_staked_asset_in_near += near_amount;
         self.data_mut().rnear_balance += rnear_amount;
 
@@ -160,25 +262,44 @@ impl Contract {
         num_shares
     }
 
-    #[pause]
+    #[pause(name = "deposits")]
     pub(crate) fn internal_stake(&mut self, amount: u128) -> ShareBalance {
+        self.assert_not_emergency_mode();
         require!(amount > 0, ERR_NON_POSITIVE_STAKING_AMOUNT);
 
         let account_id = env::predecessor_account_id();
         let mut account = self.internal_get_account(&account_id);
 
         // Calculate the number of "stake" shares that the account will receive for staking the
-        // given amount.
-        let num_shares = self.num_shares_from_staked_amount_rounded_down(amount);
+        // given amount, after the deposit fee.
+        let fee_amount = bps_mul(amount, self.effective_fee_config().deposit_fee_bps);
+        let num_shares = self.num_shares_from_staked_amount_rounded_down(amount - fee_amount);
         require!(num_shares > 0, ERR_NON_POSITIVE_CALCULATED_STAKING_SHARE);
+        let fee_shares = self.num_shares_from_staked_amount_rounded_down(fee_amount);
 
         require!(account.unstaked >= amount, ERR_NO_ENOUGH_UNSTAKED_BALANCE);
+        self.internal_check_deposit_limits(amount);
         account.unstaked -= amount;
+        account.matched_unstaked = min(account.matched_unstaked, account.unstaked);
         self.mint_lst(&account_id, num_shares, Some("stake"));
+        self.internal_charge_fee(&account_id, "deposit fee", fee_amount, fee_shares);
         self.internal_save_account(&account_id, &account);
+        self.internal_debit_withdrawal_reserve(amount);
         self.data_mut().total_staked_asset_in_near += amount;
         // Increase requested stake amount within the current epoch
         self.data_mut().epoch_requested_stake_amount += amount;
+        self.internal_on_partner_stake(&account_id, amount);
+
+        // Fund queued unstake requests first
+        let matched_amount = self.internal_match_stake(amount);
+        if matched_amount > 0 {
+            Event::StakeMatched {
+                account_id: &account_id,
+                matched_amount: &U128(matched_amount),
+                unmatched_amount: &U128(amount - matched_amount),
+            }
+            .emit();
+        }
 
         Event::Stake {
             account_id: &account_id,
@@ -198,8 +319,10 @@ impl Contract {
         num_shares
     }
 
-    #[pause]
+    #[pause(name = "unstakes")]
     pub(crate) fn internal_unstake(&mut self, amount: u128) {
+        // LST holders exit through emergency_withdraw instead
+        self.assert_not_emergency_mode();
         require!(amount > 0, ERR_NON_POSITIVE_UNSTAKING_AMOUNT);
 
         let account_id = env::predecessor_account_id();
@@ -214,29 +337,81 @@ impl Contract {
         let num_shares = self.num_shares_from_staked_amount_rounded_up(amount);
         require!(num_shares > 0, ERR_NON_POSITIVE_CALCULATED_UNSTAKING_SHARE);
 
+        // The part above the epoch unstake limit is deferred to the following epochs
+        let accepted_amount = self.internal_apply_unstake_limit(amount);
+
+        // The part matched against NEAR deposited within the current epoch pays
+        // the instant exit fee, which stays staked and is paid to the treasury in LST.
+        let fee_amount = bps_mul(
+            min(accepted_amount, self.data().epoch_requested_stake_amount),
+            self.effective_fee_config().instant_exit_fee_bps,
+        );
+        let fee_shares = self.num_shares_from_staked_amount_rounded_down(fee_amount);
+        let fee_amount = if fee_shares > 0 { fee_amount } else { 0 };
+
         self.burn_lst(&account_id, num_shares, Some("unstake"));
+        self.internal_charge_fee(&account_id, "instant exit fee", fee_amount, fee_shares);
+        let amount = amount - fee_amount;
+        let accepted_amount = accepted_amount - fee_amount;
+        let deferred_amount = amount - accepted_amount;
+
+        self.data_mut().total_staked_asset_in_near -= amount;
+
+        // Increase requested unstake amount within the current epoch
+        self.data_mut().epoch_requested_unstake_amount += accepted_amount;
+        self.internal_on_partner_unstake(&account_id, amount);
+
+        // Match against NEAR deposited within the current epoch,
+        // and queue the rest to be funded by the following deposits.
+        let matched_amount = self.internal_match_unstake(accepted_amount);
+        let unmatched_amount = accepted_amount - matched_amount;
+        let request_id = if unmatched_amount > 0 {
+            Some(self.internal_queue_unstake(&account_id, unmatched_amount))
+        } else {
+            None
+        };
 
         account.unstaked += amount;
-        account.last_unstake_request_epoch_height = get_epoch_height();
-
-        if [
-            self.data().last_settlement_epoch,
-            self.data().last_settlement_initiated_epoch,
-        ]
-        .contains(&&get_epoch_height())
-        {
-            // The unstake request is received after epoch_cleanup
-            // so actual unstake will happen in the next epoch,
-            // which will put withdraw off for one more epoch.
-            account.last_unstake_request_epoch_height += 1;
+        account.matched_unstaked += matched_amount;
+
+        if unmatched_amount > 0 || deferred_amount > 0 {
+            account.last_unstake_request_epoch_height = get_epoch_height();
+
+            if [
+                self.data().last_settlement_epoch,
+                self.data().last_settlement_initiated_epoch,
+            ]
+            .contains(&&get_epoch_height())
+            {
+                // The unstake request is received after epoch_cleanup
+                // so actual unstake will happen in the next epoch,
+                // which will put withdraw off for one more epoch.
+                account.last_unstake_request_epoch_height += 1;
+            }
+        }
+        if deferred_amount > 0 {
+            // withdraw is put off until this request is released and settled
+            account.deferred_until = self.data().flow_limits.total_deferred_amount;
+
+            Event::UnstakeDeferred {
+                account_id: &account_id,
+                deferred_amount: &U128(deferred_amount),
+                deferred_unstake_amount: &U128(self.data().flow_limits.deferred_unstake_amount),
+            }
+            .emit();
         }
 
         self.internal_save_account(&account_id, &account);
 
-        self.data_mut().total_staked_asset_in_near -= amount;
-
-        // Increase requested unstake amount within the current epoch
-        self.data_mut().epoch_requested_unstake_amount += amount;
+        if matched_amount > 0 {
+            Event::UnstakeMatched {
+                account_id: &account_id,
+                request_id,
+                matched_amount: &U128(matched_amount),
+                unmatched_amount: &U128(unmatched_amount),
+            }
+            .emit();
+        }
 
         Event::Unstake {
             account_id: &account_id,
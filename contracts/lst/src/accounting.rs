@@ -0,0 +1,251 @@
+use crate::*;
+
+const DEFAULT_MAX_ADJUSTMENT_PER_CALL: u128 = 10 * ONE_NEAR;
+const DEFAULT_MAX_ADJUSTMENT_PER_EPOCH: u128 = 100 * ONE_NEAR;
+/// Max unstaked amount on a validator that can be written off as dust
+pub const MAX_DUST_AMOUNT: u128 = ONE_NEAR;
+pub const MAX_ADJUSTMENT_REASON_LEN: usize = 256;
+
+/// Bounds of manual accounting adjustments, on the sum of absolute changes
+#[near(serializers = [borsh, json])]
+#[derive(Clone)]
+pub struct AccountingLimits {
+    pub max_adjustment_per_call: U128,
+    pub max_adjustment_per_epoch: U128,
+}
+
+impl Default for AccountingLimits {
+    fn default() -> Self {
+        Self {
+            max_adjustment_per_call: U128(DEFAULT_MAX_ADJUSTMENT_PER_CALL),
+            max_adjustment_per_epoch: U128(DEFAULT_MAX_ADJUSTMENT_PER_EPOCH),
+        }
+    }
+}
+
+#[near(serializers = [borsh])]
+#[derive(Default)]
+pub struct Accounting {
+    pub limits: AccountingLimits,
+    /// The epoch of the adjusted amount
+    pub epoch: EpochHeight,
+    pub epoch_adjusted_amount: u128,
+}
+
+impl Accounting {
+    pub fn current_epoch_adjusted_amount(&self) -> u128 {
+        if self.epoch == get_epoch_height() {
+            self.epoch_adjusted_amount
+        } else {
+            0
+        }
+    }
+}
+
+#[near(serializers = [json])]
+pub struct AccountingView {
+    pub limits: AccountingLimits,
+    pub epoch_adjusted_amount: U128,
+    /// Amount that can still be adjusted in the current epoch
+    pub epoch_headroom: U128,
+}
+
+/// A change of one accounting field
+struct Adjustment<'a> {
+    field: &'a str,
+    old_value: u128,
+    new_value: u128,
+}
+
+impl Adjustment<'_> {
+    fn amount(&self) -> u128 {
+        self.old_value.abs_diff(self.new_value)
+    }
+}
+
+impl Contract {
+    /// Check the reason and the limits of the adjustments, count them
+    /// towards the epoch limit and emit an audit event for each change.
+    fn internal_record_adjustments(
+        &mut self,
+        action: &str,
+        validator_id: Option<&AccountId>,
+        adjustments: &[Adjustment],
+        reason: &str,
+    ) {
+        require!(
+            !reason.trim().is_empty() && reason.len() <= MAX_ADJUSTMENT_REASON_LEN,
+            ERR_INVALID_ADJUSTMENT_REASON
+        );
+        let amount: u128 = adjustments
+            .iter()
+            .map(|adjustment| adjustment.amount())
+            .sum();
+        require!(amount > 0, ERR_NO_ADJUSTMENT);
+
+        let accounting = &self.data().accounting;
+        require!(
+            amount <= accounting.limits.max_adjustment_per_call.0,
+            ERR_ADJUSTMENT_TOO_LARGE
+        );
+        let epoch_adjusted_amount = accounting.current_epoch_adjusted_amount() + amount;
+        require!(
+            epoch_adjusted_amount <= accounting.limits.max_adjustment_per_epoch.0,
+            ERR_EPOCH_ADJUSTMENT_LIMIT_EXCEEDED
+        );
+
+        let epoch = get_epoch_height();
+        let accounting = &mut self.data_mut().accounting;
+        accounting.epoch = epoch;
+        accounting.epoch_adjusted_amount = epoch_adjusted_amount;
+
+        let accountant_id = env::predecessor_account_id();
+        for adjustment in adjustments
+            .iter()
+            .filter(|adjustment| adjustment.amount() > 0)
+        {
+            Event::AccountingAdjusted {
+                accountant_id: &accountant_id,
+                action,
+                validator_id,
+                field: adjustment.field,
+                old_value: &U128(adjustment.old_value),
+                new_value: &U128(adjustment.new_value),
+                reason,
+                epoch,
+                epoch_adjusted_amount: &U128(epoch_adjusted_amount),
+            }
+            .emit();
+        }
+    }
+
+    fn internal_get_idle_validator(&self, validator_id: &AccountId) -> Validator {
+        let validator = self
+            .data()
+            .validator_pool
+            .get_validator(validator_id)
+            .expect(ERR_VALIDATOR_NOT_EXIST);
+        require!(
+            !validator.is_executing(),
+            ERR_VALIDATOR_ALREADY_EXECUTING_ACTION
+        );
+        validator
+    }
+}
+
+#[near]
+impl Contract {
+    /// Correct the recorded staked and unstaked amounts of a validator
+    /// to the balances found by an off-chain audit.
+    #[payable]
+    #[access_control_any(roles(Role::Accountant))]
+    #[pause]
+    pub fn adjust_validator_amounts(
+        &mut self,
+        validator_id: AccountId,
+        staked_amount: U128,
+        unstaked_amount: U128,
+        reason: String,
+    ) {
+        assert_one_yocto();
+        let mut validator = self.internal_get_idle_validator(&validator_id);
+        self.internal_record_adjustments(
+            "adjust_validator_amounts",
+            Some(&validator_id),
+            &[
+                Adjustment {
+                    field: "staked_amount",
+                    old_value: validator.staked_amount,
+                    new_value: staked_amount.0,
+                },
+                Adjustment {
+                    field: "unstaked_amount",
+                    old_value: validator.unstaked_amount,
+                    new_value: unstaked_amount.0,
+                },
+            ],
+            &reason,
+        );
+        validator.staked_amount = staked_amount.0;
+        validator.unstaked_amount = unstaked_amount.0;
+        self.data_mut().validator_pool.save_validator(&validator);
+    }
+
+    /// Write off the unstaked dust left on a validator,
+    /// which is too small to be withdrawn.
+    #[payable]
+    #[access_control_any(roles(Role::Accountant))]
+    #[pause]
+    pub fn write_off_dust(&mut self, validator_id: AccountId, reason: String) {
+        assert_one_yocto();
+        let mut validator = self.internal_get_idle_validator(&validator_id);
+        require!(
+            validator.unstaked_amount < MAX_DUST_AMOUNT
+                && validator.redelegation_unstaked_amount == 0,
+            ERR_NOT_DUST
+        );
+        self.internal_record_adjustments(
+            "write_off_dust",
+            Some(&validator_id),
+            &[Adjustment {
+                field: "unstaked_amount",
+                old_value: validator.unstaked_amount,
+                new_value: 0,
+            }],
+            &reason,
+        );
+        validator.unstaked_amount = 0;
+        self.data_mut().validator_pool.save_validator(&validator);
+    }
+
+    /// Correct the NEAR amount waiting to be staked on validators.
+    #[payable]
+    #[access_control_any(roles(Role::Accountant))]
+    #[pause]
+    pub fn adjust_stake_amount_to_settle(&mut self, amount: U128, reason: String) {
+        assert_one_yocto();
+        self.internal_record_adjustments(
+            "adjust_stake_amount_to_settle",
+            None,
+            &[Adjustment {
+                field: "stake_amount_to_settle",
+                old_value: self.data().stake_amount_to_settle,
+                new_value: amount.0,
+            }],
+            &reason,
+        );
+        self.data_mut().stake_amount_to_settle = amount.0;
+    }
+
+    #[payable]
+    #[access_control_any(roles(Role::DAO))]
+    #[pause]
+    pub fn set_accounting_limits(&mut self, limits: AccountingLimits) {
+        assert_one_yocto();
+        require!(
+            limits.max_adjustment_per_call.0 <= limits.max_adjustment_per_epoch.0,
+            ERR_INVALID_ACCOUNTING_LIMITS
+        );
+        Event::SetAccountingLimits {
+            old_limits: &self.data().accounting.limits,
+            new_limits: &limits,
+        }
+        .emit();
+        self.data_mut().accounting.limits = limits;
+    }
+
+    pub fn get_accounting(&self) -> AccountingView {
+        let accounting = &self.data().accounting;
+        let epoch_adjusted_amount = accounting.current_epoch_adjusted_amount();
+        AccountingView {
+            limits: accounting.limits.clone(),
+            epoch_adjusted_amount: epoch_adjusted_amount.into(),
+            epoch_headroom: accounting
+                .limits
+                .max_adjustment_per_epoch
+                .0
+                .saturating_sub(epoch_adjusted_amount)
+                .into(),
+        }
+    }
+}
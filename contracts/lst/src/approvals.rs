@@ -0,0 +1,312 @@
+use crate::*;
+
+/// Max approvals that can be required for an operator action
+pub const MAX_APPROVAL_THRESHOLD: u32 = 10;
+pub const DEFAULT_APPROVAL_PROPOSAL_TTL_SEC: u64 = 3 * 24 * 3600;
+pub const MAX_APPROVAL_PROPOSAL_TTL_SEC: u64 = 30 * 24 * 3600;
+
+/// High-impact operator actions that need several approvals
+/// when multi approval is enabled
+#[near(serializers = [borsh, json])]
+#[derive(Clone)]
+pub enum OperatorAction {
+    DrainUnstake {
+        validator_id: AccountId,
+    },
+    RemoveValidator {
+        validator_id: AccountId,
+    },
+    /// Queued in the timelock once approved, if weight updates have a delay
+    UpdateWeights {
+        validator_ids: Vec<AccountId>,
+        weights: Vec<u16>,
+    },
+}
+
+#[near(serializers = [borsh, json])]
+#[derive(Clone)]
+pub struct ApprovalConfig {
+    /// Number of approvals, including the proposer, needed to run an action.
+    /// 1 disables multi approval and the actions can be called directly.
+    pub threshold: u32,
+    pub proposal_ttl_sec: u64,
+}
+
+impl Default for ApprovalConfig {
+    fn default() -> Self {
+        Self {
+            threshold: 1,
+            proposal_ttl_sec: DEFAULT_APPROVAL_PROPOSAL_TTL_SEC,
+        }
+    }
+}
+
+#[near(serializers = [borsh, json])]
+#[derive(Clone)]
+pub struct ApprovalProposal {
+    pub id: u64,
+    pub action: OperatorAction,
+    pub proposer_id: AccountId,
+    /// Accounts that approved the action, starting with the proposer
+    pub approvals: Vec<AccountId>,
+    pub created_at: U64,
+    pub expires_at: U64,
+}
+
+impl ApprovalProposal {
+    pub fn is_expired(&self) -> bool {
+        env::block_timestamp() >= self.expires_at.0
+    }
+}
+
+#[near(serializers = [borsh])]
+pub struct Approvals {
+    pub config: ApprovalConfig,
+    pub proposals: IterableMap<u64, ApprovalProposal>,
+    pub next_id: u64,
+}
+
+impl Approvals {
+    pub fn new() -> Self {
+        Self {
+            config: ApprovalConfig::default(),
+            proposals: IterableMap::new(StorageKey::ApprovalProposals),
+            next_id: 0,
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.config.threshold > 1
+    }
+}
+
+impl Default for Approvals {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Contract {
+    /// Operator actions must be proposed and approved when multi approval is enabled.
+    pub(crate) fn assert_no_approval_required(&self) {
+        require!(!self.data().approvals.is_enabled(), ERR_APPROVAL_REQUIRED);
+    }
+
+    fn assert_operator(&self, account_id: &AccountId) {
+        require!(
+            self.acl_has_any_role(
+                vec![Role::OpManager.into(), Role::DAO.into()],
+                account_id.clone()
+            ),
+            ERR_APPROVAL_NOT_ALLOWED
+        );
+    }
+
+    fn internal_execute_operator_action(&mut self, action: OperatorAction, proposer_id: AccountId) {
+        self.assert_scope_not_paused(PauseScope::ValidatorAdmin);
+        match action {
+            OperatorAction::DrainUnstake { validator_id } => {
+                let gas = self.internal_config().gas;
+                let min_gas = gas.drain_unstake.as_gas() + gas.unstake();
+                require!(
+                    env::prepaid_gas().as_gas() >= min_gas,
+                    format!("{}. require at least {:?}", ERR_NO_ENOUGH_GAS, min_gas)
+                );
+                self.internal_drain_unstake(&validator_id, None);
+            }
+            OperatorAction::RemoveValidator { validator_id } => {
+                self.data_mut()
+                    .validator_pool
+                    .remove_validator(&validator_id);
+            }
+            OperatorAction::UpdateWeights {
+                validator_ids,
+                weights,
+            } => {
+                // approvals don't skip the weights timelock
+                if self
+                    .data()
+                    .timelock
+                    .delay_sec(TimelockActionKind::UpdateWeights)
+                    > 0
+                {
+                    self.internal_queue_timelock_action(
+                        TimelockAction::UpdateWeights {
+                            validator_ids,
+                            weights,
+                        },
+                        proposer_id,
+                    );
+                } else {
+                    self.internal_update_weights(validator_ids, weights);
+                }
+            }
+        }
+    }
+
+    /// Run the proposal once it has enough approvals.
+    fn internal_try_execute_proposal(&mut self, proposal: ApprovalProposal) -> bool {
+        if (proposal.approvals.len() as u32) < self.data().approvals.config.threshold {
+            return false;
+        }
+        self.data_mut().approvals.proposals.remove(&proposal.id);
+        Event::ApprovalExecuted { id: proposal.id }.emit();
+        self.internal_execute_operator_action(proposal.action, proposal.proposer_id);
+        true
+    }
+}
+
+#[near]
+impl Contract {
+    /// Propose an operator action, which runs once enough operators approve it.
+    /// The proposal counts as the first approval.
+    #[payable]
+    #[pause]
+    pub fn propose_operator_action(&mut self, action: OperatorAction) -> u64 {
+        assert_one_yocto();
+        let proposer_id = env::predecessor_account_id();
+        self.assert_operator(&proposer_id);
+        require!(
+            self.data().approvals.is_enabled(),
+            ERR_APPROVALS_NOT_ENABLED
+        );
+
+        let now = env::block_timestamp();
+        let id = self.data().approvals.next_id;
+        let proposal = ApprovalProposal {
+            id,
+            action,
+            proposer_id: proposer_id.clone(),
+            approvals: vec![proposer_id],
+            created_at: now.into(),
+            expires_at: (now + self.data().approvals.config.proposal_ttl_sec * 1_000_000_000)
+                .into(),
+        };
+        Event::ApprovalProposed {
+            proposal: &proposal,
+        }
+        .emit();
+        self.data_mut().approvals.proposals.insert(id, proposal);
+        self.data_mut().approvals.next_id += 1;
+        id
+    }
+
+    /// Approve an open proposal. The action runs with the approval
+    /// that meets the threshold. Returns whether the action ran.
+    #[payable]
+    #[pause]
+    pub fn approve_operator_action(&mut self, id: u64) -> bool {
+        assert_one_yocto();
+        let account_id = env::predecessor_account_id();
+        self.assert_operator(&account_id);
+        let mut proposal = self
+            .data()
+            .approvals
+            .proposals
+            .get(&id)
+            .cloned()
+            .expect(ERR_APPROVAL_PROPOSAL_NOT_EXIST);
+        require!(!proposal.is_expired(), ERR_APPROVAL_PROPOSAL_EXPIRED);
+        require!(
+            !proposal.approvals.contains(&account_id),
+            ERR_ALREADY_APPROVED
+        );
+
+        proposal.approvals.push(account_id.clone());
+        Event::ApprovalAdded {
+            id,
+            account_id: &account_id,
+            num_approvals: proposal.approvals.len() as u32,
+        }
+        .emit();
+        self.data_mut()
+            .approvals
+            .proposals
+            .insert(id, proposal.clone());
+        self.internal_try_execute_proposal(proposal)
+    }
+
+    /// Run a proposal that has enough approvals, e.g. after the threshold
+    /// was lowered or a previous run ran out of gas. Anyone can call it.
+    #[pause]
+    pub fn execute_operator_action(&mut self, id: u64) {
+        let proposal = self
+            .data()
+            .approvals
+            .proposals
+            .get(&id)
+            .cloned()
+            .expect(ERR_APPROVAL_PROPOSAL_NOT_EXIST);
+        require!(!proposal.is_expired(), ERR_APPROVAL_PROPOSAL_EXPIRED);
+        require!(
+            self.internal_try_execute_proposal(proposal),
+            ERR_NOT_ENOUGH_APPROVALS
+        );
+    }
+
+    /// Cancel a proposal. Allowed for the proposer and DAO,
+    /// and for anyone once the proposal has expired.
+    #[payable]
+    #[pause]
+    pub fn cancel_operator_action(&mut self, id: u64) {
+        assert_one_yocto();
+        let proposal = self
+            .data()
+            .approvals
+            .proposals
+            .get(&id)
+            .cloned()
+            .expect(ERR_APPROVAL_PROPOSAL_NOT_EXIST);
+        let account_id = env::predecessor_account_id();
+        require!(
+            proposal.is_expired()
+                || proposal.proposer_id == account_id
+                || self.acl_has_role(Role::DAO.into(), account_id),
+            ERR_APPROVAL_NOT_ALLOWED
+        );
+        self.data_mut().approvals.proposals.remove(&id);
+        Event::ApprovalCancelled { id }.emit();
+    }
+
+    #[payable]
+    #[access_control_any(roles(Role::DAO))]
+    #[pause]
+    pub fn set_approval_config(&mut self, config: ApprovalConfig) {
+        assert_one_yocto();
+        require!(
+            (1..=MAX_APPROVAL_THRESHOLD).contains(&config.threshold)
+                && config.proposal_ttl_sec > 0
+                && config.proposal_ttl_sec <= MAX_APPROVAL_PROPOSAL_TTL_SEC,
+            ERR_INVALID_APPROVAL_CONFIG
+        );
+        Event::SetApprovalConfig {
+            old_config: &self.data().approvals.config,
+            new_config: &config,
+        }
+        .emit();
+        self.data_mut().approvals.config = config;
+    }
+
+    pub fn get_approval_config(&self) -> ApprovalConfig {
+        self.data().approvals.config.clone()
+    }
+
+    /// Proposals that have not expired yet
+    pub fn get_operator_proposals(
+        &self,
+        from_index: Option<usize>,
+        limit: Option<usize>,
+    ) -> Vec<ApprovalProposal> {
+        let skip_n = from_index.unwrap_or(0);
+        let take_n = limit.unwrap_or(self.data().approvals.proposals.len() as usize);
+        self.data()
+            .approvals
+            .proposals
+            .values()
+            .filter(|proposal| !proposal.is_expired())
+            .skip(skip_n)
+            .take(take_n)
+            .cloned()
+            .collect()
+    }
+}
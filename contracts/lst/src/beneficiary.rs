@@ -0,0 +1,252 @@
+use crate::*;
+
+/// Max number of beneficiary payouts kept in history
+pub const BENEFICIARY_PAYOUT_HISTORY_SIZE: u32 = 100;
+
+/// How a beneficiary receives its rewards
+#[near(serializers = [borsh, json])]
+#[derive(Clone, Copy, Default, PartialEq)]
+pub enum BeneficiaryPayoutKind {
+    /// LST minted from rewards
+    #[default]
+    Lst,
+    /// NEAR unstaked from the minted LST, to be claimed
+    Near,
+}
+
+#[near(serializers = [borsh])]
+#[derive(Clone, Default)]
+pub struct BeneficiaryAccount {
+    pub payout: BeneficiaryPayoutKind,
+    /// Total LST shares earned
+    pub earned_shares: u128,
+    /// Total NEAR value of the earned shares when paid out
+    pub earned_amount: u128,
+    /// Total NEAR claimed
+    pub claimed_amount: u128,
+    /// NEAR unstaked for the beneficiary and not claimed yet
+    pub unstaked_amount: u128,
+    /// The epoch of the latest unstake, after which the
+    /// unstaked NEAR becomes claimable once settled
+    pub unstake_epoch: EpochHeight,
+}
+
+#[near(serializers = [json])]
+pub struct BeneficiaryEarningsView {
+    pub account_id: AccountId,
+    pub payout: BeneficiaryPayoutKind,
+    pub earned_shares: U128,
+    pub earned_amount: U128,
+    pub claimed_amount: U128,
+    pub unstaked_amount: U128,
+    pub claimable_amount: U128,
+}
+
+#[near(serializers = [borsh, json])]
+#[derive(Clone)]
+pub struct BeneficiaryPayoutRecord {
+    pub account_id: AccountId,
+    pub epoch: EpochHeight,
+    pub payout: BeneficiaryPayoutKind,
+    pub shares: U128,
+    pub amount: U128,
+}
+
+impl Contract {
+    /// Pay reward shares to the beneficiary in the way it chose.
+    pub(crate) fn internal_pay_beneficiary(&mut self, account_id: &AccountId, shares: u128) {
+        let mut beneficiary = self
+            .data()
+            .beneficiary_accounts
+            .get(account_id)
+            .cloned()
+            .unwrap_or_default();
+        let amount = self.staked_amount_from_num_shares_rounded_down(shares);
+
+        self.mint_lst(account_id, shares, Some("beneficiary rewards"));
+        if beneficiary.payout == BeneficiaryPayoutKind::Near && amount > 0 {
+            // unstake the minted shares on behalf of the beneficiary
+            self.burn_lst(account_id, shares, Some("beneficiary payout"));
+            self.data_mut().total_staked_asset_in_near -= amount;
+            self.data_mut().epoch_requested_unstake_amount += amount;
+
+            beneficiary.unstaked_amount += amount;
+            beneficiary.unstake_epoch = get_epoch_height();
+            if [
+                self.data().last_settlement_epoch,
+                self.data().last_settlement_initiated_epoch,
+            ]
+            .contains(&get_epoch_height())
+            {
+                // unstaked in the next settlement, same as user unstakes
+                beneficiary.unstake_epoch += 1;
+            }
+        }
+        beneficiary.earned_shares += shares;
+        beneficiary.earned_amount += amount;
+        let payout = beneficiary.payout;
+        self.data_mut()
+            .beneficiary_accounts
+            .insert(account_id.clone(), beneficiary);
+
+        self.data_mut()
+            .beneficiary_payout_history
+            .push(BeneficiaryPayoutRecord {
+                account_id: account_id.clone(),
+                epoch: get_epoch_height(),
+                payout,
+                shares: shares.into(),
+                amount: amount.into(),
+            });
+        Event::BeneficiaryPaid {
+            account_id,
+            payout,
+            shares: &U128(shares),
+            amount: &U128(amount),
+        }
+        .emit();
+    }
+
+    fn internal_claimable_amount(&self, beneficiary: &BeneficiaryAccount) -> u128 {
+        if beneficiary.unstake_epoch <= self.data().last_settlement_epoch {
+            beneficiary.unstaked_amount
+        } else {
+            0
+        }
+    }
+
+    fn is_reward_recipient(&self, account_id: &AccountId) -> bool {
+        self.data().beneficiaries.get(account_id).is_some()
+            || self
+                .effective_fee_config()
+                .reward_fee_recipients()
+                .iter()
+                .any(|recipient| &recipient.account_id == account_id)
+    }
+
+    fn beneficiary_earnings_view(
+        &self,
+        account_id: &AccountId,
+        beneficiary: &BeneficiaryAccount,
+    ) -> BeneficiaryEarningsView {
+        BeneficiaryEarningsView {
+            account_id: account_id.clone(),
+            payout: beneficiary.payout,
+            earned_shares: beneficiary.earned_shares.into(),
+            earned_amount: beneficiary.earned_amount.into(),
+            claimed_amount: beneficiary.claimed_amount.into(),
+            unstaked_amount: beneficiary.unstaked_amount.into(),
+            claimable_amount: self.internal_claimable_amount(beneficiary).into(),
+        }
+    }
+}
+
+#[near]
+impl Contract {
+    /// Choose how the predecessor receives its rewards as a beneficiary.
+    #[pause]
+    pub fn set_beneficiary_payout(&mut self, payout: BeneficiaryPayoutKind) {
+        let account_id = env::predecessor_account_id();
+        require!(self.is_reward_recipient(&account_id), ERR_NOT_BENEFICIARY);
+
+        let mut beneficiary = self
+            .data()
+            .beneficiary_accounts
+            .get(&account_id)
+            .cloned()
+            .unwrap_or_default();
+        beneficiary.payout = payout;
+        self.data_mut()
+            .beneficiary_accounts
+            .insert(account_id.clone(), beneficiary);
+
+        Event::SetBeneficiaryPayout {
+            account_id: &account_id,
+            payout,
+        }
+        .emit();
+    }
+
+    /// Claim all NEAR unstaked for the predecessor as a beneficiary.
+    #[pause]
+    pub fn claim_beneficiary_payout(&mut self) -> U128 {
+        let account_id = env::predecessor_account_id();
+        let mut beneficiary = self
+            .data()
+            .beneficiary_accounts
+            .get(&account_id)
+            .cloned()
+            .expect(ERR_NOT_BENEFICIARY);
+        require!(beneficiary.unstaked_amount > 0, ERR_NOTHING_TO_CLAIM);
+        let amount = self.internal_claimable_amount(&beneficiary);
+        require!(amount > 0, ERR_UNSTAKED_BALANCE_NOT_AVAILABLE);
+        require!(
+            env::account_balance()
+                .saturating_sub(self.internal_config().contract_min_reserve_balance())
+                >= NearToken::from_yoctonear(amount),
+            ERR_NO_ENOUGH_CONTRACT_BALANCE
+        );
+
+        beneficiary.unstaked_amount -= amount;
+        beneficiary.claimed_amount += amount;
+        self.data_mut()
+            .beneficiary_accounts
+            .insert(account_id.clone(), beneficiary);
+        self.internal_debit_withdrawal_reserve(amount);
+
+        Event::BeneficiaryClaim {
+            account_id: &account_id,
+            amount: &U128(amount),
+        }
+        .emit();
+        Promise::new(account_id).transfer(NearToken::from_yoctonear(amount));
+        amount.into()
+    }
+
+    pub fn get_beneficiary_earnings(
+        &self,
+        account_id: AccountId,
+    ) -> Option<BeneficiaryEarningsView> {
+        self.data()
+            .beneficiary_accounts
+            .get(&account_id)
+            .map(|beneficiary| self.beneficiary_earnings_view(&account_id, beneficiary))
+    }
+
+    /// Returns the earnings of all accounts that have been paid as beneficiaries
+    pub fn get_beneficiaries_earnings(
+        &self,
+        from_index: Option<usize>,
+        limit: Option<usize>,
+    ) -> Vec<BeneficiaryEarningsView> {
+        let skip_n = from_index.unwrap_or(0);
+        let take_n = limit.unwrap_or(self.data().beneficiary_accounts.len() as usize);
+        self.data()
+            .beneficiary_accounts
+            .iter()
+            .skip(skip_n)
+            .take(take_n)
+            .map(|(account_id, beneficiary)| {
+                self.beneficiary_earnings_view(account_id, beneficiary)
+            })
+            .collect()
+    }
+
+    /// Returns the latest beneficiary payouts, optionally of a single account
+    pub fn get_beneficiary_payout_history(
+        &self,
+        account_id: Option<AccountId>,
+        limit: Option<usize>,
+    ) -> Vec<BeneficiaryPayoutRecord> {
+        self.data()
+            .beneficiary_payout_history
+            .iter_latest()
+            .filter(|record| match account_id.as_ref() {
+                Some(account_id) => &record.account_id == account_id,
+                None => true,
+            })
+            .take(limit.unwrap_or(BENEFICIARY_PAYOUT_HISTORY_SIZE as usize))
+            .cloned()
+            .collect()
+    }
+}
@@ -0,0 +1,354 @@
+use crate::*;
+
+/// Max gas that can be attached to a transaction
+const MAX_TX_GAS: Gas = Gas::from_tgas(300);
+const MIN_CONFIG_GAS: Gas = Gas::from_tgas(1);
+/// The number of epochs required for the locked balance to become unlocked.
+/// NOTE: The actual number of epochs when the funds are unlocked is 3. But there is a corner case
+/// when the unstaking promise can arrive at the next epoch, while the inner state is already
+/// updated in the previous epoch. It will not unlock the funds for 4 epochs.
+const MIN_NUM_EPOCHS_TO_UNLOCK: EpochHeight = 4;
+const MAX_NUM_EPOCHS_TO_UNLOCK: EpochHeight = 16;
+const MAX_STAKE_AMOUNT_CONFIG: u128 = 100 * ONE_NEAR;
+const MAX_SYNC_BALANCE_DIFF_LIMIT: u128 = 1_000_000;
+const MIN_RESERVE_BALANCE_LIMIT: u128 = ONE_NEAR / 10;
+const MAX_RESERVE_BALANCE_LIMIT: u128 = 100 * ONE_NEAR;
+const MAX_BENEFICIARIES_LIMIT: u32 = 20;
+const MIN_EXECUTION_TIMEOUT_BLOCKS: BlockHeight = 100;
+const MAX_EXECUTION_TIMEOUT_BLOCKS: BlockHeight = 100_000;
+
+/// Tunable parameters of the contract
+#[near(serializers = [borsh, json])]
+#[derive(Clone, Copy, PartialEq)]
+pub struct Config {
+    /// Epochs before NEAR unstaked from a validator can be withdrawn
+    pub num_epochs_to_unlock: EpochHeight,
+    /// Epoch stake is skipped below this amount
+    pub min_amount_to_perform_stake: U128,
+    /// Max diff in yocto NEAR tolerated when syncing validator balances
+    pub max_sync_balance_diff: U128,
+    /// Stake remainder below this amount goes to the same validator
+    pub stake_small_change_amount: U128,
+    /// Min NEAR balance this contract should hold in order to cover storage
+    pub contract_min_reserve_balance: U128,
+    pub max_beneficiaries: u32,
+    /// Max share of total LST supply that can be directed to a single validator
+    pub max_validator_directed_stake_bps: u32,
+    /// Number of blocks after which an unfinished validator execution is considered stuck
+    pub execution_timeout_blocks: BlockHeight,
+    pub gas: GasConfig,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            num_epochs_to_unlock: MIN_NUM_EPOCHS_TO_UNLOCK,
+            min_amount_to_perform_stake: U128(ONE_NEAR),
+            max_sync_balance_diff: U128(100),
+            stake_small_change_amount: U128(ONE_NEAR),
+            contract_min_reserve_balance: U128(ONE_NEAR),
+            max_beneficiaries: 10,
+            max_validator_directed_stake_bps: 1_000,
+            execution_timeout_blocks: 1_000,
+            gas: GasConfig::default(),
+        }
+    }
+}
+
+/// Gas of epoch actions, cross contract calls and their callbacks
+#[near(serializers = [borsh, json])]
+#[derive(Clone, Copy, PartialEq)]
+pub struct GasConfig {
+    pub epoch_stake: Gas,
+    pub epoch_unstake: Gas,
+    pub epoch_update_rewards: Gas,
+    pub epoch_withdraw: Gas,
+    pub sync_balance: Gas,
+    pub drain_unstake: Gas,
+    pub drain_withdraw: Gas,
+    pub redelegate: Gas,
+    /// gas reserved for the epoch crank itself on each step
+    pub epoch_run_step: Gas,
+    pub ext_deposit_and_stake: Gas,
+    pub ext_unstake: Gas,
+    pub ext_get_balance: Gas,
+    pub ext_get_account: Gas,
+    pub ext_withdraw: Gas,
+    pub ext_whitelist: Gas,
+    pub cb_validator_sync_balance: Gas,
+    pub cb_validator_staked: Gas,
+    pub cb_validator_unstaked: Gas,
+    pub cb_validator_get_balance: Gas,
+    pub cb_validator_withdraw: Gas,
+    pub cb_whitelist: Gas,
+}
+
+impl Default for GasConfig {
+    fn default() -> Self {
+        Self {
+            epoch_stake: Gas::from_tgas(75),
+            epoch_unstake: Gas::from_tgas(75),
+            epoch_update_rewards: Gas::from_tgas(75),
+            epoch_withdraw: Gas::from_tgas(75),
+            sync_balance: Gas::from_tgas(75),
+            drain_unstake: Gas::from_tgas(75),
+            drain_withdraw: Gas::from_tgas(75),
+            redelegate: Gas::from_tgas(75),
+            epoch_run_step: Gas::from_tgas(10),
+            ext_deposit_and_stake: Gas::from_tgas(75),
+            ext_unstake: Gas::from_tgas(75),
+            ext_get_balance: Gas::from_tgas(25),
+            ext_get_account: Gas::from_tgas(25),
+            ext_withdraw: Gas::from_tgas(75),
+            ext_whitelist: Gas::from_tgas(10),
+            cb_validator_sync_balance: Gas::from_tgas(25),
+            cb_validator_staked: Gas::from_tgas(25),
+            cb_validator_unstaked: Gas::from_tgas(25),
+            cb_validator_get_balance: Gas::from_tgas(25),
+            cb_validator_withdraw: Gas::from_tgas(25),
+            cb_whitelist: Gas::from_tgas(15),
+        }
+    }
+}
+
+impl GasConfig {
+    /// Callback of a validator stake, which also syncs the validator balance
+    pub fn staked_callback(&self) -> Gas {
+        Gas::from_gas(
+            self.cb_validator_staked.as_gas()
+                + self.sync_balance.as_gas()
+                + self.cb_validator_sync_balance.as_gas(),
+        )
+    }
+
+    /// Callback of a validator unstake, which also syncs the validator balance
+    pub fn unstaked_callback(&self) -> Gas {
+        Gas::from_gas(
+            self.cb_validator_unstaked.as_gas()
+                + self.sync_balance.as_gas()
+                + self.cb_validator_sync_balance.as_gas(),
+        )
+    }
+
+    /// Validator stake with its callbacks
+    pub fn stake(&self) -> u64 {
+        self.ext_deposit_and_stake.as_gas() + self.staked_callback().as_gas()
+    }
+
+    /// Validator unstake with its callbacks
+    pub fn unstake(&self) -> u64 {
+        self.ext_unstake.as_gas() + self.unstaked_callback().as_gas()
+    }
+
+    /// Validator balance refresh with its callback
+    pub fn update_rewards(&self) -> u64 {
+        self.ext_get_balance.as_gas() + self.cb_validator_get_balance.as_gas()
+    }
+
+    /// Validator withdraw with its callback
+    pub fn withdraw(&self) -> u64 {
+        self.ext_withdraw.as_gas() + self.cb_validator_withdraw.as_gas()
+    }
+
+    /// Validator account sync with its callback
+    pub fn sync_account_balance(&self) -> u64 {
+        self.ext_get_account.as_gas() + self.cb_validator_sync_balance.as_gas()
+    }
+
+    fn assert_valid(&self) {
+        for (name, gas) in [
+            ("epoch_stake", self.epoch_stake),
+            ("epoch_unstake", self.epoch_unstake),
+            ("epoch_update_rewards", self.epoch_update_rewards),
+            ("epoch_withdraw", self.epoch_withdraw),
+            ("sync_balance", self.sync_balance),
+            ("drain_unstake", self.drain_unstake),
+            ("drain_withdraw", self.drain_withdraw),
+            ("redelegate", self.redelegate),
+            ("epoch_run_step", self.epoch_run_step),
+            ("ext_deposit_and_stake", self.ext_deposit_and_stake),
+            ("ext_unstake", self.ext_unstake),
+            ("ext_get_balance", self.ext_get_balance),
+            ("ext_get_account", self.ext_get_account),
+            ("ext_withdraw", self.ext_withdraw),
+            ("ext_whitelist", self.ext_whitelist),
+            ("cb_validator_sync_balance", self.cb_validator_sync_balance),
+            ("cb_validator_staked", self.cb_validator_staked),
+            ("cb_validator_unstaked", self.cb_validator_unstaked),
+            ("cb_validator_get_balance", self.cb_validator_get_balance),
+            ("cb_validator_withdraw", self.cb_validator_withdraw),
+            ("cb_whitelist", self.cb_whitelist),
+        ] {
+            assert_config_bounds(
+                name,
+                gas.as_gas() as u128,
+                MIN_CONFIG_GAS.as_gas() as u128,
+                MAX_TX_GAS.as_gas() as u128,
+            );
+        }
+
+        // every action with its cross contract calls must fit in a transaction
+        let step = self.epoch_run_step.as_gas();
+        for (name, gas) in [
+            ("epoch_stake", self.epoch_stake.as_gas() + self.stake()),
+            (
+                "epoch_unstake",
+                self.epoch_unstake.as_gas() + self.unstake(),
+            ),
+            (
+                "epoch_update_rewards",
+                self.epoch_update_rewards.as_gas() + self.update_rewards(),
+            ),
+            (
+                "epoch_withdraw",
+                self.epoch_withdraw.as_gas() + self.withdraw(),
+            ),
+            (
+                "epoch_run",
+                step + self.stake().max(self.unstake()).max(self.withdraw()),
+            ),
+            (
+                "sync_balance",
+                self.sync_balance.as_gas() + self.sync_account_balance(),
+            ),
+            (
+                "drain_unstake",
+                self.drain_unstake.as_gas() + self.unstake() + step,
+            ),
+            (
+                "drain_withdraw",
+                self.drain_withdraw.as_gas() + self.withdraw() + step,
+            ),
+            (
+                "redelegate",
+                self.redelegate.as_gas() + self.stake().max(self.unstake()),
+            ),
+            (
+                "whitelist",
+                self.ext_whitelist.as_gas() + self.cb_whitelist.as_gas(),
+            ),
+        ] {
+            assert_config_bounds(name, gas as u128, 0, MAX_TX_GAS.as_gas() as u128);
+        }
+    }
+}
+
+impl Config {
+    pub fn assert_valid(&self, num_beneficiaries: u32) {
+        assert_config_bounds(
+            "num_epochs_to_unlock",
+            self.num_epochs_to_unlock as u128,
+            MIN_NUM_EPOCHS_TO_UNLOCK as u128,
+            MAX_NUM_EPOCHS_TO_UNLOCK as u128,
+        );
+        assert_config_bounds(
+            "min_amount_to_perform_stake",
+            self.min_amount_to_perform_stake.0,
+            1,
+            MAX_STAKE_AMOUNT_CONFIG,
+        );
+        assert_config_bounds(
+            "max_sync_balance_diff",
+            self.max_sync_balance_diff.0,
+            0,
+            MAX_SYNC_BALANCE_DIFF_LIMIT,
+        );
+        assert_config_bounds(
+            "stake_small_change_amount",
+            self.stake_small_change_amount.0,
+            0,
+            MAX_STAKE_AMOUNT_CONFIG,
+        );
+        assert_config_bounds(
+            "contract_min_reserve_balance",
+            self.contract_min_reserve_balance.0,
+            MIN_RESERVE_BALANCE_LIMIT,
+            MAX_RESERVE_BALANCE_LIMIT,
+        );
+        // existing beneficiaries must stay within the limit
+        assert_config_bounds(
+            "max_beneficiaries",
+            self.max_beneficiaries as u128,
+            num_beneficiaries.max(1) as u128,
+            MAX_BENEFICIARIES_LIMIT as u128,
+        );
+        assert_config_bounds(
+            "max_validator_directed_stake_bps",
+            self.max_validator_directed_stake_bps as u128,
+            0,
+            FULL_BASIS_POINTS as u128,
+        );
+        assert_config_bounds(
+            "execution_timeout_blocks",
+            self.execution_timeout_blocks as u128,
+            MIN_EXECUTION_TIMEOUT_BLOCKS as u128,
+            MAX_EXECUTION_TIMEOUT_BLOCKS as u128,
+        );
+        self.gas.assert_valid();
+    }
+
+    pub fn contract_min_reserve_balance(&self) -> NearToken {
+        NearToken::from_yoctonear(self.contract_min_reserve_balance.0)
+    }
+}
+
+fn assert_config_bounds(name: &str, value: u128, min_value: u128, max_value: u128) {
+    if value < min_value || value > max_value {
+        env::panic_str(&format!(
+            "{}: {} must be within [{}, {}]",
+            ERR_INVALID_CONFIG, name, min_value, max_value
+        ));
+    }
+}
+
+/// Config in state, new versions are added as the config grows
+#[near(serializers = [borsh])]
+pub enum VersionedConfig {
+    V1(Config),
+}
+
+impl Contract {
+    #[allow(unreachable_patterns)]
+    pub(crate) fn internal_config(&self) -> &Config {
+        match &self.data().config {
+            VersionedConfig::V1(config) => config,
+            _ => unimplemented!(),
+        }
+    }
+
+    fn internal_set_config(&mut self, config: Config) {
+        config.assert_valid(self.data().beneficiaries.len());
+        Event::ConfigUpdated {
+            old_config: self.internal_config(),
+            new_config: &config,
+        }
+        .emit();
+        self.data_mut().config = VersionedConfig::V1(config);
+    }
+}
+
+#[near]
+impl Contract {
+    #[payable]
+    #[access_control_any(roles(Role::DAO))]
+    #[pause]
+    pub fn set_config(&mut self, config: Config) {
+        assert_one_yocto();
+        self.internal_set_config(config);
+    }
+
+    #[payable]
+    #[access_control_any(roles(Role::DAO))]
+    #[pause]
+    pub fn set_gas_config(&mut self, gas: GasConfig) {
+        assert_one_yocto();
+        self.internal_set_config(Config {
+            gas,
+            ..*self.internal_config()
+        });
+    }
+
+    pub fn get_config(&self) -> Config {
+        *self.internal_config()
+    }
+}
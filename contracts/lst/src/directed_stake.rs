@@ -0,0 +1,188 @@
+use crate::*;
+
+/// The validator an account chose to stake on, and the amount of
+/// its LST shares currently attributed to that validator.
+#[near(serializers = [borsh])]
+#[derive(Clone)]
+pub struct DirectedStake {
+    pub validator_id: AccountId,
+    pub shares: u128,
+}
+
+#[near(serializers = [json])]
+pub struct DirectedStakeView {
+    pub validator_id: AccountId,
+    pub directed_shares: U128,
+    pub directed_stake_amount: U128,
+}
+
+impl Contract {
+    /// Set or clear the validator preference of the account, and
+    /// attribute its current LST balance to the new validator.
+    pub(crate) fn internal_set_validator_preference(
+        &mut self,
+        account_id: &AccountId,
+        validator_id: Option<AccountId>,
+    ) {
+        if let Some(validator_id) = validator_id.as_ref() {
+            let validator = self
+                .data()
+                .validator_pool
+                .get_validator(validator_id)
+                .expect(ERR_VALIDATOR_NOT_EXIST);
+            require!(validator.weight > 0, ERR_VALIDATOR_NO_WEIGHT);
+        }
+
+        // release shares attributed to the previous validator
+        if let Some(directed_stake) = self.data_mut().directed_stakes.remove(account_id) {
+            self.internal_update_directed_shares(&directed_stake, 0);
+        }
+
+        let directed_shares = if let Some(validator_id) = validator_id.as_ref() {
+            self.data_mut().directed_stakes.insert(
+                account_id.clone(),
+                DirectedStake {
+                    validator_id: validator_id.clone(),
+                    shares: 0,
+                },
+            );
+            self.internal_sync_directed_stake(account_id)
+        } else {
+            0
+        };
+
+        Event::ValidatorPreferenceUpdated {
+            account_id,
+            validator_id: validator_id.as_ref(),
+            directed_shares: &U128(directed_shares),
+        }
+        .emit();
+    }
+
+    /// Should be called whenever the LST balance of an account changes.
+    pub(crate) fn internal_on_lst_balance_changed(&mut self, account_id: &AccountId) {
+        self.internal_sync_directed_stake(account_id);
+        self.internal_sync_referred_shares(account_id);
+    }
+
+    /// Attribute the LST balance of the account to its preferred validator,
+    /// up to the per validator cap. Shares above the cap, or shares of an
+    /// account whose validator left the pool, fall back to the pool weights.
+    /// Returns the directed shares of the account.
+    fn internal_sync_directed_stake(&mut self, account_id: &AccountId) -> u128 {
+        let Some(directed_stake) = self.data().directed_stakes.get(account_id).cloned() else {
+            return 0;
+        };
+
+        let Some(validator) = self
+            .data()
+            .validator_pool
+            .get_validator(&directed_stake.validator_id)
+        else {
+            // validator has been removed, drop the preference
+            self.data_mut().directed_stakes.remove(account_id);
+            Event::ValidatorPreferenceUpdated {
+                account_id,
+                validator_id: None,
+                directed_shares: &U128(0),
+            }
+            .emit();
+            return 0;
+        };
+
+        let balance = self.data().token.accounts.get(account_id).unwrap_or(0);
+        let new_shares = if balance <= directed_stake.shares {
+            balance
+        } else {
+            let max_directed_shares = bps_mul(
+                self.data().token.total_supply,
+                self.internal_config().max_validator_directed_stake_bps,
+            );
+            let room = max_directed_shares.saturating_sub(validator.directed_shares);
+            directed_stake.shares + min(balance - directed_stake.shares, room)
+        };
+
+        if new_shares != directed_stake.shares {
+            self.internal_update_directed_shares(&directed_stake, new_shares);
+            self.data_mut().directed_stakes.insert(
+                account_id.clone(),
+                DirectedStake {
+                    validator_id: directed_stake.validator_id,
+                    shares: new_shares,
+                },
+            );
+        }
+        new_shares
+    }
+
+    /// Charge the attached deposit for the storage taken since `initial_storage_usage`
+    /// and refund the rest. Released storage is not refunded, since preferences set
+    /// along with a deposit were never charged.
+    fn internal_charge_storage(&self, account_id: &AccountId, initial_storage_usage: StorageUsage) {
+        let attached_deposit = env::attached_deposit().as_yoctonear();
+        let storage_cost = env::storage_byte_cost()
+            .saturating_mul(env::storage_usage().saturating_sub(initial_storage_usage) as u128)
+            .as_yoctonear();
+        require!(
+            attached_deposit >= storage_cost,
+            format!(
+                "{}. required: {}",
+                ERR_INSUFFICIENT_STORAGE_DEPOSIT, storage_cost
+            )
+        );
+        let refund = attached_deposit - storage_cost;
+        if refund > 0 {
+            Promise::new(account_id.clone()).transfer(NearToken::from_yoctonear(refund));
+        }
+    }
+
+    fn internal_update_directed_shares(
+        &mut self,
+        directed_stake: &DirectedStake,
+        new_shares: u128,
+    ) {
+        if self
+            .data()
+            .validator_pool
+            .get_validator(&directed_stake.validator_id)
+            .is_some()
+        {
+            self.data_mut().validator_pool.update_directed_shares(
+                &directed_stake.validator_id,
+                directed_stake.shares,
+                new_shares,
+            );
+        }
+    }
+}
+
+#[near]
+impl Contract {
+    /// Choose a validator to stake on for the LST held by the predecessor.
+    /// Pass `None` to fall back to the pool weights.
+    /// The attached deposit pays for the storage of the preference, the rest is refunded.
+    #[payable]
+    pub fn set_validator_preference(&mut self, validator_id: Option<AccountId>) {
+        let account_id = env::predecessor_account_id();
+        require!(
+            self.data().token.accounts.get(&account_id).is_some(),
+            ERR_ACCOUNT_NOT_REGISTERED
+        );
+        let initial_storage_usage = env::storage_usage();
+        self.internal_set_validator_preference(&account_id, validator_id);
+        self.internal_charge_storage(&account_id, initial_storage_usage);
+    }
+
+    pub fn get_validator_preference(&self, account_id: AccountId) -> Option<DirectedStakeView> {
+        self.data()
+            .directed_stakes
+            .get(&account_id)
+            .map(|directed_stake| DirectedStakeView {
+                validator_id: directed_stake.validator_id.clone(),
+                directed_shares: directed_stake.shares.into(),
+                directed_stake_amount: self
+                    .staked_amount_from_num_shares_rounded_down(directed_stake.shares)
+                    .into(),
+            })
+    }
+}
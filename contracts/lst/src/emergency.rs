@@ -0,0 +1,288 @@
+use crate::*;
+
+const DEFAULT_EMERGENCY_UNWIND_MAX_STEPS: u32 = 10;
+
+/// Wind-down state of the protocol. Once enabled, all stake is drained
+/// from validators and LST holders are paid out of the unwound NEAR.
+#[near(serializers = [borsh])]
+pub struct EmergencyState {
+    pub enabled_epoch: EpochHeight,
+    /// NEAR set aside for LST holders, withdrawn from validators or not staked yet
+    pub unwound_amount: u128,
+    /// Unwound NEAR not paid out yet
+    pub available_amount: u128,
+    pub paid_amount: u128,
+}
+
+#[near(serializers = [json])]
+pub struct EmergencyStatus {
+    pub enabled: bool,
+    pub enabled_epoch: Option<EpochHeight>,
+    pub num_validators: u32,
+    /// Validators with stake left to unstake
+    pub num_validators_staked: u32,
+    /// Validators waiting for their unstaked NEAR to be withdrawn
+    pub num_validators_draining: u32,
+    pub num_validators_drained: u32,
+    /// NEAR still staked on validators
+    pub staked_amount: U128,
+    /// NEAR unstaked on validators and not withdrawn yet
+    pub unstaked_amount: U128,
+    pub unwound_amount: U128,
+    pub available_amount: U128,
+    pub paid_amount: U128,
+    /// LST left to be paid out
+    pub total_supply: U128,
+    /// Validators that `emergency_unwind` can drain now
+    pub validators_to_unstake: Vec<AccountId>,
+    /// Validators that `emergency_unwind` can withdraw from now
+    pub validators_to_withdraw: Vec<AccountId>,
+}
+
+#[near(serializers = [json])]
+pub struct EmergencyUnwindResult {
+    /// Drain actions started by this call
+    pub executed_actions: Vec<EpochActionView>,
+    pub status: EmergencyStatus,
+}
+
+fn can_drain_unstake(validator: &Validator, config: &Config) -> bool {
+    validator.staked_amount > 0
+        && !validator.draining
+        && !validator.is_executing()
+        && !validator.pending_release(config)
+        // unstaked balance from user unstake requests is withdrawn by epoch actions first
+        && validator.unstaked_amount < ONE_NEAR
+}
+
+fn can_drain_withdraw(validator: &Validator, config: &Config) -> bool {
+    validator.draining
+        && validator.staked_amount == 0
+        && !validator.is_executing()
+        && !validator.pending_release(config)
+}
+
+impl Contract {
+    pub(crate) fn is_emergency_mode(&self) -> bool {
+        self.data().emergency.is_some()
+    }
+
+    pub(crate) fn assert_not_emergency_mode(&self) {
+        require!(!self.is_emergency_mode(), ERR_EMERGENCY_MODE);
+    }
+
+    /// Set aside NEAR withdrawn from a drained validator, together with deposits
+    /// that were not staked yet, to pay out LST holders. Unstake requests waiting
+    /// to settle are funded first, since their NEAR was drained as well.
+    pub(crate) fn internal_emergency_settle(&mut self, withdrawn_amount: u128) {
+        self.epoch_cleanup();
+        let funds = withdrawn_amount + self.data().stake_amount_to_settle;
+        let reserved_amount = min(funds, self.data().unstake_amount_to_settle);
+        self.data_mut().stake_amount_to_settle = 0;
+        self.data_mut().unstake_amount_to_settle -= reserved_amount;
+        self.internal_credit_withdrawal_reserve(reserved_amount);
+
+        let emergency = self
+            .data_mut()
+            .emergency
+            .as_mut()
+            .expect(ERR_NOT_IN_EMERGENCY_MODE);
+        emergency.unwound_amount += funds - reserved_amount;
+        emergency.available_amount += funds - reserved_amount;
+        let available_amount = emergency.available_amount;
+
+        if funds > 0 {
+            Event::EmergencyFundsSettled {
+                withdrawn_amount: &U128(withdrawn_amount),
+                reserved_amount: &U128(reserved_amount),
+                available_amount: &U128(available_amount),
+            }
+            .emit();
+        }
+    }
+}
+
+#[near]
+impl Contract {
+    /// Start an orderly wind-down. Deposits, stakes and unstakes are blocked,
+    /// all weights and base stake amounts are set to 0, and the stake on
+    /// validators is drained by `emergency_unwind`. LST holders exit through
+    /// `emergency_withdraw`. It can't be turned off.
+    #[payable]
+    #[access_control_any(roles(Role::DAO))]
+    pub fn enable_emergency_mode(&mut self) {
+        assert_one_yocto();
+        self.assert_not_emergency_mode();
+
+        let validators = self.data().validator_pool.get_validators(None, None);
+        let mut account_ids = Vec::new();
+        let mut old_weights = Vec::new();
+        let mut new_weights = Vec::new();
+        for validator in validators.iter() {
+            let old_weight = self
+                .data_mut()
+                .validator_pool
+                .update_weight(&validator.account_id, 0);
+            if validator.base_stake_amount > 0 {
+                self.data_mut()
+                    .validator_pool
+                    .update_base_stake_amount(&validator.account_id, 0);
+            }
+            account_ids.push(&validator.account_id);
+            old_weights.push(old_weight);
+            new_weights.push(0);
+        }
+        Event::ValidatorsUpdatedWeights {
+            account_ids,
+            old_weights,
+            new_weights,
+        }
+        .emit();
+
+        self.data_mut().emergency = Some(EmergencyState {
+            enabled_epoch: get_epoch_height(),
+            unwound_amount: 0,
+            available_amount: 0,
+            paid_amount: 0,
+        });
+        Event::EmergencyModeEnabled {
+            account_id: &env::predecessor_account_id(),
+            num_validators: validators.len() as u32,
+            total_staked: &U128(self.data().total_staked_asset_in_near),
+            total_supply: &U128(self.data().token.total_supply),
+        }
+        .emit();
+
+        self.internal_emergency_settle(0);
+    }
+
+    /// Drain validators in emergency mode: unstake all stake and withdraw it once
+    /// released. Runs at most `max_steps` actions, as many as the prepaid gas allows.
+    /// Anyone can call it, even while other operations are paused, and the caller
+    /// is paid a keeper reward for each started action that succeeds.
+    pub fn emergency_unwind(&mut self, max_steps: Option<u32>) -> EmergencyUnwindResult {
+        require!(self.is_emergency_mode(), ERR_NOT_IN_EMERGENCY_MODE);
+        let max_steps = max_steps.unwrap_or(DEFAULT_EMERGENCY_UNWIND_MAX_STEPS) as usize;
+        let config = *self.internal_config();
+        let gas_to_unstake = config.gas.unstake();
+        let gas_to_withdraw = config.gas.withdraw();
+        let has_gas = |gas: u64| {
+            env::prepaid_gas()
+                .as_gas()
+                .saturating_sub(env::used_gas().as_gas())
+                >= gas + config.gas.epoch_run_step.as_gas()
+        };
+
+        // deposits of the current epoch are set aside once it ends
+        self.internal_emergency_settle(0);
+
+        let keeper_id = env::predecessor_account_id();
+        let mut executed_actions = vec![];
+        for validator in self.data().validator_pool.get_validators(None, None) {
+            if executed_actions.len() >= max_steps {
+                break;
+            }
+            if can_drain_unstake(&validator, &config) {
+                if has_gas(gas_to_unstake) {
+                    executed_actions.push(EpochActionView::Unstake {
+                        validator_id: validator.account_id.clone(),
+                        amount: validator.staked_amount.into(),
+                    });
+                    self.internal_drain_unstake(&validator.account_id, Some(keeper_id.clone()));
+                }
+            } else if can_drain_withdraw(&validator, &config) && has_gas(gas_to_withdraw) {
+                executed_actions.push(EpochActionView::Withdraw {
+                    validator_id: validator.account_id.clone(),
+                    amount: validator.unstaked_amount.into(),
+                });
+                self.internal_drain_withdraw(&validator.account_id, Some(keeper_id.clone()));
+            }
+        }
+
+        EmergencyUnwindResult {
+            executed_actions,
+            status: self.get_emergency_status(),
+        }
+    }
+
+    /// Burn LST for its NEAR value out of the unwound funds, all LST of the
+    /// caller by default. Works while other operations are paused.
+    pub fn emergency_withdraw(&mut self, shares: Option<U128>) -> U128 {
+        require!(self.is_emergency_mode(), ERR_NOT_IN_EMERGENCY_MODE);
+        let account_id = env::predecessor_account_id();
+        let shares = shares
+            .map(|shares| shares.0)
+            .unwrap_or_else(|| self.data().token.accounts.get(&account_id).unwrap_or(0));
+        require!(shares > 0, ERR_NON_POSITIVE_SHARES);
+
+        let amount = self.staked_amount_from_num_shares_rounded_down(shares);
+        require!(amount > 0, ERR_NON_POSITIVE_WITHDRAWAL_AMOUNT);
+        let emergency = self.data().emergency.as_ref().unwrap();
+        require!(
+            amount <= emergency.available_amount,
+            ERR_EMERGENCY_FUNDS_NOT_AVAILABLE
+        );
+
+        self.burn_lst(&account_id, shares, Some("emergency withdraw"));
+        self.data_mut().total_staked_asset_in_near -= amount;
+        let emergency = self.data_mut().emergency.as_mut().unwrap();
+        emergency.available_amount -= amount;
+        emergency.paid_amount += amount;
+
+        Event::EmergencyWithdraw {
+            account_id: &account_id,
+            burnt_shares: &U128(shares),
+            amount: &U128(amount),
+        }
+        .emit();
+        Promise::new(account_id).transfer(NearToken::from_yoctonear(amount));
+        amount.into()
+    }
+
+    pub fn get_emergency_status(&self) -> EmergencyStatus {
+        let validators = self.data().validator_pool.get_validators(None, None);
+        let emergency = self.data().emergency.as_ref();
+        let config = self.internal_config();
+        EmergencyStatus {
+            enabled: emergency.is_some(),
+            enabled_epoch: emergency.map(|emergency| emergency.enabled_epoch),
+            num_validators: validators.len() as u32,
+            num_validators_staked: validators.iter().filter(|v| v.staked_amount > 0).count() as u32,
+            num_validators_draining: validators.iter().filter(|v| v.draining).count() as u32,
+            num_validators_drained: validators
+                .iter()
+                .filter(|v| v.staked_amount == 0 && !v.draining)
+                .count() as u32,
+            staked_amount: validators
+                .iter()
+                .map(|v| v.staked_amount)
+                .sum::<u128>()
+                .into(),
+            unstaked_amount: validators
+                .iter()
+                .map(|v| v.unstaked_amount)
+                .sum::<u128>()
+                .into(),
+            unwound_amount: emergency
+                .map_or(0, |emergency| emergency.unwound_amount)
+                .into(),
+            available_amount: emergency
+                .map_or(0, |emergency| emergency.available_amount)
+                .into(),
+            paid_amount: emergency
+                .map_or(0, |emergency| emergency.paid_amount)
+                .into(),
+            total_supply: self.data().token.total_supply.into(),
+            validators_to_unstake: validators
+                .iter()
+                .filter(|v| emergency.is_some() && can_drain_unstake(v, config))
+                .map(|v| v.account_id.clone())
+                .collect(),
+            validators_to_withdraw: validators
+                .iter()
+                .filter(|v| emergency.is_some() && can_drain_withdraw(v, config))
+                .map(|v| v.account_id.clone())
+                .collect(),
+        }
+    }
+}
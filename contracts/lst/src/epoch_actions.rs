@@ -1,31 +1,113 @@
 use crate::*;
 
-const MIN_AMOUNT_TO_PERFORM_STAKE: u128 = NearToken::from_near(1).as_yoctonear();
-const MAX_SYNC_BALANCE_DIFF: u128 = 100;
+const DEFAULT_EPOCH_RUN_MAX_STEPS: u32 = 10;
+
+/// An action started by the epoch crank
+#[near(serializers = [json])]
+pub enum EpochActionView {
+    Stake {
+        validator_id: AccountId,
+        amount: U128,
+    },
+    Unstake {
+        validator_id: AccountId,
+        amount: U128,
+    },
+    UpdateRewards {
+        validator_id: AccountId,
+    },
+    Withdraw {
+        validator_id: AccountId,
+        amount: U128,
+    },
+}
+
+/// What an epoch stake or unstake call would do if sent now
+#[near(serializers = [json])]
+pub struct EpochActionPreview {
+    /// The validator that would be selected
+    pub validator_id: Option<AccountId>,
+    pub amount: U128,
+    /// Stake amount left to settle after the action
+    pub stake_amount_to_settle: U128,
+    /// Unstake amount left to settle after the action
+    pub unstake_amount_to_settle: U128,
+    /// Why nothing would happen, if so
+    pub reason: Option<String>,
+}
+
+#[near(serializers = [json])]
+pub struct RewardsPreview {
+    pub validator_id: AccountId,
+    /// Last known total balance on the validator
+    pub total_balance: U128,
+    pub staked_amount: U128,
+    pub unstaked_amount: U128,
+    /// The epoch when rewards were last refreshed
+    pub rewards_updated_epoch: EpochHeight,
+    pub current_epoch: EpochHeight,
+    /// Whether `epoch_update_rewards` should be called in this epoch
+    pub need_rewards_update: bool,
+    pub executing: Option<ExecutionInfo>,
+}
+
+#[near(serializers = [json])]
+pub struct EpochRunResult {
+    /// Actions started by this call
+    pub executed_actions: Vec<EpochActionView>,
+    /// Stake amount left to settle
+    pub stake_amount_to_settle: U128,
+    /// Unstake amount left to settle
+    pub unstake_amount_to_settle: U128,
+    /// Validators whose rewards are not refreshed in the current epoch yet
+    pub validators_to_update_rewards: Vec<AccountId>,
+    /// Validators with matured unstaked balance to withdraw
+    pub validators_to_withdraw: Vec<AccountId>,
+}
+
+/// Why an epoch stake or unstake would not run
+enum EpochPlanError {
+    /// There is nothing to do in this epoch
+    Skip(String),
+    /// The action would break a contract invariant
+    Abort(&'static str),
+}
+
+impl EpochPlanError {
+    fn reason(self) -> String {
+        match self {
+            EpochPlanError::Skip(reason) => reason,
+            EpochPlanError::Abort(err) => err.to_string(),
+        }
+    }
+}
 
 impl Contract {
     /// Cleaning up stake requirements and unstake requirements,
     /// since some stake requirements could be eliminated if
     /// there are more unstake requirements, and vice versa.
-    fn epoch_cleanup(&mut self) {
+    pub(crate) fn epoch_cleanup(&mut self) {
         if self.data().last_settlement_epoch == get_epoch_height() {
             return;
         }
+        self.internal_release_deferred_unstakes();
+        let (stake_amount_to_settle, unstake_amount_to_settle, netted_amount) =
+            self.epoch_cleanup_amounts();
         self.data_mut().last_settlement_epoch = get_epoch_height();
+        // all released deferred unstakes are settled from here on
+        self.data_mut().flow_limits.settled_released_amount =
+            self.data().flow_limits.total_released_amount;
 
-        // here we use += because cleanup amount might not be 0
-        self.data_mut().stake_amount_to_settle += self.data().epoch_requested_stake_amount;
-        self.data_mut().unstake_amount_to_settle += self.data().epoch_requested_unstake_amount;
+        self.data_mut().stake_amount_to_settle = stake_amount_to_settle;
+        self.data_mut().unstake_amount_to_settle = unstake_amount_to_settle;
         self.data_mut().epoch_requested_stake_amount = 0;
         self.data_mut().epoch_requested_unstake_amount = 0;
 
-        if self.data().stake_amount_to_settle > self.data().unstake_amount_to_settle {
-            self.data_mut().stake_amount_to_settle -= self.data().unstake_amount_to_settle;
-            self.data_mut().unstake_amount_to_settle = 0;
-        } else {
-            self.data_mut().unstake_amount_to_settle -= self.data().stake_amount_to_settle;
-            self.data_mut().stake_amount_to_settle = 0;
-        }
+        // the netted amount stays in the contract to fund withdrawals
+        self.internal_credit_withdrawal_reserve(netted_amount);
+
+        self.internal_prune_unstake_queue();
+        self.internal_record_price_sample();
 
         Event::EpochCleanup {
             stake_amount_to_settle: &U128(self.data().stake_amount_to_settle),
@@ -33,64 +115,56 @@ impl Contract {
         }
         .emit();
     }
+
+    /// Returns the stake and unstake amounts to settle, and the netted amount,
+    /// as they would be after the cleanup of the current epoch.
+    fn epoch_cleanup_amounts(&self) -> (u128, u128, u128) {
+        if self.data().last_settlement_epoch == get_epoch_height() {
+            return (
+                self.data().stake_amount_to_settle,
+                self.data().unstake_amount_to_settle,
+                0,
+            );
+        }
+
+        // cleanup amount might not be 0
+        let stake_amount_to_settle =
+            self.data().stake_amount_to_settle + self.data().epoch_requested_stake_amount;
+        let unstake_amount_to_settle = self.data().unstake_amount_to_settle
+            + self.data().epoch_requested_unstake_amount
+            + self.data().flow_limits.releasable_deferred_unstake_amount();
+        let netted_amount = min(stake_amount_to_settle, unstake_amount_to_settle);
+        (
+            stake_amount_to_settle - netted_amount,
+            unstake_amount_to_settle - netted_amount,
+            netted_amount,
+        )
+    }
 }
 
-#[near]
 impl Contract {
-    /// Stake $NEAR to one of the validators.
-    ///
-    /// Select a candidate validator and stake part of or all of the to-settle
-    /// stake amounts to this validator. This function is expected to be called
-    /// in each epoch.
-    ///
-    /// # Return
-    /// * `true` - a candidate validator is selected and successfully staked to.
-    ///            There might be more stake amounts to settle so this function
-    ///            should be called again.
-    /// * `false` - There is no need to call this function again in this epoch.
-    #[pause]
-    pub fn epoch_stake(&mut self) -> PromiseOrValue<bool> {
-        // make sure enough gas was given
-        let min_gas = GAS_EPOCH_STAKE.as_gas()
-            + GAS_EXT_DEPOSIT_AND_STAKE.as_gas()
-            + GAS_CB_VALIDATOR_STAKED.as_gas()
-            + GAS_SYNC_BALANCE.as_gas()
-            + GAS_CB_VALIDATOR_SYNC_BALANCE.as_gas();
-        require!(
-            env::prepaid_gas().as_gas() >= min_gas,
-            format!("{}. require at least {:?}", ERR_NO_ENOUGH_GAS, min_gas)
-        );
-
+    /// Stake part of or all of the to-settle stake amounts to a candidate validator.
+    /// Returns `None` if there is nothing to stake in this epoch,
+    /// or the error if the stake would break a contract invariant.
+    /// The keeper, if any, is rewarded once the stake succeeds.
+    fn internal_epoch_stake(
+        &mut self,
+        keeper_id: Option<AccountId>,
+    ) -> Result<Option<(EpochActionView, Promise)>, &'static str> {
         self.epoch_cleanup();
-        // after cleanup, there might be no need to stake
-        if self.data().stake_amount_to_settle == 0 {
-            log!("no need to stake, amount to settle is zero");
-            return PromiseOrValue::Value(false);
-        }
-
-        let candidate = self.data().validator_pool.get_candidate_to_stake(
+        let mut candidate = match self.plan_epoch_stake(
             self.data().stake_amount_to_settle,
-            self.data().total_staked_asset_in_near,
-        );
-
-        if candidate.is_none() {
-            log!("no candidate found to stake");
-            return PromiseOrValue::Value(false);
-        }
-
-        let mut candidate = candidate.unwrap();
+            self.data().withdrawal_reserve,
+        ) {
+            Ok(candidate) => candidate,
+            Err(EpochPlanError::Skip(reason)) => {
+                log!("{}", reason);
+                return Ok(None);
+            }
+            Err(EpochPlanError::Abort(err)) => return Err(err),
+        };
         let amount_to_stake = candidate.amount;
-
-        if amount_to_stake < MIN_AMOUNT_TO_PERFORM_STAKE {
-            log!("stake amount too low: {}", amount_to_stake);
-            return PromiseOrValue::Value(false);
-        }
-
-        require!(
-            env::account_balance().as_yoctonear()
-                >= amount_to_stake + CONTRACT_MIN_RESERVE_BALANCE.as_yoctonear(),
-            ERR_MIN_RESERVE
-        );
+        let config = *self.internal_config();
 
         // update internal state
         self.data_mut().stake_amount_to_settle -= amount_to_stake;
@@ -102,67 +176,48 @@ impl Contract {
         .emit();
 
         // do staking on selected validator
-        candidate
+        let promise = candidate
             .validator
-            .deposit_and_stake(&mut self.data_mut().validator_pool, amount_to_stake)
+            .deposit_and_stake(
+                &mut self.data_mut().validator_pool,
+                amount_to_stake,
+                &config,
+            )
             .then(
                 Self::ext(env::current_account_id())
-                    .with_static_gas(
-                        GAS_CB_VALIDATOR_STAKED
-                            .checked_add(GAS_SYNC_BALANCE)
-                            .unwrap()
-                            .checked_add(GAS_CB_VALIDATOR_SYNC_BALANCE)
-                            .unwrap(),
-                    )
+                    .with_static_gas(config.gas.staked_callback())
                     .validator_staked_callback(
                         candidate.validator.account_id.clone(),
                         amount_to_stake.into(),
+                        keeper_id,
                     ),
-            )
-            .into()
+            );
+        Ok(Some((
+            EpochActionView::Stake {
+                validator_id: candidate.validator.account_id,
+                amount: amount_to_stake.into(),
+            },
+            promise,
+        )))
     }
 
-    /// Unstake $NEAR from one of the validators.
-    ///
-    /// Select a candidate validator and unstake part of or all of the to-settle
-    /// unstake amounts from this validator. This function is expected to be called
-    /// in each epoch.
-    ///
-    /// # Return
-    /// * `true` - a candidate validator is selected and successfully unstaked from.
-    ///            There might be more unstake amounts to settle so this function
-    ///            should be called again.
-    /// * `false` - There is no need to call this function again in this epoch.
-    #[pause]
-    pub fn epoch_unstake(&mut self) -> PromiseOrValue<bool> {
-        // make sure enough gas was given
-        let min_gas = GAS_EPOCH_UNSTAKE.as_gas()
-            + GAS_EXT_UNSTAKE.as_gas()
-            + GAS_CB_VALIDATOR_UNSTAKED.as_gas()
-            + GAS_SYNC_BALANCE.as_gas()
-            + GAS_CB_VALIDATOR_SYNC_BALANCE.as_gas();
-        require!(
-            env::prepaid_gas().as_gas() >= min_gas,
-            format!("{}. require at least {:?}", ERR_NO_ENOUGH_GAS, min_gas)
-        );
-
+    /// Unstake part of or all of the to-settle unstake amounts from a candidate validator.
+    /// Returns `None` if there is nothing to unstake in this epoch.
+    /// The keeper, if any, is rewarded once the unstake succeeds.
+    fn internal_epoch_unstake(
+        &mut self,
+        keeper_id: Option<AccountId>,
+    ) -> Option<(EpochActionView, Promise)> {
         self.epoch_cleanup();
-        // after cleanup, there might be no need to unstake
-        if self.data().unstake_amount_to_settle == 0 {
-            log!("no need to unstake, amount to settle is zero");
-            return PromiseOrValue::Value(false);
-        }
-
-        let candidate = self.data().validator_pool.get_candidate_to_unstake_v2(
-            self.data().unstake_amount_to_settle,
-            self.data().total_staked_asset_in_near,
-        );
-        if candidate.is_none() {
-            log!("no candidate found to unstake");
-            return PromiseOrValue::Value(false);
-        }
-        let mut candidate = candidate.unwrap();
+        let mut candidate = match self.plan_epoch_unstake(self.data().unstake_amount_to_settle) {
+            Ok(candidate) => candidate,
+            Err(err) => {
+                log!("{}", err.reason());
+                return None;
+            }
+        };
         let amount_to_unstake = candidate.amount;
+        let config = *self.internal_config();
 
         // Since it's reasonable to unstake any amount of NEAR from a validator, as low as 1 yocto NEAR,
         // when its target stake amount is 0, here we don't enforce the minimun unstake amount requirement.
@@ -177,85 +232,444 @@ impl Contract {
         .emit();
 
         // do unstaking on selected validator
-        candidate
+        let promise = candidate
             .validator
-            .unstake(&mut self.data_mut().validator_pool, amount_to_unstake)
+            .unstake(
+                &mut self.data_mut().validator_pool,
+                amount_to_unstake,
+                &config,
+            )
             .then(
                 Self::ext(env::current_account_id())
-                    .with_static_gas(
-                        GAS_CB_VALIDATOR_UNSTAKED
-                            .checked_add(GAS_SYNC_BALANCE)
-                            .unwrap()
-                            .checked_add(GAS_CB_VALIDATOR_SYNC_BALANCE)
-                            .unwrap(),
-                    )
+                    .with_static_gas(config.gas.unstaked_callback())
                     .validator_unstaked_callback(
-                        candidate.validator.account_id,
+                        candidate.validator.account_id.clone(),
                         amount_to_unstake.into(),
+                        keeper_id,
                     ),
+            );
+        Some((
+            EpochActionView::Unstake {
+                validator_id: candidate.validator.account_id,
+                amount: amount_to_unstake.into(),
+            },
+            promise,
+        ))
+    }
+
+    /// Pick the validator and amount of the next epoch stake, without any side effect.
+    /// Shared by `epoch_stake` and `preview_epoch_stake` so that they always agree.
+    fn plan_epoch_stake(
+        &self,
+        stake_amount_to_settle: u128,
+        withdrawal_reserve: u128,
+    ) -> Result<CandidateValidator, EpochPlanError> {
+        // nothing is staked again once the protocol winds down
+        if self.is_emergency_mode() {
+            return Err(EpochPlanError::Skip(
+                "no need to stake in emergency mode".to_string(),
+            ));
+        }
+        // after cleanup, there might be no need to stake
+        if stake_amount_to_settle == 0 {
+            return Err(EpochPlanError::Skip(
+                "no need to stake, amount to settle is zero".to_string(),
+            ));
+        }
+
+        let config = self.internal_config();
+        let candidate = self
+            .data()
+            .validator_pool
+            .get_candidate_to_stake(
+                stake_amount_to_settle,
+                self.data().total_staked_asset_in_near,
+                self.data().token.total_supply,
+                config,
+            )
+            .ok_or_else(|| EpochPlanError::Skip("no candidate found to stake".to_string()))?;
+
+        let amount_to_stake = candidate.amount;
+        if amount_to_stake < config.min_amount_to_perform_stake.0 {
+            return Err(EpochPlanError::Skip(format!(
+                "stake amount too low: {}",
+                amount_to_stake
+            )));
+        }
+
+        let balance = env::account_balance().as_yoctonear();
+        let min_reserve = config.contract_min_reserve_balance.0;
+        if balance < amount_to_stake + min_reserve {
+            return Err(EpochPlanError::Abort(ERR_MIN_RESERVE));
+        }
+        // never restake NEAR that matured users are waiting to withdraw
+        if balance
+            < amount_to_stake
+                + min_reserve
+                + withdrawal_reserve
+                + self.data().redelegation_in_transit_amount
+        {
+            return Err(EpochPlanError::Abort(ERR_WITHDRAWAL_RESERVE));
+        }
+        Ok(candidate)
+    }
+
+    /// Pick the validator and amount of the next epoch unstake, without any side effect.
+    /// Shared by `epoch_unstake` and `preview_epoch_unstake` so that they always agree.
+    fn plan_epoch_unstake(
+        &self,
+        unstake_amount_to_settle: u128,
+    ) -> Result<CandidateValidator, EpochPlanError> {
+        // after cleanup, there might be no need to unstake
+        if unstake_amount_to_settle == 0 {
+            return Err(EpochPlanError::Skip(
+                "no need to unstake, amount to settle is zero".to_string(),
+            ));
+        }
+        self.data()
+            .validator_pool
+            .get_candidate_to_unstake_v2(
+                unstake_amount_to_settle,
+                self.data().total_staked_asset_in_near,
+                self.data().token.total_supply,
+                self.internal_config(),
+            )
+            .ok_or_else(|| EpochPlanError::Skip("no candidate found to unstake".to_string()))
+    }
+
+    fn internal_epoch_update_rewards(
+        &mut self,
+        mut validator: Validator,
+        keeper_id: Option<AccountId>,
+    ) -> Promise {
+        let config = *self.internal_config();
+        validator
+            .refresh_total_balance(&mut self.data_mut().validator_pool, &config)
+            .then(
+                Self::ext(env::current_account_id())
+                    .with_static_gas(config.gas.cb_validator_get_balance)
+                    .validator_get_balance_callback(validator.account_id, keeper_id),
             )
-            .into()
+    }
+
+    fn internal_epoch_withdraw(
+        &mut self,
+        mut validator: Validator,
+        keeper_id: Option<AccountId>,
+    ) -> Promise {
+        require!(!validator.draining, ERR_DRAINING);
+
+        let amount = validator.withdrawable_amount();
+
+        Event::EpochWithdrawAttempt {
+            validator_id: &validator.account_id,
+            amount: &U128(amount),
+        }
+        .emit();
+
+        let config = *self.internal_config();
+        validator
+            .withdraw(&mut self.data_mut().validator_pool, amount, &config)
+            .then(
+                Self::ext(env::current_account_id())
+                    .with_static_gas(config.gas.cb_validator_withdraw)
+                    .validator_withdraw_callback(
+                        validator.account_id.clone(),
+                        amount.into(),
+                        keeper_id,
+                    ),
+            )
+    }
+
+    /// Plan the epoch work from state and run as much of it as the prepaid gas allows.
+    pub(crate) fn internal_epoch_run(
+        &mut self,
+        max_steps: u32,
+        keeper_id: Option<AccountId>,
+    ) -> EpochRunResult {
+        let config = *self.internal_config();
+        let gas_to_stake = config.gas.stake();
+        let gas_to_unstake = config.gas.unstake();
+        let gas_to_update_rewards = config.gas.update_rewards();
+        let gas_to_withdraw = config.gas.withdraw();
+        let has_gas = |gas: u64| {
+            env::prepaid_gas()
+                .as_gas()
+                .saturating_sub(env::used_gas().as_gas())
+                >= gas + config.gas.epoch_run_step.as_gas()
+        };
+
+        self.epoch_cleanup();
+
+        let mut executed_actions = vec![];
+        // each stake or unstake leaves its validator executing,
+        // so the next one goes to another candidate
+        while executed_actions.len() < max_steps as usize && has_gas(gas_to_stake) {
+            let action = match self.internal_epoch_stake(keeper_id.clone()) {
+                Ok(Some((action, _))) => action,
+                Ok(None) => break,
+                Err(err) => {
+                    // nothing is staked in this run, the other steps still go on
+                    Event::EpochStakeSkipped {
+                        stake_amount_to_settle: &U128(self.data().stake_amount_to_settle),
+                        reason: err,
+                    }
+                    .emit();
+                    break;
+                }
+            };
+            executed_actions.push(action);
+        }
+        while executed_actions.len() < max_steps as usize && has_gas(gas_to_unstake) {
+            let Some((action, _)) = self.internal_epoch_unstake(keeper_id.clone()) else {
+                break;
+            };
+            executed_actions.push(action);
+        }
+        for validator in self.data().validator_pool.get_validators(None, None) {
+            if executed_actions.len() >= max_steps as usize {
+                break;
+            }
+            if validator.is_executing() {
+                continue;
+            }
+            if validator.need_rewards_update() {
+                if has_gas(gas_to_update_rewards) {
+                    executed_actions.push(EpochActionView::UpdateRewards {
+                        validator_id: validator.account_id.clone(),
+                    });
+                    self.internal_epoch_update_rewards(validator, keeper_id.clone());
+                }
+            } else if validator.need_withdraw(&config) && has_gas(gas_to_withdraw) {
+                executed_actions.push(EpochActionView::Withdraw {
+                    validator_id: validator.account_id.clone(),
+                    amount: validator.withdrawable_amount().into(),
+                });
+                self.internal_epoch_withdraw(validator, keeper_id.clone());
+            }
+        }
+
+        let validators = self.data().validator_pool.get_validators(None, None);
+        EpochRunResult {
+            executed_actions,
+            stake_amount_to_settle: self.data().stake_amount_to_settle.into(),
+            unstake_amount_to_settle: self.data().unstake_amount_to_settle.into(),
+            validators_to_update_rewards: validators
+                .iter()
+                .filter(|v| !v.is_executing() && v.need_rewards_update())
+                .map(|v| v.account_id.clone())
+                .collect(),
+            validators_to_withdraw: validators
+                .iter()
+                .filter(|v| !v.is_executing() && v.need_withdraw(&config))
+                .map(|v| v.account_id.clone())
+                .collect(),
+        }
+    }
+}
+
+#[near]
+impl Contract {
+    /// Stake $NEAR to one of the validators.
+    ///
+    /// Select a candidate validator and stake part of or all of the to-settle
+    /// stake amounts to this validator. This function is expected to be called
+    /// in each epoch.
+    ///
+    /// # Return
+    /// * `true` - a candidate validator is selected and successfully staked to.
+    ///            There might be more stake amounts to settle so this function
+    ///            should be called again.
+    /// * `false` - There is no need to call this function again in this epoch.
+    #[pause]
+    pub fn epoch_stake(&mut self) -> PromiseOrValue<bool> {
+        self.assert_scope_not_paused(PauseScope::EpochActions);
+        // make sure enough gas was given
+        let gas = self.internal_config().gas;
+        let min_gas = gas.epoch_stake.as_gas() + gas.stake();
+        require!(
+            env::prepaid_gas().as_gas() >= min_gas,
+            format!("{}. require at least {:?}", ERR_NO_ENOUGH_GAS, min_gas)
+        );
+
+        match self.internal_epoch_stake(Some(env::predecessor_account_id())) {
+            Ok(Some((_, promise))) => promise.into(),
+            Ok(None) => PromiseOrValue::Value(false),
+            Err(err) => env::panic_str(err),
+        }
+    }
+
+    /// Unstake $NEAR from one of the validators.
+    ///
+    /// Select a candidate validator and unstake part of or all of the to-settle
+    /// unstake amounts from this validator. This function is expected to be called
+    /// in each epoch.
+    ///
+    /// # Return
+    /// * `true` - a candidate validator is selected and successfully unstaked from.
+    ///            There might be more unstake amounts to settle so this function
+    ///            should be called again.
+    /// * `false` - There is no need to call this function again in this epoch.
+    #[pause]
+    pub fn epoch_unstake(&mut self) -> PromiseOrValue<bool> {
+        self.assert_scope_not_paused(PauseScope::EpochActions);
+        // make sure enough gas was given
+        let gas = self.internal_config().gas;
+        let min_gas = gas.epoch_unstake.as_gas() + gas.unstake();
+        require!(
+            env::prepaid_gas().as_gas() >= min_gas,
+            format!("{}. require at least {:?}", ERR_NO_ENOUGH_GAS, min_gas)
+        );
+
+        match self.internal_epoch_unstake(Some(env::predecessor_account_id())) {
+            Some((_, promise)) => promise.into(),
+            None => PromiseOrValue::Value(false),
+        }
     }
 
     #[pause]
     pub fn epoch_update_rewards(&mut self, validator_id: AccountId) {
-        let min_gas = GAS_EPOCH_UPDATE_REWARDS.as_gas()
-            + GAS_EXT_GET_BALANCE.as_gas()
-            + GAS_CB_VALIDATOR_GET_BALANCE.as_gas();
+        self.assert_scope_not_paused(PauseScope::EpochActions);
+        let gas = self.internal_config().gas;
+        let min_gas = gas.epoch_update_rewards.as_gas() + gas.update_rewards();
         require!(
             env::prepaid_gas().as_gas() >= min_gas,
             format!("{}. require at least {:?}", ERR_NO_ENOUGH_GAS, min_gas)
         );
 
-        let mut validator = self
-            .data_mut()
+        let validator = self
+            .data()
             .validator_pool
             .get_validator(&validator_id)
             .expect(ERR_VALIDATOR_NOT_EXIST);
 
-        validator
-            .refresh_total_balance(&mut self.data_mut().validator_pool)
-            .then(
-                Self::ext(env::current_account_id())
-                    .with_static_gas(GAS_CB_VALIDATOR_GET_BALANCE)
-                    .validator_get_balance_callback(validator.account_id),
-            );
+        // only the first refresh in an epoch is rewarded
+        let keeper_id = validator
+            .need_rewards_update()
+            .then(env::predecessor_account_id);
+        self.internal_epoch_update_rewards(validator, keeper_id);
     }
 
     #[pause]
     pub fn epoch_withdraw(&mut self, validator_id: AccountId) {
+        self.assert_scope_not_paused(PauseScope::EpochActions);
         // make sure enough gas was given
-        let min_gas = GAS_EPOCH_WITHDRAW.as_gas()
-            + GAS_EXT_WITHDRAW.as_gas()
-            + GAS_CB_VALIDATOR_WITHDRAW.as_gas();
+        let gas = self.internal_config().gas;
+        let min_gas = gas.epoch_withdraw.as_gas() + gas.withdraw();
         require!(
             env::prepaid_gas().as_gas() >= min_gas,
             format!("{}. require at least {:?}", ERR_NO_ENOUGH_GAS, min_gas)
         );
 
-        let mut validator = self
-            .data_mut()
+        let validator = self
+            .data()
             .validator_pool
             .get_validator(&validator_id)
             .expect(ERR_VALIDATOR_NOT_EXIST);
 
-        require!(!validator.draining, ERR_DRAINING);
+        let keeper_id = validator
+            .need_withdraw(self.internal_config())
+            .then(env::predecessor_account_id);
+        self.internal_epoch_withdraw(validator, keeper_id);
+    }
 
-        let amount = validator.unstaked_amount;
+    /// Run the epoch work in a single call: cleanup, stake and unstake settlements,
+    /// rewards refresh of stale validators and withdrawals of matured unstaked balance.
+    /// Runs at most `max_steps` actions, as many as the prepaid gas allows,
+    /// and returns the actions that were started and the work that is left.
+    /// The caller is paid a keeper reward for each started action that succeeds.
+    #[pause]
+    pub fn epoch_run(&mut self, max_steps: Option<u32>) -> EpochRunResult {
+        self.assert_scope_not_paused(PauseScope::EpochActions);
+        self.internal_epoch_run(
+            max_steps.unwrap_or(DEFAULT_EPOCH_RUN_MAX_STEPS),
+            Some(env::predecessor_account_id()),
+        )
+    }
 
-        Event::EpochWithdrawAttempt {
-            validator_id: &validator_id,
-            amount: &U128(amount),
+    /// Shows what `epoch_stake` would do if sent now, without any side effect.
+    pub fn preview_epoch_stake(&self) -> EpochActionPreview {
+        let (stake_amount_to_settle, unstake_amount_to_settle, netted_amount) =
+            self.epoch_cleanup_amounts();
+        let mut preview = EpochActionPreview {
+            validator_id: None,
+            amount: U128(0),
+            stake_amount_to_settle: stake_amount_to_settle.into(),
+            unstake_amount_to_settle: unstake_amount_to_settle.into(),
+            reason: None,
+        };
+
+        if self.pa_is_paused("epoch_stake".to_string()) {
+            preview.reason = Some("epoch_stake is paused".to_string());
+            return preview;
         }
-        .emit();
+        if self.is_scope_paused(PauseScope::EpochActions) {
+            preview.reason = Some("epoch_actions is paused".to_string());
+            return preview;
+        }
+        // the netted amount is set aside for withdrawals by the cleanup,
+        // after settling NEAR paid out ahead of the reserve
+        let withdrawal_reserve = self.data().withdrawal_reserve + netted_amount
+            - min(netted_amount, self.data().withdrawal_reserve_advance);
+        match self.plan_epoch_stake(stake_amount_to_settle, withdrawal_reserve) {
+            Ok(candidate) => {
+                preview.validator_id = Some(candidate.validator.account_id);
+                preview.amount = candidate.amount.into();
+                preview.stake_amount_to_settle = (stake_amount_to_settle - candidate.amount).into();
+            }
+            Err(err) => preview.reason = Some(err.reason()),
+        }
+        preview
+    }
 
-        validator
-            .withdraw(&mut self.data_mut().validator_pool, amount)
-            .then(
-                Self::ext(env::current_account_id())
-                    .with_static_gas(GAS_CB_VALIDATOR_WITHDRAW)
-                    .validator_withdraw_callback(validator.account_id.clone(), amount.into()),
-            );
+    /// Shows what `epoch_unstake` would do if sent now, without any side effect.
+    pub fn preview_epoch_unstake(&self) -> EpochActionPreview {
+        let (stake_amount_to_settle, unstake_amount_to_settle, _) = self.epoch_cleanup_amounts();
+        let mut preview = EpochActionPreview {
+            validator_id: None,
+            amount: U128(0),
+            stake_amount_to_settle: stake_amount_to_settle.into(),
+            unstake_amount_to_settle: unstake_amount_to_settle.into(),
+            reason: None,
+        };
+
+        if self.pa_is_paused("epoch_unstake".to_string()) {
+            preview.reason = Some("epoch_unstake is paused".to_string());
+            return preview;
+        }
+        if self.is_scope_paused(PauseScope::EpochActions) {
+            preview.reason = Some("epoch_actions is paused".to_string());
+            return preview;
+        }
+        match self.plan_epoch_unstake(unstake_amount_to_settle) {
+            Ok(candidate) => {
+                preview.validator_id = Some(candidate.validator.account_id);
+                preview.amount = candidate.amount.into();
+                preview.unstake_amount_to_settle =
+                    (unstake_amount_to_settle - candidate.amount).into();
+            }
+            Err(err) => preview.reason = Some(err.reason()),
+        }
+        preview
+    }
+
+    /// Shows the last known balance of the validator and when its rewards were last refreshed.
+    pub fn preview_rewards(&self, validator_id: AccountId) -> RewardsPreview {
+        let validator = self
+            .data()
+            .validator_pool
+            .get_validator(&validator_id)
+            .expect(ERR_VALIDATOR_NOT_EXIST);
+        RewardsPreview {
+            validator_id,
+            total_balance: validator.total_balance().into(),
+            staked_amount: validator.staked_amount.into(),
+            unstaked_amount: validator.unstaked_amount.into(),
+            rewards_updated_epoch: validator.rewards_updated_epoch,
+            current_epoch: get_epoch_height(),
+            need_rewards_update: validator.need_rewards_update(),
+            executing: validator.executing,
+        }
     }
 }
 
@@ -270,6 +684,7 @@ impl Contract {
         &mut self,
         validator_id: AccountId,
         amount: U128,
+        keeper_id: Option<AccountId>,
     ) -> PromiseOrValue<bool> {
         let amount = amount.into();
         let mut validator = self
@@ -287,11 +702,16 @@ impl Contract {
             }
             .emit();
 
+            if let Some(keeper_id) = keeper_id {
+                self.internal_reward_keeper(&keeper_id, 1);
+            }
+
+            let config = *self.internal_config();
             validator
-                .sync_account_balance(&mut self.data_mut().validator_pool, true)
+                .sync_account_balance(&mut self.data_mut().validator_pool, true, &config)
                 .then(
                     Self::ext(env::current_account_id())
-                        .with_static_gas(GAS_CB_VALIDATOR_SYNC_BALANCE)
+                        .with_static_gas(config.gas.cb_validator_sync_balance)
                         .validator_get_account_callback(validator_id),
                 )
                 .into()
@@ -319,6 +739,7 @@ impl Contract {
         &mut self,
         validator_id: AccountId,
         amount: U128,
+        keeper_id: Option<AccountId>,
     ) -> PromiseOrValue<bool> {
         let amount = amount.into();
         let mut validator = self
@@ -336,11 +757,16 @@ impl Contract {
             }
             .emit();
 
+            if let Some(keeper_id) = keeper_id {
+                self.internal_reward_keeper(&keeper_id, 1);
+            }
+
+            let config = *self.internal_config();
             validator
-                .sync_account_balance(&mut self.data_mut().validator_pool, true)
+                .sync_account_balance(&mut self.data_mut().validator_pool, true, &config)
                 .then(
                     Self::ext(env::current_account_id())
-                        .with_static_gas(GAS_CB_VALIDATOR_SYNC_BALANCE)
+                        .with_static_gas(config.gas.cb_validator_sync_balance)
                         .validator_get_account_callback(validator_id),
                 )
                 .into()
@@ -366,6 +792,7 @@ impl Contract {
     pub fn validator_get_balance_callback(
         &mut self,
         validator_id: AccountId,
+        keeper_id: Option<AccountId>,
         #[callback_result] call_result: Result<U128, PromiseError>,
     ) {
         let mut validator = self
@@ -374,9 +801,23 @@ impl Contract {
             .get_validator(&validator_id)
             .expect(ERR_VALIDATOR_NOT_EXIST);
         match call_result {
+            Ok(total_balance) if total_balance.0 < validator.total_balance() => {
+                // a loss is never taken out of the share price here, it's left
+                // to later rewards or to the settlement of a stuck validator
+                Event::EpochBalanceShortfall {
+                    validator_id: &validator_id,
+                    old_balance: &U128(validator.total_balance()),
+                    new_balance: &total_balance,
+                    shortfall: &U128(validator.total_balance() - total_balance.0),
+                }
+                .emit();
+
+                validator.on_total_balance_shortfall(&mut self.data_mut().validator_pool);
+            }
             Ok(total_balance) => {
                 let new_balance = total_balance.0;
-                let rewards = new_balance - validator.total_balance();
+                let rewards = new_balance.saturating_sub(validator.total_balance());
+                let rewards = self.internal_cap_rewards(&validator, rewards);
                 Event::EpochUpdateRewards {
                     validator_id: &validator_id,
                     old_balance: &U128(validator.total_balance()),
@@ -387,12 +828,15 @@ impl Contract {
 
                 validator.on_new_total_balance(&mut self.data_mut().validator_pool, new_balance);
 
-                if rewards == 0 {
-                    return;
+                if rewards > 0 {
+                    self.internal_credit_staking_rewards(rewards);
                 }
+                self.internal_record_price_sample();
 
-                self.data_mut().total_staked_asset_in_near += rewards;
-                self.internal_distribute_staking_rewards(rewards);
+                // the keeper is paid only for a refresh that brought rewards in
+                if let Some(keeper_id) = keeper_id.filter(|_| rewards > 0) {
+                    self.internal_reward_keeper(&keeper_id, 1);
+                }
             }
             Err(_) => {
                 validator.on_get_account_total_balance_failed(&mut self.data_mut().validator_pool);
@@ -418,20 +862,21 @@ impl Contract {
 
         match result {
             Ok(account) => {
+                let max_sync_balance_diff = self.internal_config().max_sync_balance_diff.0;
                 // allow at most max_sync_balance_diff diff in total balance, staked balance and unstake balance
                 let new_total_balance = account.staked_balance.0 + account.unstaked_balance.0;
                 if abs_diff_eq(
                     new_total_balance,
                     validator.total_balance(),
-                    MAX_SYNC_BALANCE_DIFF,
+                    max_sync_balance_diff,
                 ) && abs_diff_eq(
                     account.staked_balance.0,
                     validator.staked_amount,
-                    MAX_SYNC_BALANCE_DIFF,
+                    max_sync_balance_diff,
                 ) && abs_diff_eq(
                     account.unstaked_balance.0,
                     validator.unstaked_amount,
-                    MAX_SYNC_BALANCE_DIFF,
+                    max_sync_balance_diff,
                 ) {
                     Event::SyncValidatorBalanceSuccess {
                         validator_id: &validator_id,
@@ -477,7 +922,12 @@ impl Contract {
     }
 
     #[private]
-    pub fn validator_withdraw_callback(&mut self, validator_id: AccountId, amount: U128) {
+    pub fn validator_withdraw_callback(
+        &mut self,
+        validator_id: AccountId,
+        amount: U128,
+        keeper_id: Option<AccountId>,
+    ) {
         let amount = amount.into();
         let mut validator = self
             .data_mut()
@@ -488,11 +938,18 @@ impl Contract {
         if is_promise_success() {
             validator.on_withdraw_success(&mut self.data_mut().validator_pool);
 
+            // withdrawn NEAR is set aside for users' withdrawals
+            self.internal_credit_withdrawal_reserve(amount);
+
             Event::EpochWithdrawSuccess {
                 validator_id: &validator_id,
                 amount: &U128(amount),
             }
             .emit();
+
+            if let Some(keeper_id) = keeper_id {
+                self.internal_reward_keeper(&keeper_id, 1);
+            }
         } else {
             // withdraw failed, revert
             validator.on_withdraw_failed(&mut self.data_mut().validator_pool, amount);
@@ -5,11 +5,18 @@ pub const ERR_NO_ENOUGH_INIT_DEPOSIT: &str =
 
 // owner
 pub const ERR_NOT_OWNER: &str = "Only owner can perform this action";
+pub const ERR_ALREADY_OWNER: &str = "The account is already the owner";
+pub const ERR_NO_OWNER_PROPOSAL: &str = "No owner proposal";
+pub const ERR_NOT_PROPOSED_OWNER: &str = "Only the proposed owner can accept the ownership";
+pub const ERR_OWNER_PROPOSAL_EXPIRED: &str = "Owner proposal has expired";
+pub const ERR_INVALID_OWNER_PROPOSAL_EXPIRY: &str = "Owner proposal expiry is out of range";
 
 // account
 pub const ERR_FORCE_UNGREGISTER: &str = "Force unregister is not allowed";
 pub const ERR_UNREGISTER_POSITIVE_UNSTAKED: &str =
     "Can't unregister the account with the positive unstaked balance";
+pub const ERR_ACCOUNT_NOT_REGISTERED: &str = "Account is not registered";
+pub const ERR_INSUFFICIENT_STORAGE_DEPOSIT: &str = "Attached deposit doesn't cover the storage";
 
 // fraction
 pub const ERR_BPS_SUM_ONE: &str = "bps sum should be less than 1";
@@ -56,6 +63,7 @@ pub const ERR_NO_ENOUGH_CONTRACT_BALANCE: &str =
 
 // validator
 pub const ERR_MIN_RESERVE: &str = "Contract min reserve error";
+pub const ERR_WITHDRAWAL_RESERVE: &str = "Cannot stake NEAR reserved for withdrawals";
 pub const ERR_VALIDATOR_NOT_EXIST: &str = "Validator not exist";
 pub const ERR_VALIDATOR_ALREADY_EXIST: &str = "Validator already exists";
 pub const ERR_VALIDATOR_IN_USE: &str = "Validator is in use, cannot remove";
@@ -63,6 +71,89 @@ pub const ERR_NO_ENOUGH_GAS: &str = "No enough gas";
 pub const ERR_BAD_VALIDATOR_LIST: &str = "Bad validator list";
 pub const ERR_VALIDATOR_NOT_WHITELISTED: &str = "Validator not whitelisted";
 pub const ERR_VALIDATOR_WHITELIST_NOT_SET: &str = "Validator whitelist not set";
+pub const ERR_VALIDATOR_NO_WEIGHT: &str = "Validator has zero weight";
+
+// redelegation
+pub const ERR_REDELEGATION_NOT_EXIST: &str = "Redelegation not exist";
+pub const ERR_BAD_REDELEGATION_STATUS: &str = "Redelegation is not in the expected status";
+pub const ERR_NON_POSITIVE_REDELEGATION_AMOUNT: &str = "Redelegation amount should be positive";
+pub const ERR_SAME_REDELEGATION_VALIDATOR: &str = "Cannot redelegate to the same validator";
+
+// partner
+pub const ERR_PARTNER_ALREADY_EXIST: &str = "Partner already exists";
+pub const ERR_PARTNER_NOT_EXIST: &str = "Partner not exist";
+
+// keeper
+pub const ERR_KEEPER_REWARD_BPS_TOO_HIGH: &str = "Keeper reward bps is too high";
+
+// beneficiary
+pub const ERR_NOT_BENEFICIARY: &str = "Not a beneficiary";
+pub const ERR_NOTHING_TO_CLAIM: &str = "Nothing to claim";
+
+// reward vesting
+pub const ERR_REWARD_VESTING_PERIOD_TOO_LONG: &str = "Reward vesting period is too long";
+
+// referral
+pub const ERR_REFERRER_ALREADY_EXIST: &str = "Referrer already exists";
+pub const ERR_REFERRER_NOT_EXIST: &str = "Referrer not exist";
+
+// price history
+pub const ERR_INVALID_PRICE_WINDOW: &str = "Price window must be positive";
+
+// reward cap
+pub const ERR_INVALID_MAX_REWARD_APR: &str = "Invalid max reward APR";
+pub const ERR_NO_QUARANTINED_REWARDS: &str = "No quarantined rewards for the validator";
+pub const ERR_INVALID_QUARANTINED_AMOUNT: &str = "Invalid quarantined rewards amount";
+
+// timelock
+pub const ERR_TIMELOCK_REQUIRED: &str = "The action must be queued in the timelock";
+pub const ERR_TIMELOCK_NOT_ALLOWED: &str = "No permission to queue the action";
+pub const ERR_TIMELOCK_PROPOSAL_NOT_EXIST: &str = "Timelock proposal not exist";
+pub const ERR_TIMELOCK_NOT_READY: &str = "Timelock delay has not passed";
+pub const ERR_TIMELOCK_DELAY_TOO_LONG: &str = "Timelock delay is too long";
+
+// pause
+pub const ERR_SCOPE_PAUSED: &str = "Pause scope is paused";
+pub const ERR_EXIT_ONLY_MODE_UNCHANGED: &str = "Exit only mode is already in the requested state";
+
+// emergency
+pub const ERR_EMERGENCY_MODE: &str = "Not allowed in emergency mode";
+pub const ERR_NOT_IN_EMERGENCY_MODE: &str = "Emergency mode is not enabled";
+pub const ERR_EMERGENCY_FUNDS_NOT_AVAILABLE: &str = "Not enough unwound funds to withdraw";
+
+// flow limits
+pub const ERR_TVL_CAP_EXCEEDED: &str = "Total staked amount would exceed the TVL cap";
+pub const ERR_EPOCH_DEPOSIT_LIMIT_EXCEEDED: &str = "Deposit limit of the epoch is exceeded";
+pub const ERR_INVALID_FLOW_LIMIT: &str = "Flow limits must be positive";
+
+// accounting
+pub const ERR_INVALID_ADJUSTMENT_REASON: &str = "Invalid adjustment reason";
+pub const ERR_NO_ADJUSTMENT: &str = "Nothing to adjust";
+pub const ERR_ADJUSTMENT_TOO_LARGE: &str = "Adjustment exceeds the per call limit";
+pub const ERR_EPOCH_ADJUSTMENT_LIMIT_EXCEEDED: &str = "Adjustment exceeds the per epoch limit";
+pub const ERR_INVALID_ACCOUNTING_LIMITS: &str = "Per call limit must not exceed per epoch limit";
+pub const ERR_NOT_DUST: &str = "Unstaked amount is not dust";
+
+// config
+pub const ERR_INVALID_CONFIG: &str = "Invalid config";
+
+// approvals
+pub const ERR_APPROVAL_REQUIRED: &str = "The action must be proposed and approved";
+pub const ERR_APPROVALS_NOT_ENABLED: &str = "Multi approval is not enabled";
+pub const ERR_APPROVAL_NOT_ALLOWED: &str = "No permission to propose or approve the action";
+pub const ERR_APPROVAL_PROPOSAL_NOT_EXIST: &str = "Approval proposal not exist";
+pub const ERR_APPROVAL_PROPOSAL_EXPIRED: &str = "Approval proposal has expired";
+pub const ERR_ALREADY_APPROVED: &str = "Already approved";
+pub const ERR_NOT_ENOUGH_APPROVALS: &str = "Not enough approvals";
+pub const ERR_INVALID_APPROVAL_CONFIG: &str = "Invalid approval config";
+
+// fees
+pub const ERR_FEE_TOO_HIGH: &str = "Fee exceeds the hard max";
+pub const ERR_NO_FEE_TREASURY: &str = "Treasury is required to charge deposit or exit fees";
+pub const ERR_NO_PENDING_FEE_CONFIG: &str = "No pending fee config";
+pub const ERR_FEE_CONFIG_NOT_EFFECTIVE: &str = "Fee config change delay has not passed";
+pub const ERR_FEE_CONFIG_ALREADY_EFFECTIVE: &str = "Fee config is already in effect";
+pub const ERR_FEE_CHANGE_DELAY_TOO_SHORT: &str = "Fee change delay is below the min";
 
 pub const ERR_VALIDATOR_UNSTAKE_AMOUNT: &str = "No enough amount to unstake from validator";
 pub const ERR_VALIDATOR_UNSTAKE_WHEN_LOCKED: &str =
@@ -72,6 +163,7 @@ pub const ERR_VALIDATOR_WITHDRAW_WHEN_LOCKED: &str =
 pub const ERR_VALIDATOR_ALREADY_EXECUTING_ACTION: &str = "Validator is already executing action";
 pub const ERR_VALIDATOR_SYNC_BALANCE_NOT_EXPECTED: &str =
     "Validator sync balance is expected to be called after stake or unstake";
+pub const ERR_VALIDATOR_NOT_STUCK: &str = "Validator is not stuck in executing action";
 
 // LST
 pub const ERR_NON_POSITIVE_SHARES: &str = "Share number should be positive";
@@ -1,3 +1,8 @@
+use crate::{
+    AccountingLimits, ApprovalConfig, ApprovalProposal, BeneficiaryPayoutKind, Config,
+    ExecutionKind, FeeConfig, FlowLimitConfig, KeeperRewardConfig, TimelockActionKind,
+    TimelockProposal,
+};
 use near_sdk::{json_types::U128, log, serde::Serialize, serde_json::json, AccountId};
 
 const EVENT_STANDARD: &str = "rhea_lst";
@@ -21,6 +26,10 @@ pub enum Event<'a> {
         validator_id: &'a AccountId,
         amount: &'a U128,
     },
+    EpochStakeSkipped {
+        stake_amount_to_settle: &'a U128,
+        reason: &'a str,
+    },
     EpochUnstakeAttempt {
         validator_id: &'a AccountId,
         amount: &'a U128,
@@ -51,6 +60,12 @@ pub enum Event<'a> {
         new_balance: &'a U128,
         rewards: &'a U128,
     },
+    EpochBalanceShortfall {
+        validator_id: &'a AccountId,
+        old_balance: &'a U128,
+        new_balance: &'a U128,
+        shortfall: &'a U128,
+    },
     EpochCleanup {
         stake_amount_to_settle: &'a U128,
         unstake_amount_to_settle: &'a U128,
@@ -80,6 +95,74 @@ pub enum Event<'a> {
         validator_id: &'a AccountId,
         amount: &'a U128,
     },
+    // Stuck validator recovery
+    ResetStuckValidatorAttempt {
+        validator_id: &'a AccountId,
+        kind: ExecutionKind,
+        block_height: u64,
+    },
+    ResetStuckValidatorSuccess {
+        validator_id: &'a AccountId,
+        old_staked_balance: &'a U128,
+        old_unstaked_balance: &'a U128,
+        new_staked_balance: &'a U128,
+        new_unstaked_balance: &'a U128,
+    },
+    ResetStuckValidatorFailed {
+        validator_id: &'a AccountId,
+    },
+    StuckExecutionSettled {
+        validator_id: &'a AccountId,
+        kind: ExecutionKind,
+        amount: &'a U128,
+        settled_amount: &'a U128,
+        rewards: &'a U128,
+        loss: &'a U128,
+    },
+    // Redelegation
+    RedelegationCreated {
+        redelegation_id: u64,
+        from_validator_id: &'a AccountId,
+        to_validator_id: &'a AccountId,
+        amount: &'a U128,
+    },
+    RedelegationUnstakeSuccess {
+        redelegation_id: u64,
+        validator_id: &'a AccountId,
+        amount: &'a U128,
+    },
+    RedelegationUnstakeFailed {
+        redelegation_id: u64,
+        validator_id: &'a AccountId,
+        amount: &'a U128,
+    },
+    RedelegationWithdrawSuccess {
+        redelegation_id: u64,
+        validator_id: &'a AccountId,
+        amount: &'a U128,
+    },
+    RedelegationWithdrawFailed {
+        redelegation_id: u64,
+        validator_id: &'a AccountId,
+        amount: &'a U128,
+    },
+    RedelegationStakeFailed {
+        redelegation_id: u64,
+        validator_id: &'a AccountId,
+        amount: &'a U128,
+    },
+    RedelegationCompleted {
+        redelegation_id: u64,
+        from_validator_id: &'a AccountId,
+        to_validator_id: &'a AccountId,
+        amount: &'a U128,
+    },
+    RedelegationCancelled {
+        redelegation_id: u64,
+        from_validator_id: &'a AccountId,
+        to_validator_id: &'a AccountId,
+        amount: &'a U128,
+    },
     // Sync validator balance
     SyncValidatorBalanceSuccess {
         validator_id: &'a AccountId,
@@ -131,6 +214,44 @@ pub enum Event<'a> {
         new_stake_shares: &'a U128,
         last_unstake_request_epoch_height: u64,
     },
+    // Matching
+    StakeMatched {
+        account_id: &'a AccountId,
+        matched_amount: &'a U128,
+        unmatched_amount: &'a U128,
+    },
+    UnstakeMatched {
+        account_id: &'a AccountId,
+        request_id: Option<u64>,
+        matched_amount: &'a U128,
+        unmatched_amount: &'a U128,
+    },
+    // Directed stake
+    ValidatorPreferenceUpdated {
+        account_id: &'a AccountId,
+        validator_id: Option<&'a AccountId>,
+        directed_shares: &'a U128,
+    },
+    // Partners
+    PartnerRegistered {
+        partner_id: &'a AccountId,
+        validator_id: &'a AccountId,
+    },
+    PartnerDeregistered {
+        partner_id: &'a AccountId,
+        validator_id: &'a AccountId,
+        released_amount: &'a U128,
+    },
+    // Keeper rewards
+    KeeperRewarded {
+        keeper_id: &'a AccountId,
+        num_actions: u32,
+        shares: &'a U128,
+        amount: &'a U128,
+    },
+    SetKeeperRewardConfig {
+        config: &'a KeeperRewardConfig,
+    },
     // Validators
     ValidatorAdded {
         account_id: &'a AccountId,
@@ -149,7 +270,174 @@ pub enum Event<'a> {
     ValidatorRemoved {
         account_id: &'a AccountId,
     },
+    // Fees
+    FeeConfigAnnounced {
+        config: &'a FeeConfig,
+        effective_epoch: u64,
+    },
+    FeeConfigApplied {
+        config: &'a FeeConfig,
+    },
+    FeeConfigCancelled {
+        config: &'a FeeConfig,
+    },
+    SetFeeChangeDelay {
+        old_delay_epochs: u64,
+        new_delay_epochs: u64,
+        effective_epoch: u64,
+    },
+    FeeCharged {
+        account_id: &'a AccountId,
+        kind: &'a str,
+        treasury_id: &'a AccountId,
+        fee_amount: &'a U128,
+        fee_shares: &'a U128,
+    },
+    // Referral
+    ReferrerRegistered {
+        referrer_id: &'a AccountId,
+    },
+    ReferralBound {
+        account_id: &'a AccountId,
+        referrer_id: &'a AccountId,
+    },
+    ReferredDeposit {
+        account_id: &'a AccountId,
+        referrer_id: &'a AccountId,
+        amount: &'a U128,
+    },
+    ReferralRewardsDistributed {
+        reward_shares: &'a U128,
+        total_active_shares: &'a U128,
+    },
+    ReferralRewardsClaimed {
+        referrer_id: &'a AccountId,
+        shares: &'a U128,
+    },
+    // Reward vesting
+    SetRewardVestingPeriod {
+        old_period_sec: u64,
+        new_period_sec: u64,
+    },
+    // Reward cap
+    SetMaxRewardApr {
+        old_max_apr_bps: Option<u32>,
+        new_max_apr_bps: Option<u32>,
+    },
+    RewardsQuarantined {
+        validator_id: &'a AccountId,
+        rewards: &'a U128,
+        max_rewards: &'a U128,
+        quarantined: &'a U128,
+    },
+    QuarantinedRewardsReleased {
+        validator_id: &'a AccountId,
+        amount: &'a U128,
+    },
+    QuarantinedRewardsDiscarded {
+        validator_id: &'a AccountId,
+        amount: &'a U128,
+    },
+    // Timelock
+    TimelockQueued {
+        proposal: &'a TimelockProposal,
+    },
+    TimelockCancelled {
+        id: u64,
+    },
+    TimelockExecuted {
+        id: u64,
+    },
+    SetTimelockDelay {
+        kind: TimelockActionKind,
+        old_delay_sec: u64,
+        new_delay_sec: u64,
+    },
+    // Emergency
+    EmergencyModeEnabled {
+        account_id: &'a AccountId,
+        num_validators: u32,
+        total_staked: &'a U128,
+        total_supply: &'a U128,
+    },
+    EmergencyFundsSettled {
+        withdrawn_amount: &'a U128,
+        reserved_amount: &'a U128,
+        available_amount: &'a U128,
+    },
+    EmergencyWithdraw {
+        account_id: &'a AccountId,
+        burnt_shares: &'a U128,
+        amount: &'a U128,
+    },
+    // Flow limits
+    SetFlowLimits {
+        old_config: &'a FlowLimitConfig,
+        new_config: &'a FlowLimitConfig,
+    },
+    UnstakeDeferred {
+        account_id: &'a AccountId,
+        deferred_amount: &'a U128,
+        deferred_unstake_amount: &'a U128,
+    },
+    DeferredUnstakeReleased {
+        amount: &'a U128,
+        deferred_unstake_amount: &'a U128,
+    },
+    // Accounting
+    AccountingAdjusted {
+        accountant_id: &'a AccountId,
+        action: &'a str,
+        validator_id: Option<&'a AccountId>,
+        field: &'a str,
+        old_value: &'a U128,
+        new_value: &'a U128,
+        reason: &'a str,
+        epoch: u64,
+        epoch_adjusted_amount: &'a U128,
+    },
+    SetAccountingLimits {
+        old_limits: &'a AccountingLimits,
+        new_limits: &'a AccountingLimits,
+    },
+    // Config
+    ConfigUpdated {
+        old_config: &'a Config,
+        new_config: &'a Config,
+    },
+    // Approvals
+    ApprovalProposed {
+        proposal: &'a ApprovalProposal,
+    },
+    ApprovalAdded {
+        id: u64,
+        account_id: &'a AccountId,
+        num_approvals: u32,
+    },
+    ApprovalExecuted {
+        id: u64,
+    },
+    ApprovalCancelled {
+        id: u64,
+    },
+    SetApprovalConfig {
+        old_config: &'a ApprovalConfig,
+        new_config: &'a ApprovalConfig,
+    },
+    // Pause
+    ExitOnlyModeUpdated {
+        account_id: &'a AccountId,
+        enabled: bool,
+    },
     // Owner
+    OwnerProposed {
+        owner_id: &'a AccountId,
+        new_owner_id: &'a AccountId,
+        expires_at: U64,
+    },
+    OwnerProposalCancelled {
+        new_owner_id: &'a AccountId,
+    },
     ChangeOwner {
         old_owner_id: &'a AccountId,
         new_owner_id: &'a AccountId,
@@ -167,6 +455,20 @@ pub enum Event<'a> {
     RemoveBeneficiary {
         account_id: &'a AccountId,
     },
+    SetBeneficiaryPayout {
+        account_id: &'a AccountId,
+        payout: BeneficiaryPayoutKind,
+    },
+    BeneficiaryPaid {
+        account_id: &'a AccountId,
+        payout: BeneficiaryPayoutKind,
+        shares: &'a U128,
+        amount: &'a U128,
+    },
+    BeneficiaryClaim {
+        account_id: &'a AccountId,
+        amount: &'a U128,
+    },
     SetWhitelist {
         account_id: &'a AccountId,
     },
@@ -0,0 +1,302 @@
+use crate::*;
+
+/// Hard max of all reward fees, including beneficiaries
+pub const MAX_REWARD_FEE_BPS: u32 = 3_000;
+/// Hard max of the deposit fee
+pub const MAX_DEPOSIT_FEE_BPS: u32 = 100;
+/// Hard max of the instant exit fee
+pub const MAX_INSTANT_EXIT_FEE_BPS: u32 = 300;
+/// Default number of epochs between announcing and applying a fee change
+pub const DEFAULT_FEE_CHANGE_DELAY_EPOCHS: EpochHeight = 4;
+/// Min number of epochs between announcing and applying a fee change
+pub const MIN_FEE_CHANGE_DELAY_EPOCHS: EpochHeight = 2;
+
+#[near(serializers = [borsh, json])]
+#[derive(Clone)]
+pub struct FeeRecipient {
+    pub account_id: AccountId,
+    pub bps: u32,
+}
+
+/// Fees on top of the beneficiary fees. Reward fees are paid in LST minted
+/// from staking rewards. Deposit and instant exit fees are paid in LST to the
+/// treasury.
+#[near(serializers = [borsh, json])]
+#[derive(Clone, Default)]
+pub struct FeeConfig {
+    pub protocol_fee: Option<FeeRecipient>,
+    pub operator_fee: Option<FeeRecipient>,
+    pub treasury_fee: Option<FeeRecipient>,
+    /// Fee on staked NEAR
+    pub deposit_fee_bps: u32,
+    /// Fee on unstaked NEAR that is matched and can be withdrawn at once
+    pub instant_exit_fee_bps: u32,
+    /// Part of the protocol fee shared with referrers
+    #[serde(default)]
+    pub referral_share_bps: u32,
+}
+
+impl FeeConfig {
+    pub fn reward_fee_bps(&self) -> u32 {
+        [&self.protocol_fee, &self.operator_fee, &self.treasury_fee]
+            .into_iter()
+            .flatten()
+            .map(|recipient| recipient.bps)
+            .sum()
+    }
+
+    pub fn reward_fee_recipients(&self) -> Vec<&FeeRecipient> {
+        [&self.protocol_fee, &self.operator_fee, &self.treasury_fee]
+            .into_iter()
+            .flatten()
+            .filter(|recipient| recipient.bps > 0)
+            .collect()
+    }
+
+    pub fn treasury_id(&self) -> Option<&AccountId> {
+        self.treasury_fee
+            .as_ref()
+            .map(|recipient| &recipient.account_id)
+    }
+
+    pub fn assert_valid(&self, beneficiary_bps: u32) {
+        require!(
+            self.reward_fee_bps() + beneficiary_bps <= MAX_REWARD_FEE_BPS,
+            ERR_FEE_TOO_HIGH
+        );
+        require!(
+            self.deposit_fee_bps <= MAX_DEPOSIT_FEE_BPS,
+            ERR_FEE_TOO_HIGH
+        );
+        require!(
+            self.instant_exit_fee_bps <= MAX_INSTANT_EXIT_FEE_BPS,
+            ERR_FEE_TOO_HIGH
+        );
+        require!(
+            self.referral_share_bps <= FULL_BASIS_POINTS,
+            ERR_FEE_TOO_HIGH
+        );
+        require!(
+            self.treasury_fee.is_some()
+                || (self.deposit_fee_bps == 0 && self.instant_exit_fee_bps == 0),
+            ERR_NO_FEE_TREASURY
+        );
+    }
+}
+
+#[near(serializers = [borsh, json])]
+#[derive(Clone)]
+pub struct PendingFeeConfig {
+    pub config: FeeConfig,
+    /// The epoch from which the config takes effect
+    pub effective_epoch: EpochHeight,
+}
+
+#[near(serializers = [borsh, json])]
+#[derive(Clone)]
+pub struct PendingFeeChangeDelay {
+    pub delay_epochs: EpochHeight,
+    /// The epoch from which the delay takes effect
+    pub effective_epoch: EpochHeight,
+}
+
+#[near(serializers = [json])]
+pub struct FeesView {
+    pub effective: FeeConfig,
+    pub pending: Option<PendingFeeConfig>,
+    pub beneficiary_fee_bps: u32,
+    pub change_delay_epochs: EpochHeight,
+    pub pending_change_delay: Option<PendingFeeChangeDelay>,
+}
+
+impl Contract {
+    /// The fee config in effect, including an announced change whose delay has passed
+    pub(crate) fn effective_fee_config(&self) -> FeeConfig {
+        match self.data().pending_fee_config.as_ref() {
+            Some(pending) if pending.effective_epoch <= get_epoch_height() => {
+                pending.config.clone()
+            }
+            _ => self.data().fee_config.clone(),
+        }
+    }
+
+    /// The fee change delay in effect, including a change whose own delay has passed
+    pub(crate) fn effective_fee_change_delay(&self) -> EpochHeight {
+        match self.data().pending_fee_change_delay.as_ref() {
+            Some(pending) if pending.effective_epoch <= get_epoch_height() => pending.delay_epochs,
+            _ => self.data().fee_change_delay_epochs,
+        }
+    }
+
+    fn internal_beneficiary_bps(&self) -> u32 {
+        self.data().beneficiaries.values().sum()
+    }
+
+    /// The highest reward fee bps that is or will be in effect
+    pub(crate) fn max_scheduled_reward_fee_bps(&self) -> u32 {
+        let pending_bps = self
+            .data()
+            .pending_fee_config
+            .as_ref()
+            .map(|pending| pending.config.reward_fee_bps())
+            .unwrap_or(0);
+        self.data().fee_config.reward_fee_bps().max(pending_bps)
+    }
+
+    /// Mint LST worth the fee amount to the treasury. The shares must be
+    /// computed before any other supply change in the same operation.
+    pub(crate) fn internal_charge_fee(
+        &mut self,
+        account_id: &AccountId,
+        kind: &str,
+        fee_amount: u128,
+        fee_shares: u128,
+    ) {
+        if fee_shares == 0 {
+            return;
+        }
+        let treasury_id = self
+            .effective_fee_config()
+            .treasury_id()
+            .cloned()
+            .expect(ERR_NO_FEE_TREASURY);
+        self.mint_lst(&treasury_id, fee_shares, Some(kind));
+        Event::FeeCharged {
+            account_id,
+            kind,
+            treasury_id: &treasury_id,
+            fee_amount: &U128(fee_amount),
+            fee_shares: &U128(fee_shares),
+        }
+        .emit();
+    }
+}
+
+#[near]
+impl Contract {
+    /// Announce a new fee config, which can be applied after the change delay.
+    /// Replaces any pending announcement.
+    #[payable]
+    #[access_control_any(roles(Role::DAO))]
+    #[pause]
+    pub fn announce_fee_config(&mut self, config: FeeConfig) {
+        assert_one_yocto();
+        config.assert_valid(self.internal_beneficiary_bps());
+        // a due announcement is applied before it gets replaced
+        self.data_mut().fee_config = self.effective_fee_config();
+
+        let effective_epoch = get_epoch_height() + self.effective_fee_change_delay();
+        Event::FeeConfigAnnounced {
+            config: &config,
+            effective_epoch,
+        }
+        .emit();
+        self.data_mut().pending_fee_config = Some(PendingFeeConfig {
+            config,
+            effective_epoch,
+        });
+    }
+
+    /// Apply the announced fee config once its delay has passed.
+    /// Anyone can call it since the config is already in effect.
+    #[pause]
+    pub fn apply_fee_config(&mut self) {
+        let pending = self
+            .data()
+            .pending_fee_config
+            .clone()
+            .expect(ERR_NO_PENDING_FEE_CONFIG);
+        require!(
+            pending.effective_epoch <= get_epoch_height(),
+            ERR_FEE_CONFIG_NOT_EFFECTIVE
+        );
+        self.data_mut().fee_config = pending.config;
+        self.data_mut().pending_fee_config = None;
+        Event::FeeConfigApplied {
+            config: &self.data().fee_config,
+        }
+        .emit();
+    }
+
+    #[payable]
+    #[access_control_any(roles(Role::DAO))]
+    #[pause]
+    pub fn cancel_fee_config(&mut self) {
+        assert_one_yocto();
+        let pending = self
+            .data()
+            .pending_fee_config
+            .clone()
+            .expect(ERR_NO_PENDING_FEE_CONFIG);
+        require!(
+            pending.effective_epoch > get_epoch_height(),
+            ERR_FEE_CONFIG_ALREADY_EFFECTIVE
+        );
+        self.data_mut().pending_fee_config = None;
+        Event::FeeConfigCancelled {
+            config: &pending.config,
+        }
+        .emit();
+    }
+
+    /// Set the number of epochs between announcing and applying a fee change.
+    /// The new delay itself takes effect after the current delay, and only
+    /// affects later announcements. Replaces any pending delay change.
+    #[payable]
+    #[access_control_any(roles(Role::DAO))]
+    #[pause]
+    pub fn set_fee_change_delay(&mut self, delay_epochs: EpochHeight) {
+        assert_one_yocto();
+        require!(
+            delay_epochs >= MIN_FEE_CHANGE_DELAY_EPOCHS,
+            ERR_FEE_CHANGE_DELAY_TOO_SHORT
+        );
+        // a due delay change is applied before it gets replaced
+        let old_delay_epochs = self.effective_fee_change_delay();
+        self.data_mut().fee_change_delay_epochs = old_delay_epochs;
+
+        let effective_epoch = get_epoch_height() + old_delay_epochs;
+        Event::SetFeeChangeDelay {
+            old_delay_epochs,
+            new_delay_epochs: delay_epochs,
+            effective_epoch,
+        }
+        .emit();
+        self.data_mut().pending_fee_change_delay = Some(PendingFeeChangeDelay {
+            delay_epochs,
+            effective_epoch,
+        });
+    }
+
+    pub fn get_fees(&self) -> FeesView {
+        let pending = self
+            .data()
+            .pending_fee_config
+            .clone()
+            .filter(|pending| pending.effective_epoch > get_epoch_height());
+        FeesView {
+            effective: self.effective_fee_config(),
+            pending,
+            beneficiary_fee_bps: self.internal_beneficiary_bps(),
+            change_delay_epochs: self.effective_fee_change_delay(),
+            pending_change_delay: self
+                .data()
+                .pending_fee_change_delay
+                .clone()
+                .filter(|pending| pending.effective_epoch > get_epoch_height()),
+        }
+    }
+
+    /// Returns the reward fee that will be in effect once the
+    /// announced fee config applies, if there is one.
+    pub fn get_pending_reward_fee_fraction(&self) -> Option<RewardFeeFraction> {
+        self.data()
+            .pending_fee_config
+            .as_ref()
+            .filter(|pending| pending.effective_epoch > get_epoch_height())
+            .map(|pending| RewardFeeFraction {
+                numerator: self.internal_beneficiary_bps() + pending.config.reward_fee_bps(),
+                denominator: FULL_BASIS_POINTS,
+            })
+    }
+}
@@ -0,0 +1,204 @@
+use crate::*;
+
+/// Limits of NEAR flowing in and out of the protocol. None means no limit.
+#[near(serializers = [borsh, json])]
+#[derive(Clone, Default)]
+pub struct FlowLimitConfig {
+    /// Max total staked NEAR, checked on stake
+    pub tvl_cap: Option<U128>,
+    /// Max NEAR staked within an epoch
+    pub epoch_deposit_limit: Option<U128>,
+    /// Max NEAR unstaked within an epoch. The excess is deferred to the following epochs.
+    pub epoch_unstake_limit: Option<U128>,
+}
+
+#[near(serializers = [borsh])]
+#[derive(Default)]
+pub struct FlowLimits {
+    pub config: FlowLimitConfig,
+    /// The epoch of the flow counters
+    pub epoch: EpochHeight,
+    pub epoch_deposited: u128,
+    pub epoch_unstaked: u128,
+    /// Unstaked NEAR above the limit, waiting for the following epochs
+    pub deferred_unstake_amount: u128,
+    /// Deferred unstakes form a FIFO stream: the total deferred so far,
+    /// the total released so far, and the total released as of the last
+    /// epoch cleanup, whose unstakes are settled by validators.
+    pub total_deferred_amount: u128,
+    pub total_released_amount: u128,
+    pub settled_released_amount: u128,
+}
+
+impl FlowLimits {
+    pub fn current_epoch_deposited(&self) -> u128 {
+        if self.epoch == get_epoch_height() {
+            self.epoch_deposited
+        } else {
+            0
+        }
+    }
+
+    pub fn current_epoch_unstaked(&self) -> u128 {
+        if self.epoch == get_epoch_height() {
+            self.epoch_unstaked
+        } else {
+            0
+        }
+    }
+
+    pub fn deposit_headroom(&self) -> Option<u128> {
+        self.config
+            .epoch_deposit_limit
+            .map(|limit| limit.0.saturating_sub(self.current_epoch_deposited()))
+    }
+
+    /// Deferred unstakes that fit in the unstake limit of the current epoch
+    pub fn releasable_deferred_unstake_amount(&self) -> u128 {
+        match self.config.epoch_unstake_limit {
+            Some(limit) => min(
+                self.deferred_unstake_amount,
+                limit.0.saturating_sub(self.current_epoch_unstaked()),
+            ),
+            None => self.deferred_unstake_amount,
+        }
+    }
+
+    /// Unstake headroom of the current epoch, after deferred unstakes are released
+    pub fn unstake_headroom(&self) -> Option<u128> {
+        self.config.epoch_unstake_limit.map(|limit| {
+            limit.0.saturating_sub(
+                self.current_epoch_unstaked() + self.releasable_deferred_unstake_amount(),
+            )
+        })
+    }
+
+    /// Whether the deferred unstakes up to the position are released and settled
+    pub fn is_deferred_unstake_settled(&self, position: u128) -> bool {
+        self.settled_released_amount >= position
+    }
+
+    fn roll_epoch(&mut self) {
+        let current_epoch = get_epoch_height();
+        if self.epoch != current_epoch {
+            self.epoch = current_epoch;
+            self.epoch_deposited = 0;
+            self.epoch_unstaked = 0;
+        }
+    }
+}
+
+#[near(serializers = [json])]
+pub struct FlowLimitsView {
+    pub config: FlowLimitConfig,
+    pub total_staked: U128,
+    /// NEAR that can still be staked before hitting the TVL cap
+    pub tvl_headroom: Option<U128>,
+    pub epoch_deposited: U128,
+    /// NEAR that can still be staked in the current epoch
+    pub deposit_headroom: Option<U128>,
+    pub epoch_unstaked: U128,
+    /// NEAR that can still be unstaked in the current epoch without being deferred
+    pub unstake_headroom: Option<U128>,
+    pub deferred_unstake_amount: U128,
+}
+
+impl Contract {
+    /// Check the TVL cap and the epoch deposit limit before staking the amount.
+    pub(crate) fn internal_check_deposit_limits(&mut self, amount: u128) {
+        if let Some(tvl_cap) = self.data().flow_limits.config.tvl_cap {
+            require!(
+                self.data().total_staked_asset_in_near + amount <= tvl_cap.0,
+                ERR_TVL_CAP_EXCEEDED
+            );
+        }
+        if let Some(headroom) = self.data().flow_limits.deposit_headroom() {
+            require!(amount <= headroom, ERR_EPOCH_DEPOSIT_LIMIT_EXCEEDED);
+        }
+        let flow_limits = &mut self.data_mut().flow_limits;
+        flow_limits.roll_epoch();
+        flow_limits.epoch_deposited += amount;
+    }
+
+    /// Move deferred unstakes into the current epoch as far as the limit allows.
+    pub(crate) fn internal_release_deferred_unstakes(&mut self) {
+        let amount = self.data().flow_limits.releasable_deferred_unstake_amount();
+        if amount == 0 {
+            return;
+        }
+        let flow_limits = &mut self.data_mut().flow_limits;
+        flow_limits.roll_epoch();
+        flow_limits.epoch_unstaked += amount;
+        flow_limits.deferred_unstake_amount -= amount;
+        flow_limits.total_released_amount += amount;
+        self.data_mut().epoch_requested_unstake_amount += amount;
+
+        Event::DeferredUnstakeReleased {
+            amount: &U128(amount),
+            deferred_unstake_amount: &U128(self.data().flow_limits.deferred_unstake_amount),
+        }
+        .emit();
+    }
+
+    /// Split the unstake amount into the part within the epoch unstake limit
+    /// and the deferred excess. Returns the amount within the limit.
+    pub(crate) fn internal_apply_unstake_limit(&mut self, amount: u128) -> u128 {
+        self.internal_release_deferred_unstakes();
+        let accepted_amount = min(
+            amount,
+            self.data().flow_limits.unstake_headroom().unwrap_or(amount),
+        );
+        let flow_limits = &mut self.data_mut().flow_limits;
+        flow_limits.roll_epoch();
+        flow_limits.epoch_unstaked += accepted_amount;
+        flow_limits.deferred_unstake_amount += amount - accepted_amount;
+        flow_limits.total_deferred_amount += amount - accepted_amount;
+        accepted_amount
+    }
+}
+
+#[near]
+impl Contract {
+    #[payable]
+    #[access_control_any(roles(Role::DAO))]
+    #[pause]
+    pub fn set_flow_limits(&mut self, config: FlowLimitConfig) {
+        assert_one_yocto();
+        // pause deposits or unstakes to stop them entirely
+        require!(
+            [
+                config.tvl_cap,
+                config.epoch_deposit_limit,
+                config.epoch_unstake_limit
+            ]
+            .into_iter()
+            .flatten()
+            .all(|limit| limit.0 > 0),
+            ERR_INVALID_FLOW_LIMIT
+        );
+        Event::SetFlowLimits {
+            old_config: &self.data().flow_limits.config,
+            new_config: &config,
+        }
+        .emit();
+        self.data_mut().flow_limits.config = config;
+    }
+
+    pub fn get_flow_limits(&self) -> FlowLimitsView {
+        let flow_limits = &self.data().flow_limits;
+        let total_staked = self.data().total_staked_asset_in_near;
+        FlowLimitsView {
+            config: flow_limits.config.clone(),
+            total_staked: total_staked.into(),
+            tvl_headroom: flow_limits
+                .config
+                .tvl_cap
+                .map(|tvl_cap| tvl_cap.0.saturating_sub(total_staked).into()),
+            epoch_deposited: flow_limits.current_epoch_deposited().into(),
+            deposit_headroom: flow_limits.deposit_headroom().map(U128),
+            epoch_unstaked: flow_limits.current_epoch_unstaked().into(),
+            unstake_headroom: flow_limits.unstake_headroom().map(U128),
+            deferred_unstake_amount: flow_limits.deferred_unstake_amount.into(),
+        }
+    }
+}
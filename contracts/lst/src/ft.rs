@@ -4,7 +4,12 @@ use crate::*;
 impl FungibleTokenCore for Contract {
     #[payable]
     fn ft_transfer(&mut self, receiver_id: AccountId, amount: U128, memo: Option<String>) {
-        self.data_mut().token.ft_transfer(receiver_id, amount, memo)
+        let sender_id = env::predecessor_account_id();
+        self.data_mut()
+            .token
+            .ft_transfer(receiver_id.clone(), amount, memo);
+        self.internal_on_lst_balance_changed(&sender_id);
+        self.internal_on_lst_balance_changed(&receiver_id);
     }
 
     #[payable]
@@ -15,7 +20,14 @@ impl FungibleTokenCore for Contract {
         memo: Option<String>,
         msg: String,
     ) -> PromiseOrValue<U128> {
-        self.data_mut().token.ft_transfer_call(receiver_id, amount, memo, msg)
+        let sender_id = env::predecessor_account_id();
+        let result = self
+            .data_mut()
+            .token
+            .ft_transfer_call(receiver_id.clone(), amount, memo, msg);
+        self.internal_on_lst_balance_changed(&sender_id);
+        self.internal_on_lst_balance_changed(&receiver_id);
+        result
     }
 
     fn ft_total_supply(&self) -> U128 {
@@ -36,9 +48,13 @@ impl FungibleTokenResolver for Contract {
         receiver_id: AccountId,
         amount: U128,
     ) -> U128 {
-        let (used_amount, burned_amount) =
-            self.data_mut().token
-                .internal_ft_resolve_transfer(&sender_id, receiver_id, amount);
+        let (used_amount, burned_amount) = self.data_mut().token.internal_ft_resolve_transfer(
+            &sender_id,
+            receiver_id.clone(),
+            amount,
+        );
+        self.internal_on_lst_balance_changed(&sender_id);
+        self.internal_on_lst_balance_changed(&receiver_id);
         if burned_amount > 0 {
             log!("Account @{} burned {}", sender_id, burned_amount);
         }
@@ -16,10 +16,50 @@ impl Contract {
             .collect()
     }
 
+    /// Add rewards to the total staked amount, vest and distribute them.
+    /// The part paid out in LST is not vested, so it is minted at a price
+    /// that includes it, and minting it doesn't move the share price.
+    pub(crate) fn internal_credit_staking_rewards(&mut self, rewards: u128) {
+        let paid_amount = self.staking_rewards_paid_amount(rewards);
+        self.data_mut().total_staked_asset_in_near += rewards;
+        self.data_mut()
+            .reward_vesting
+            .add_rewards(rewards - paid_amount, env::block_timestamp());
+        self.internal_distribute_staking_rewards(rewards);
+    }
+
+    /// The part of the rewards paid to keepers, beneficiaries and fee recipients
+    fn staking_rewards_paid_amount(&self, rewards: u128) -> u128 {
+        let fee_bps = self.internal_get_beneficiaries().values().sum::<u32>()
+            + self.effective_fee_config().reward_fee_bps();
+        bps_mul(rewards, self.data().keeper_rewards.config.reward_bps) + bps_mul(rewards, fee_bps)
+    }
+
     /// When there are rewards, a part of them will be
     /// given to executor, manager or treasury by minting new LST tokens.
     pub(crate) fn internal_distribute_staking_rewards(&mut self, rewards: u128) {
-        let hashmap = self.internal_get_beneficiaries();
+        self.internal_fund_keeper_rewards(rewards);
+
+        let fee_config = self.effective_fee_config();
+        let mut hashmap = self.internal_get_beneficiaries();
+        for recipient in fee_config.reward_fee_recipients() {
+            *hashmap.entry(recipient.account_id.clone()).or_insert(0) += recipient.bps;
+        }
+
+        // referrers take their part out of the protocol fee
+        let referral_bps = self.referral_reward_bps(&fee_config);
+        let referral_reward_shares = if referral_bps > 0 {
+            let protocol_fee = fee_config.protocol_fee.as_ref().unwrap();
+            let protocol_bps = hashmap.get_mut(&protocol_fee.account_id).unwrap();
+            *protocol_bps -= referral_bps;
+            if *protocol_bps == 0 {
+                hashmap.remove(&protocol_fee.account_id);
+            }
+            self.num_shares_from_staked_amount_rounded_down(bps_mul(rewards, referral_bps))
+        } else {
+            0
+        };
+
         let total_bps = hashmap.values().sum::<u32>();
         if total_bps > 0 {
             let total_reward_near_amount = bps_mul(rewards, total_bps);
@@ -30,20 +70,18 @@ impl Contract {
             while let Some((account_id, bps)) = hashmap_iter.next() {
                 if hashmap_iter.peek().is_none() {
                     if remain_reward_shares > 0 {
-                        self.mint_lst(
-                            account_id,
-                            remain_reward_shares,
-                            Some("beneficiary rewards"),
-                        );
+                        self.internal_pay_beneficiary(account_id, remain_reward_shares);
                     }
                 } else {
                     let reward_shares = total_reward_shares * *bps as u128 / total_bps as u128;
                     if reward_shares > 0 {
-                        self.mint_lst(account_id, reward_shares, Some("beneficiary rewards"));
+                        self.internal_pay_beneficiary(account_id, reward_shares);
                         remain_reward_shares -= reward_shares;
                     }
                 }
             }
         }
+
+        self.internal_distribute_referral_rewards(referral_reward_shares);
     }
 }
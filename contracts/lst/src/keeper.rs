@@ -0,0 +1,168 @@
+use crate::*;
+
+/// Max part of staking rewards that can fund keeper rewards
+pub const MAX_KEEPER_REWARD_BPS: u32 = 500;
+
+#[near(serializers = [borsh, json])]
+#[derive(Clone, Default)]
+pub struct KeeperRewardConfig {
+    /// Part of staking rewards set aside for keepers, in bps
+    pub reward_bps: u32,
+    /// NEAR value paid for each useful epoch action
+    pub reward_per_action: U128,
+    /// Max NEAR value paid to all keepers within an epoch
+    pub max_reward_per_epoch: U128,
+}
+
+#[near(serializers = [json])]
+pub struct KeeperRewardInfo {
+    pub config: KeeperRewardConfig,
+    /// LST held by the contract to pay keepers
+    pub reward_pool_shares: U128,
+    /// NEAR value paid to keepers in the current epoch
+    pub epoch_paid_amount: U128,
+}
+
+/// Keeper reward state
+#[near(serializers = [borsh])]
+pub struct KeeperRewards {
+    pub config: KeeperRewardConfig,
+    pub reward_pool_shares: u128,
+    pub epoch: EpochHeight,
+    pub epoch_paid_amount: u128,
+    /// Total LST earned by each keeper
+    pub earnings: LookupMap<AccountId, u128>,
+}
+
+impl Default for KeeperRewards {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl KeeperRewards {
+    pub fn new() -> Self {
+        Self {
+            config: KeeperRewardConfig::default(),
+            reward_pool_shares: 0,
+            epoch: 0,
+            epoch_paid_amount: 0,
+            earnings: LookupMap::new(StorageKey::KeeperEarnings),
+        }
+    }
+}
+
+impl Contract {
+    /// Set aside the keeper part of staking rewards by minting LST to the contract.
+    pub(crate) fn internal_fund_keeper_rewards(&mut self, rewards: u128) {
+        let reward_amount = bps_mul(rewards, self.data().keeper_rewards.config.reward_bps);
+        let reward_shares = self.num_shares_from_staked_amount_rounded_down(reward_amount);
+        if reward_shares > 0 {
+            self.mint_lst(
+                &env::current_account_id(),
+                reward_shares,
+                Some("keeper rewards"),
+            );
+            self.data_mut().keeper_rewards.reward_pool_shares += reward_shares;
+        }
+    }
+
+    /// Pay the keeper for the epoch actions it started once they succeeded.
+    /// Keepers must be registered on the LST contract to be paid.
+    pub(crate) fn internal_reward_keeper(&mut self, keeper_id: &AccountId, num_actions: u32) {
+        if num_actions == 0
+            || *keeper_id == env::current_account_id()
+            || self.data().token.accounts.get(keeper_id).is_none()
+        {
+            return;
+        }
+
+        let current_epoch = get_epoch_height();
+        if self.data().keeper_rewards.epoch != current_epoch {
+            self.data_mut().keeper_rewards.epoch = current_epoch;
+            self.data_mut().keeper_rewards.epoch_paid_amount = 0;
+        }
+
+        let config = self.data().keeper_rewards.config.clone();
+        let amount = min(
+            config.reward_per_action.0 * num_actions as u128,
+            config
+                .max_reward_per_epoch
+                .0
+                .saturating_sub(self.data().keeper_rewards.epoch_paid_amount),
+        );
+        let shares = min(
+            self.num_shares_from_staked_amount_rounded_down(amount),
+            self.data().keeper_rewards.reward_pool_shares,
+        );
+        if shares == 0 {
+            return;
+        }
+        let amount = self.staked_amount_from_num_shares_rounded_down(shares);
+
+        self.data_mut().token.internal_transfer(
+            &env::current_account_id(),
+            keeper_id,
+            shares,
+            Some("keeper reward".to_string()),
+        );
+        self.internal_on_lst_balance_changed(&env::current_account_id());
+        self.internal_on_lst_balance_changed(keeper_id);
+
+        let keeper_rewards = &mut self.data_mut().keeper_rewards;
+        keeper_rewards.reward_pool_shares -= shares;
+        keeper_rewards.epoch_paid_amount += amount;
+        let earned_shares = keeper_rewards.earnings.get(keeper_id).copied().unwrap_or(0);
+        keeper_rewards
+            .earnings
+            .insert(keeper_id.clone(), earned_shares + shares);
+
+        Event::KeeperRewarded {
+            keeper_id,
+            num_actions,
+            shares: &U128(shares),
+            amount: &U128(amount),
+        }
+        .emit();
+    }
+}
+
+#[near]
+impl Contract {
+    #[payable]
+    #[access_control_any(roles(Role::DAO))]
+    #[pause]
+    pub fn set_keeper_reward_config(&mut self, config: KeeperRewardConfig) {
+        assert_one_yocto();
+        require!(
+            config.reward_bps <= MAX_KEEPER_REWARD_BPS,
+            ERR_KEEPER_REWARD_BPS_TOO_HIGH
+        );
+        Event::SetKeeperRewardConfig { config: &config }.emit();
+        self.data_mut().keeper_rewards.config = config;
+    }
+
+    pub fn get_keeper_reward_info(&self) -> KeeperRewardInfo {
+        let keeper_rewards = &self.data().keeper_rewards;
+        KeeperRewardInfo {
+            config: keeper_rewards.config.clone(),
+            reward_pool_shares: keeper_rewards.reward_pool_shares.into(),
+            epoch_paid_amount: if keeper_rewards.epoch == get_epoch_height() {
+                keeper_rewards.epoch_paid_amount.into()
+            } else {
+                U128(0)
+            },
+        }
+    }
+
+    /// Returns the total LST earned by the keeper
+    pub fn get_keeper_earnings(&self, account_id: AccountId) -> U128 {
+        self.data()
+            .keeper_rewards
+            .earnings
+            .get(&account_id)
+            .copied()
+            .unwrap_or(0)
+            .into()
+    }
+}
@@ -19,25 +19,44 @@ use near_sdk::{
     log, near, require,
     serde::{Deserialize, Serialize},
     serde_json,
-    store::{IterableMap, LazyOption},
-    AccountId, BorshStorageKey, EpochHeight, Gas, NearToken, PanicOnDefault, Promise, PromiseError,
-    PromiseOrValue, PromiseResult, PublicKey, StorageUsage,
+    store::{IterableMap, LazyOption, LookupMap},
+    AccountId, BlockHeight, BorshStorageKey, EpochHeight, Gas, NearToken, PanicOnDefault, Promise,
+    PromiseError, PromiseOrValue, PromiseResult, PublicKey, StorageUsage, Timestamp,
 };
 use std::cmp::min;
 use std::collections::HashMap;
 
 mod account;
+mod accounting;
+mod approvals;
+mod beneficiary;
 mod big_decimal;
 mod burrow;
+mod config;
+mod directed_stake;
+mod emergency;
 mod epoch_actions;
 mod errors;
 mod event;
+mod fees;
+mod flow_limits;
 mod ft;
 mod internal;
+mod keeper;
+mod matching;
 mod owner;
+mod partners;
+mod pause;
+mod price_history;
+mod redelegation;
+mod referral;
+mod reward_cap;
+mod reward_vesting;
+mod ring_buffer;
 mod rnear;
 mod stake_pool_itf;
 mod storage;
+mod timelock;
 mod upgrade;
 mod utils;
 mod validator;
@@ -45,11 +64,33 @@ mod validator_pool;
 mod view;
 
 pub use account::*;
+pub use accounting::*;
+pub use approvals::*;
+pub use beneficiary::*;
 pub use big_decimal::*;
 pub use burrow::*;
+pub use config::*;
+pub use directed_stake::*;
+pub use emergency::*;
+pub use epoch_actions::*;
 pub use errors::*;
 pub use event::*;
+pub use fees::*;
+pub use flow_limits::*;
+pub use keeper::*;
+pub use matching::*;
+pub use owner::*;
+pub use partners::*;
+pub use pause::*;
+pub use price_history::*;
+pub use redelegation::*;
+pub use referral::*;
+pub use reward_cap::*;
+pub use reward_vesting::*;
+pub use ring_buffer::*;
 pub use rnear::*;
+pub use timelock::*;
+pub use upgrade::*;
 pub use utils::*;
 pub use validator::*;
 pub use validator_pool::*;
@@ -73,6 +114,21 @@ enum StorageKey {
     Accounts,
     Beneficiaries,
     Validators,
+    UnstakeQueue,
+    VersionedAccounts,
+    DirectedStakes,
+    Partners,
+    Redelegations,
+    KeeperEarnings,
+    BeneficiaryAccounts,
+    BeneficiaryPayoutHistory,
+    Referrers,
+    ReferredAccounts,
+    PriceHistory,
+    QuarantinedRewards,
+    TimelockDelays,
+    TimelockQueue,
+    ApprovalProposals,
 }
 
 #[near(serializers = [borsh])]
@@ -81,7 +137,9 @@ pub struct ContractData {
     pub metadata: LazyOption<FungibleTokenMetadata>,
     owner_id: AccountId,
     total_staked_asset_in_near: Balance,
-    accounts: IterableMap<AccountId, Account>,
+    accounts: IterableMap<AccountId, VersionedAccount>,
+    /// Accounts stored by v1.0.1, moved into `accounts` when saved
+    legacy_accounts: IterableMap<AccountId, AccountV0>,
     account_storage_usage: StorageUsage,
     beneficiaries: IterableMap<AccountId, u32>,
     validator_pool: ValidatorPool,
@@ -97,11 +155,58 @@ pub struct ContractData {
     unstake_amount_to_settle: u128,
     last_settlement_epoch: EpochHeight,
     last_settlement_initiated_epoch: EpochHeight,
+    /// NEAR held by the contract for users waiting to withdraw,
+    /// which must never be restaked.
+    withdrawal_reserve: u128,
+    /// NEAR paid out of the unstaked balances before it was set aside in the reserve,
+    /// settled by the next NEAR that is.
+    withdrawal_reserve_advance: u128,
+    /// Unstake requests waiting to be funded by new deposits
+    unstake_queue: UnstakeQueue,
+    /// Validator preferences of LST holders
+    directed_stakes: LookupMap<AccountId, DirectedStake>,
+    /// Partner accounts managing the base stake of validators
+    partners: IterableMap<AccountId, Partner>,
+    redelegations: IterableMap<u64, Redelegation>,
+    next_redelegation_id: u64,
+    /// NEAR withdrawn from redelegation sources and not yet staked on the targets
+    redelegation_in_transit_amount: u128,
+    /// Rewards paid to accounts running epoch actions
+    keeper_rewards: KeeperRewards,
+    fee_config: FeeConfig,
+    /// Announced fee config waiting for the change delay
+    pending_fee_config: Option<PendingFeeConfig>,
+    fee_change_delay_epochs: EpochHeight,
+    /// Announced fee change delay waiting for the current delay
+    pending_fee_change_delay: Option<PendingFeeChangeDelay>,
+    /// Payout choices and earnings of beneficiaries
+    beneficiary_accounts: IterableMap<AccountId, BeneficiaryAccount>,
+    beneficiary_payout_history: RingBuffer<BeneficiaryPayoutRecord>,
+    /// Rewards excluded from the share price until vested
+    reward_vesting: RewardVesting,
+    referrals: Referrals,
+    price_history: RingBuffer<PriceSample>,
+    reward_cap: RewardCap,
+    owner_proposal: Option<OwnerProposal>,
+    timelock: Timelock,
+    /// Wind-down state, set once emergency mode is enabled
+    emergency: Option<EmergencyState>,
+    flow_limits: FlowLimits,
+    /// Manual accounting adjustments by the Accountant
+    accounting: Accounting,
+    /// Tunable parameters, validated on update
+    config: VersionedConfig,
+    /// Operator actions waiting for approvals
+    approvals: Approvals,
+    /// Withdrawals of matured unstaked NEAR stay open while paused
+    exit_only_mode: bool,
 }
 
 #[near(serializers = [borsh])]
 pub enum VersionedContractData {
-    Current(ContractData),
+    /// State of v1.0.1, converted by `migrate_state`
+    V0(ContractDataV0),
+    V1(ContractData),
 }
 
 #[derive(AccessControlRole, Deserialize, Serialize, Copy, Clone)]
@@ -115,6 +220,8 @@ pub enum Role {
     OpManager,
     Accountant,
     Strategist,
+    BeneficiaryManager,
+    WhitelistManager,
 }
 
 #[near(contract_state)]
@@ -143,7 +250,7 @@ impl Contract {
         burrow_contract_id: Option<AccountId>,
     ) -> Self {
         let mut contract = Self {
-            data: VersionedContractData::Current(ContractData {
+            data: VersionedContractData::V1(ContractData {
                 token: FungibleToken::new(StorageKey::FungibleToken),
                 metadata: LazyOption::new(
                     StorageKey::Metadata,
@@ -159,7 +266,8 @@ impl Contract {
                 ),
                 owner_id: owner_id.clone(),
                 total_staked_asset_in_near: 0,
-                accounts: IterableMap::new(StorageKey::Accounts),
+                accounts: IterableMap::new(StorageKey::VersionedAccounts),
+                legacy_accounts: IterableMap::new(StorageKey::Accounts),
                 account_storage_usage: 0,
                 beneficiaries: IterableMap::new(StorageKey::Beneficiaries),
                 validator_pool: ValidatorPool::new(),
@@ -180,6 +288,36 @@ impl Contract {
                 unstake_amount_to_settle: 0,
                 last_settlement_epoch: 0,
                 last_settlement_initiated_epoch: 0,
+                withdrawal_reserve: 0,
+                withdrawal_reserve_advance: 0,
+                unstake_queue: UnstakeQueue::new(),
+                directed_stakes: LookupMap::new(StorageKey::DirectedStakes),
+                partners: IterableMap::new(StorageKey::Partners),
+                redelegations: IterableMap::new(StorageKey::Redelegations),
+                next_redelegation_id: 0,
+                redelegation_in_transit_amount: 0,
+                keeper_rewards: KeeperRewards::new(),
+                fee_config: FeeConfig::default(),
+                pending_fee_config: None,
+                fee_change_delay_epochs: DEFAULT_FEE_CHANGE_DELAY_EPOCHS,
+                pending_fee_change_delay: None,
+                beneficiary_accounts: IterableMap::new(StorageKey::BeneficiaryAccounts),
+                beneficiary_payout_history: RingBuffer::new(
+                    StorageKey::BeneficiaryPayoutHistory,
+                    BENEFICIARY_PAYOUT_HISTORY_SIZE,
+                ),
+                reward_vesting: RewardVesting::default(),
+                referrals: Referrals::new(),
+                price_history: RingBuffer::new(StorageKey::PriceHistory, PRICE_HISTORY_SIZE),
+                reward_cap: RewardCap::new(),
+                owner_proposal: None,
+                timelock: Timelock::new(),
+                emergency: None,
+                flow_limits: FlowLimits::default(),
+                accounting: Accounting::default(),
+                config: VersionedConfig::V1(Config::default()),
+                approvals: Approvals::new(),
+                exit_only_mode: false,
             }),
         };
 
@@ -189,10 +327,9 @@ impl Contract {
         contract.acl_init_super_admin(env::predecessor_account_id());
         contract.acl_add_super_admin(owner_id.clone());
 
-        contract.acl_grant_role(Role::DAO.into(), owner_id.clone());
-        contract.acl_grant_role(Role::PauseManager.into(), owner_id.clone());
-        contract.acl_grant_role(Role::UnpauseManager.into(), owner_id.clone());
-        contract.acl_grant_role(Role::OpManager.into(), owner_id.clone());
+        for role in OWNER_ROLES {
+            contract.acl_grant_role(role.into(), owner_id.clone());
+        }
 
         contract
     }
@@ -224,7 +361,7 @@ impl Contract {
                 + env::storage_usage()
                 - initial_storage_usage;
         }
-        self.data_mut().accounts.remove(&tmp_account_id);
+        self.internal_remove_account(&tmp_account_id);
     }
 
     fn init_staking(&mut self) {
@@ -250,7 +387,7 @@ impl Contract {
     #[allow(unreachable_patterns)]
     fn data(&self) -> &ContractData {
         match &self.data {
-            VersionedContractData::Current(data) => data,
+            VersionedContractData::V1(data) => data,
             _ => unimplemented!(),
         }
     }
@@ -258,7 +395,7 @@ impl Contract {
     #[allow(unreachable_patterns)]
     fn data_mut(&mut self) -> &mut ContractData {
         match &mut self.data {
-            VersionedContractData::Current(data) => data,
+            VersionedContractData::V1(data) => data,
             _ => unimplemented!(),
         }
     }
@@ -273,13 +410,10 @@ impl Contract {
     /// amount * total_shares = total_staked * num_shares
     /// num_shares = amount * total_shares / total_staked
     pub(crate) fn num_shares_from_staked_amount_rounded_down(&self, amount: u128) -> ShareBalance {
-        require!(
-            self.data().total_staked_asset_in_near > 0,
-            ERR_NON_POSITIVE_TOTAL_STAKED_BALANCE
-        );
-        (U256::from(self.data().token.total_supply) * U256::from(amount)
-            / U256::from(self.data().total_staked_asset_in_near))
-        .as_u128()
+        let total_staked = self.vested_total_staked_amount();
+        require!(total_staked > 0, ERR_NON_POSITIVE_TOTAL_STAKED_BALANCE);
+        (U256::from(self.data().token.total_supply) * U256::from(amount) / U256::from(total_staked))
+            .as_u128()
     }
 
     /// Returns the number of "stake" shares rounded up corresponding to the given staked balance
@@ -287,13 +421,11 @@ impl Contract {
     ///
     /// Rounding up division of `a / b` is done using `(a + b - 1) / b`.
     pub(crate) fn num_shares_from_staked_amount_rounded_up(&self, amount: u128) -> ShareBalance {
-        require!(
-            self.data().total_staked_asset_in_near > 0,
-            ERR_NON_POSITIVE_TOTAL_STAKED_BALANCE
-        );
+        let total_staked = self.vested_total_staked_amount();
+        require!(total_staked > 0, ERR_NON_POSITIVE_TOTAL_STAKED_BALANCE);
         ((U256::from(self.data().token.total_supply) * U256::from(amount)
-            + U256::from(self.data().total_staked_asset_in_near - 1))
-            / U256::from(self.data().total_staked_asset_in_near))
+            + U256::from(total_staked - 1))
+            / U256::from(total_staked))
         .as_u128()
     }
 
@@ -306,7 +438,7 @@ impl Contract {
             self.data().token.total_supply > 0,
             ERR_NON_POSITIVE_TOTAL_STAKE_SHARES
         );
-        (U256::from(self.data().total_staked_asset_in_near) * U256::from(num_shares)
+        (U256::from(self.vested_total_staked_amount()) * U256::from(num_shares)
             / U256::from(self.data().token.total_supply))
         .as_u128()
     }
@@ -320,6 +452,7 @@ impl Contract {
             self.data_mut().token.internal_register_account(account_id);
         }
         self.data_mut().token.internal_deposit(account_id, shares);
+        self.internal_on_lst_balance_changed(account_id);
         FtMint {
             owner_id: account_id,
             amount: U128(shares),
@@ -332,6 +465,7 @@ impl Contract {
         require!(shares > 0, ERR_NON_POSITIVE_SHARES);
         // burn from account
         self.data_mut().token.internal_withdraw(account_id, shares);
+        self.internal_on_lst_balance_changed(account_id);
         FtBurn {
             owner_id: account_id,
             amount: U128(shares),
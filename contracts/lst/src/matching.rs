@@ -0,0 +1,206 @@
+use crate::*;
+
+/// Max number of queued unstake requests visited in one matching pass
+const MAX_MATCHING_ITERATIONS: u32 = 20;
+
+#[near(serializers = [borsh])]
+#[derive(Clone)]
+pub struct UnstakeRequest {
+    pub account_id: AccountId,
+    /// The amount of NEAR that is not matched yet
+    pub amount: u128,
+    /// The settlement epoch when the request was queued.
+    /// Once the epoch cleanup runs again, the request is
+    /// settled by validators and can no longer be matched.
+    pub settlement_epoch: EpochHeight,
+}
+
+#[near(serializers = [json])]
+pub struct UnstakeRequestView {
+    pub request_id: u64,
+    pub account_id: AccountId,
+    pub amount: U128,
+}
+
+/// FIFO queue of unstake requests waiting to be matched against deposits
+#[near(serializers = [borsh])]
+pub struct UnstakeQueue {
+    requests: LookupMap<u64, UnstakeRequest>,
+    head: u64,
+    tail: u64,
+}
+
+impl Default for UnstakeQueue {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl UnstakeQueue {
+    pub fn new() -> Self {
+        Self {
+            requests: LookupMap::new(StorageKey::UnstakeQueue),
+            head: 0,
+            tail: 0,
+        }
+    }
+
+    pub fn len(&self) -> u64 {
+        self.tail - self.head
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn push(&mut self, request: UnstakeRequest) -> u64 {
+        let request_id = self.tail;
+        self.requests.insert(request_id, request);
+        self.tail += 1;
+        request_id
+    }
+
+    pub fn front(&self) -> Option<(u64, UnstakeRequest)> {
+        if self.is_empty() {
+            return None;
+        }
+        self.requests
+            .get(&self.head)
+            .map(|request| (self.head, request.clone()))
+    }
+
+    pub fn pop_front(&mut self) {
+        if !self.is_empty() {
+            self.requests.remove(&self.head);
+            self.head += 1;
+        }
+    }
+
+    pub fn update_front(&mut self, request: UnstakeRequest) {
+        require!(!self.is_empty());
+        self.requests.insert(self.head, request);
+    }
+
+    pub fn get_requests(
+        &self,
+        from_index: Option<u64>,
+        limit: Option<u64>,
+    ) -> Vec<UnstakeRequestView> {
+        let skip_n = from_index.unwrap_or(0);
+        let take_n = limit.unwrap_or(self.len().saturating_sub(skip_n));
+        (self.head.saturating_add(skip_n)..self.tail)
+            .take(take_n as usize)
+            .filter_map(|request_id| {
+                self.requests
+                    .get(&request_id)
+                    .map(|request| UnstakeRequestView {
+                        request_id,
+                        account_id: request.account_id.clone(),
+                        amount: request.amount.into(),
+                    })
+            })
+            .collect()
+    }
+}
+
+impl Contract {
+    /// Match a new unstake request against the NEAR deposited for staking
+    /// within the current epoch. Returns the matched amount.
+    pub(crate) fn internal_match_unstake(&mut self, amount: u128) -> u128 {
+        let matched_amount = min(amount, self.data().epoch_requested_stake_amount);
+        if matched_amount > 0 {
+            self.internal_settle_matched_amount(matched_amount);
+        }
+        matched_amount
+    }
+
+    /// Fund queued unstake requests in FIFO order with NEAR deposited for staking.
+    /// Returns the matched amount.
+    pub(crate) fn internal_match_stake(&mut self, amount: u128) -> u128 {
+        let mut remaining_amount = amount;
+        let mut iterations = 0;
+        while remaining_amount > 0 && iterations < MAX_MATCHING_ITERATIONS {
+            iterations += 1;
+            let Some((request_id, mut request)) = self.data().unstake_queue.front() else {
+                break;
+            };
+            // requests already rolled into the settlement can't be matched
+            if request.settlement_epoch != self.data().last_settlement_epoch {
+                self.data_mut().unstake_queue.pop_front();
+                continue;
+            }
+
+            let matched_amount = min(remaining_amount, request.amount);
+            let mut account = self.internal_get_account(&request.account_id);
+            account.matched_unstaked += matched_amount;
+            self.internal_save_account(&request.account_id, &account);
+
+            request.amount -= matched_amount;
+            remaining_amount -= matched_amount;
+
+            Event::UnstakeMatched {
+                account_id: &request.account_id,
+                request_id: Some(request_id),
+                matched_amount: &U128(matched_amount),
+                unmatched_amount: &U128(request.amount),
+            }
+            .emit();
+
+            if request.amount == 0 {
+                self.data_mut().unstake_queue.pop_front();
+            } else {
+                self.data_mut().unstake_queue.update_front(request);
+            }
+        }
+
+        let matched_amount = amount - remaining_amount;
+        if matched_amount > 0 {
+            self.internal_settle_matched_amount(matched_amount);
+        }
+        matched_amount
+    }
+
+    /// Queue the unmatched part of an unstake request. Returns the request ID.
+    pub(crate) fn internal_queue_unstake(&mut self, account_id: &AccountId, amount: u128) -> u64 {
+        let settlement_epoch = self.data().last_settlement_epoch;
+        self.data_mut().unstake_queue.push(UnstakeRequest {
+            account_id: account_id.clone(),
+            amount,
+            settlement_epoch,
+        })
+    }
+
+    /// Drop requests that have been rolled into the settlement by epoch cleanup.
+    pub(crate) fn internal_prune_unstake_queue(&mut self) {
+        for _ in 0..MAX_MATCHING_ITERATIONS {
+            match self.data().unstake_queue.front() {
+                Some((_, request))
+                    if request.settlement_epoch != self.data().last_settlement_epoch =>
+                {
+                    self.data_mut().unstake_queue.pop_front();
+                }
+                _ => break,
+            }
+        }
+    }
+
+    /// Matched NEAR is kept out of validator operations entirely
+    /// and set aside for withdrawals.
+    fn internal_settle_matched_amount(&mut self, amount: u128) {
+        self.data_mut().epoch_requested_stake_amount -= amount;
+        self.data_mut().epoch_requested_unstake_amount -= amount;
+        self.internal_credit_withdrawal_reserve(amount);
+    }
+}
+
+#[near]
+impl Contract {
+    /// Returns the unstake requests waiting to be matched, in FIFO order.
+    pub fn get_unstake_queue(
+        &self,
+        from_index: Option<u64>,
+        limit: Option<u64>,
+    ) -> Vec<UnstakeRequestView> {
+        self.data().unstake_queue.get_requests(from_index, limit)
+    }
+}
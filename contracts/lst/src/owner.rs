@@ -1,27 +1,54 @@
 use crate::*;
 
-#[near]
+/// Default time the proposed owner has to accept the ownership
+pub const DEFAULT_OWNER_PROPOSAL_EXPIRY_SEC: u64 = 7 * 24 * 3600;
+/// Min time the proposed owner has to accept the ownership
+pub const MIN_OWNER_PROPOSAL_EXPIRY_SEC: u64 = 60;
+/// Max time the proposed owner has to accept the ownership
+pub const MAX_OWNER_PROPOSAL_EXPIRY_SEC: u64 = 30 * 24 * 3600;
+
+/// Roles that belong to the owner and move with the ownership
+pub const OWNER_ROLES: [Role; 6] = [
+    Role::DAO,
+    Role::PauseManager,
+    Role::UnpauseManager,
+    Role::OpManager,
+    Role::BeneficiaryManager,
+    Role::WhitelistManager,
+];
+
+#[near(serializers = [borsh, json])]
+#[derive(Clone)]
+pub struct OwnerProposal {
+    pub new_owner_id: AccountId,
+    pub expires_at: U64,
+}
+
 impl Contract {
-    #[payable]
-    pub fn set_owner(&mut self, new_owner_id: AccountId) {
-        self.assert_owner();
-        assert_one_yocto();
-        let old_owner_id = self.data().owner_id.clone();
-        self.data_mut().owner_id = new_owner_id;
-        Event::ChangeOwner {
-            old_owner_id: &old_owner_id,
-            new_owner_id: &self.data().owner_id,
+    /// Move the owner roles and the super admin from the old owner to the new one.
+    fn internal_transfer_owner_roles(
+        &mut self,
+        old_owner_id: &AccountId,
+        new_owner_id: &AccountId,
+    ) {
+        for role in OWNER_ROLES {
+            if self.acl_has_role(role.into(), old_owner_id.clone()) {
+                self.acl_get_or_init()
+                    .revoke_role_unchecked(role, old_owner_id);
+                self.acl_get_or_init()
+                    .grant_role_unchecked(role, new_owner_id);
+            }
+        }
+        if self.acl_is_super_admin(old_owner_id.clone()) {
+            self.acl_get_or_init()
+                .revoke_super_admin_unchecked(old_owner_id);
+            self.acl_get_or_init()
+                .add_super_admin_unchecked(new_owner_id);
         }
-        .emit();
     }
 
-    #[pause]
-    #[payable]
-    pub fn set_beneficiary(&mut self, account_id: AccountId, bps: u32) {
-        self.assert_owner();
-        assert_one_yocto();
-
-        if self.data().beneficiaries.len() == MAX_BENEFICIARIES
+    pub(crate) fn internal_set_beneficiary(&mut self, account_id: AccountId, bps: u32) {
+        if self.data().beneficiaries.len() >= self.internal_config().max_beneficiaries
             && self.data().beneficiaries.get(&account_id).is_none()
         {
             env::panic_str(ERR_TOO_MANY_BENEFICIARIES);
@@ -35,6 +62,10 @@ impl Contract {
             bps_sum - old_value + bps <= FULL_BASIS_POINTS,
             ERR_BPS_SUM_ONE
         );
+        require!(
+            bps_sum - old_value + bps + self.max_scheduled_reward_fee_bps() <= MAX_REWARD_FEE_BPS,
+            ERR_FEE_TOO_HIGH
+        );
 
         Event::SetBeneficiary {
             account_id: &account_id,
@@ -44,11 +75,7 @@ impl Contract {
         self.data_mut().beneficiaries.insert(account_id, bps);
     }
 
-    #[pause]
-    #[payable]
-    pub fn remove_beneficiary(&mut self, account_id: AccountId) {
-        self.assert_owner();
-        assert_one_yocto();
+    pub(crate) fn internal_remove_beneficiary(&mut self, account_id: AccountId) {
         self.data_mut().beneficiaries.remove(&account_id);
         Event::RemoveBeneficiary {
             account_id: &account_id,
@@ -56,12 +83,7 @@ impl Contract {
         .emit();
     }
 
-    /// Set whitelist account ID
-    #[pause]
-    #[payable]
-    pub fn set_whitelist_contract_id(&mut self, account_id: AccountId) {
-        self.assert_owner();
-        assert_one_yocto();
+    pub(crate) fn internal_set_whitelist_contract_id(&mut self, account_id: AccountId) {
         self.data_mut().whitelist_account_id = Some(account_id.clone());
         Event::SetWhitelist {
             account_id: &account_id,
@@ -69,3 +91,115 @@ impl Contract {
         .emit();
     }
 }
+
+#[near]
+impl Contract {
+    /// Propose a new owner, who has to accept the ownership before the proposal expires.
+    /// Replaces any pending proposal.
+    #[payable]
+    pub fn propose_owner(&mut self, new_owner_id: AccountId, expiry_sec: Option<u64>) {
+        self.assert_owner();
+        assert_one_yocto();
+        require!(new_owner_id != self.data().owner_id, ERR_ALREADY_OWNER);
+        let expiry_sec = expiry_sec.unwrap_or(DEFAULT_OWNER_PROPOSAL_EXPIRY_SEC);
+        require!(
+            (MIN_OWNER_PROPOSAL_EXPIRY_SEC..=MAX_OWNER_PROPOSAL_EXPIRY_SEC).contains(&expiry_sec),
+            ERR_INVALID_OWNER_PROPOSAL_EXPIRY
+        );
+        let expires_at = expiry_sec
+            .checked_mul(1_000_000_000)
+            .and_then(|expiry| env::block_timestamp().checked_add(expiry))
+            .expect(ERR_INVALID_OWNER_PROPOSAL_EXPIRY);
+        Event::OwnerProposed {
+            owner_id: &self.data().owner_id,
+            new_owner_id: &new_owner_id,
+            expires_at: expires_at.into(),
+        }
+        .emit();
+        self.data_mut().owner_proposal = Some(OwnerProposal {
+            new_owner_id,
+            expires_at: expires_at.into(),
+        });
+    }
+
+    #[payable]
+    pub fn cancel_owner_proposal(&mut self) {
+        self.assert_owner();
+        assert_one_yocto();
+        let proposal = self
+            .data_mut()
+            .owner_proposal
+            .take()
+            .expect(ERR_NO_OWNER_PROPOSAL);
+        Event::OwnerProposalCancelled {
+            new_owner_id: &proposal.new_owner_id,
+        }
+        .emit();
+    }
+
+    /// Accept the ownership as the proposed owner.
+    /// The owner roles of the old owner are moved to the new owner.
+    #[payable]
+    pub fn accept_owner(&mut self) {
+        assert_one_yocto();
+        let proposal = self
+            .data()
+            .owner_proposal
+            .clone()
+            .expect(ERR_NO_OWNER_PROPOSAL);
+        require!(
+            env::predecessor_account_id() == proposal.new_owner_id,
+            ERR_NOT_PROPOSED_OWNER
+        );
+        require!(
+            env::block_timestamp() <= proposal.expires_at.0,
+            ERR_OWNER_PROPOSAL_EXPIRED
+        );
+
+        let old_owner_id = self.data().owner_id.clone();
+        self.internal_transfer_owner_roles(&old_owner_id, &proposal.new_owner_id);
+        self.data_mut().owner_id = proposal.new_owner_id;
+        self.data_mut().owner_proposal = None;
+        Event::ChangeOwner {
+            old_owner_id: &old_owner_id,
+            new_owner_id: &self.data().owner_id,
+        }
+        .emit();
+    }
+
+    pub fn get_owner(&self) -> AccountId {
+        self.data().owner_id.clone()
+    }
+
+    pub fn get_owner_proposal(&self) -> Option<OwnerProposal> {
+        self.data().owner_proposal.clone()
+    }
+
+    #[pause]
+    #[payable]
+    #[access_control_any(roles(Role::BeneficiaryManager, Role::DAO))]
+    pub fn set_beneficiary(&mut self, account_id: AccountId, bps: u32) {
+        assert_one_yocto();
+        self.assert_no_timelock(TimelockActionKind::SetBeneficiary);
+        self.internal_set_beneficiary(account_id, bps);
+    }
+
+    #[pause]
+    #[payable]
+    #[access_control_any(roles(Role::BeneficiaryManager, Role::DAO))]
+    pub fn remove_beneficiary(&mut self, account_id: AccountId) {
+        assert_one_yocto();
+        self.assert_no_timelock(TimelockActionKind::RemoveBeneficiary);
+        self.internal_remove_beneficiary(account_id);
+    }
+
+    /// Set whitelist account ID
+    #[pause]
+    #[payable]
+    #[access_control_any(roles(Role::WhitelistManager, Role::DAO))]
+    pub fn set_whitelist_contract_id(&mut self, account_id: AccountId) {
+        assert_one_yocto();
+        self.assert_no_timelock(TimelockActionKind::SetWhitelistContractId);
+        self.internal_set_whitelist_contract_id(account_id);
+    }
+}
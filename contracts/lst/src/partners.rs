@@ -0,0 +1,155 @@
+use crate::*;
+
+/// A partner account tied to a validator. NEAR staked by the partner
+/// raises the base stake of the validator, and NEAR unstaked by the
+/// partner lowers it.
+#[near(serializers = [borsh])]
+#[derive(Clone)]
+pub struct Partner {
+    pub validator_id: AccountId,
+    /// The amount of NEAR staked by the partner that is currently
+    /// counted in the base stake of the validator
+    pub attributed_amount: u128,
+}
+
+#[near(serializers = [json])]
+pub struct PartnerView {
+    pub partner_id: AccountId,
+    pub validator_id: AccountId,
+    pub attributed_amount: U128,
+}
+
+impl Contract {
+    pub(crate) fn internal_on_partner_stake(&mut self, account_id: &AccountId, amount: u128) {
+        let Some(mut partner) = self.data().partners.get(account_id).cloned() else {
+            return;
+        };
+        let Some(validator) = self
+            .data()
+            .validator_pool
+            .get_validator(&partner.validator_id)
+        else {
+            return;
+        };
+
+        self.data_mut()
+            .validator_pool
+            .update_base_stake_amount(&partner.validator_id, validator.base_stake_amount + amount);
+        partner.attributed_amount += amount;
+        self.data_mut().partners.insert(account_id.clone(), partner);
+    }
+
+    pub(crate) fn internal_on_partner_unstake(&mut self, account_id: &AccountId, amount: u128) {
+        let Some(mut partner) = self.data().partners.get(account_id).cloned() else {
+            return;
+        };
+        let amount = min(amount, partner.attributed_amount);
+        if amount == 0 {
+            return;
+        }
+
+        self.internal_release_partner_base_stake(&partner.validator_id, amount);
+        partner.attributed_amount -= amount;
+        self.data_mut().partners.insert(account_id.clone(), partner);
+    }
+
+    /// Lower the base stake of the validator by the given amount.
+    /// Base stake amounts are rescaled on balance sync, so the
+    /// validator might hold less than the attributed amount.
+    fn internal_release_partner_base_stake(&mut self, validator_id: &AccountId, amount: u128) {
+        if let Some(validator) = self.data().validator_pool.get_validator(validator_id) {
+            self.data_mut().validator_pool.update_base_stake_amount(
+                validator_id,
+                validator.base_stake_amount.saturating_sub(amount),
+            );
+        }
+    }
+}
+
+#[near]
+impl Contract {
+    #[payable]
+    #[access_control_any(roles(Role::DAO))]
+    #[pause]
+    pub fn register_partner(&mut self, partner_id: AccountId, validator_id: AccountId) {
+        assert_one_yocto();
+        require!(
+            self.data().partners.get(&partner_id).is_none(),
+            ERR_PARTNER_ALREADY_EXIST
+        );
+        require!(
+            self.data()
+                .validator_pool
+                .get_validator(&validator_id)
+                .is_some(),
+            ERR_VALIDATOR_NOT_EXIST
+        );
+
+        self.data_mut().partners.insert(
+            partner_id.clone(),
+            Partner {
+                validator_id: validator_id.clone(),
+                attributed_amount: 0,
+            },
+        );
+
+        Event::PartnerRegistered {
+            partner_id: &partner_id,
+            validator_id: &validator_id,
+        }
+        .emit();
+    }
+
+    /// Deregister the partner and release its attributed base stake.
+    #[payable]
+    #[access_control_any(roles(Role::DAO))]
+    #[pause]
+    pub fn deregister_partner(&mut self, partner_id: AccountId) {
+        assert_one_yocto();
+        let partner = self
+            .data_mut()
+            .partners
+            .remove(&partner_id)
+            .expect(ERR_PARTNER_NOT_EXIST);
+
+        self.internal_release_partner_base_stake(&partner.validator_id, partner.attributed_amount);
+
+        Event::PartnerDeregistered {
+            partner_id: &partner_id,
+            validator_id: &partner.validator_id,
+            released_amount: &U128(partner.attributed_amount),
+        }
+        .emit();
+    }
+
+    pub fn get_partner(&self, partner_id: AccountId) -> Option<PartnerView> {
+        self.data()
+            .partners
+            .get(&partner_id)
+            .map(|partner| PartnerView {
+                partner_id: partner_id.clone(),
+                validator_id: partner.validator_id.clone(),
+                attributed_amount: partner.attributed_amount.into(),
+            })
+    }
+
+    pub fn get_partners(
+        &self,
+        from_index: Option<usize>,
+        limit: Option<usize>,
+    ) -> Vec<PartnerView> {
+        let skip_n = from_index.unwrap_or(0);
+        let take_n = limit.unwrap_or(self.data().partners.len() as usize);
+        self.data()
+            .partners
+            .iter()
+            .skip(skip_n)
+            .take(take_n)
+            .map(|(partner_id, partner)| PartnerView {
+                partner_id: partner_id.clone(),
+                validator_id: partner.validator_id.clone(),
+                attributed_amount: partner.attributed_amount.into(),
+            })
+            .collect()
+    }
+}
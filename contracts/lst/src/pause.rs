@@ -0,0 +1,146 @@
+use crate::*;
+
+/// Pause key of all features
+pub const PAUSE_ALL: &str = "ALL";
+
+/// Named pause features covering a group of methods,
+/// paused and unpaused with `pa_pause_feature` and `pa_unpause_feature`.
+#[near(serializers = [json])]
+#[derive(Clone, Copy)]
+pub enum PauseScope {
+    /// Deposits and stakes, including rNEAR deposits
+    Deposits,
+    Unstakes,
+    Withdrawals,
+    EpochActions,
+    /// Validator management by OpManager or DAO
+    ValidatorAdmin,
+}
+
+impl PauseScope {
+    pub const ALL: [PauseScope; 5] = [
+        PauseScope::Deposits,
+        PauseScope::Unstakes,
+        PauseScope::Withdrawals,
+        PauseScope::EpochActions,
+        PauseScope::ValidatorAdmin,
+    ];
+
+    pub fn key(&self) -> &'static str {
+        match self {
+            PauseScope::Deposits => "deposits",
+            PauseScope::Unstakes => "unstakes",
+            PauseScope::Withdrawals => "withdrawals",
+            PauseScope::EpochActions => "epoch_actions",
+            PauseScope::ValidatorAdmin => "validator_admin",
+        }
+    }
+}
+
+#[near(serializers = [json])]
+pub struct PauseScopeStatus {
+    pub scope: PauseScope,
+    pub key: String,
+    pub paused: bool,
+}
+
+#[near(serializers = [json])]
+pub struct PauseStatus {
+    pub all: bool,
+    pub scopes: Vec<PauseScopeStatus>,
+    /// All paused feature keys, including method names
+    pub paused_keys: Vec<String>,
+    /// Withdrawals of matured unstaked NEAR ignore the pause
+    pub exit_only: bool,
+}
+
+impl Contract {
+    /// Whether the scope is paused, directly or by pausing all
+    pub(crate) fn is_scope_paused(&self, scope: PauseScope) -> bool {
+        self.pa_is_paused(scope.key().to_string())
+    }
+
+    pub(crate) fn assert_scope_not_paused(&self, scope: PauseScope) {
+        if self.is_scope_paused(scope) {
+            env::panic_str(&format!("{}: {}", ERR_SCOPE_PAUSED, scope.key()));
+        }
+    }
+
+    /// Whether the scope itself is paused, not just by pausing all
+    fn is_scope_explicitly_paused(&self, scope: PauseScope) -> bool {
+        self.pa_all_paused()
+            .is_some_and(|keys| keys.contains(scope.key()))
+    }
+
+    /// In exit only mode users can still withdraw matured unstaked NEAR while
+    /// all features are paused, but not while withdrawals themselves are paused.
+    /// Deposits, unstakes and operations stay paused.
+    /// LST transfers are not paused at all.
+    pub(crate) fn assert_withdrawals_not_paused(&self, account: &Account) {
+        if !self.is_scope_paused(PauseScope::Withdrawals) {
+            return;
+        }
+        if !self.data().exit_only_mode || self.is_scope_explicitly_paused(PauseScope::Withdrawals) {
+            self.assert_scope_not_paused(PauseScope::Withdrawals);
+        }
+        // matched NEAR that is not settled yet stays paused
+        require!(
+            self.is_unstaked_matured(account),
+            ERR_UNSTAKED_BALANCE_NOT_AVAILABLE
+        );
+    }
+
+    fn internal_set_exit_only_mode(&mut self, enabled: bool) {
+        require!(
+            self.data().exit_only_mode != enabled,
+            ERR_EXIT_ONLY_MODE_UNCHANGED
+        );
+        self.data_mut().exit_only_mode = enabled;
+        Event::ExitOnlyModeUpdated {
+            account_id: &env::predecessor_account_id(),
+            enabled,
+        }
+        .emit();
+    }
+}
+
+#[near]
+impl Contract {
+    /// Pause state of all features and of each scope
+    pub fn get_pause_status(&self) -> PauseStatus {
+        let mut paused_keys: Vec<String> = self
+            .pa_all_paused()
+            .map(|keys| keys.into_iter().collect())
+            .unwrap_or_default();
+        paused_keys.sort();
+        PauseStatus {
+            all: self.pa_is_paused(PAUSE_ALL.to_string()),
+            scopes: PauseScope::ALL
+                .into_iter()
+                .map(|scope| PauseScopeStatus {
+                    scope,
+                    key: scope.key().to_string(),
+                    paused: self.is_scope_paused(scope),
+                })
+                .collect(),
+            paused_keys,
+            exit_only: self.data().exit_only_mode,
+        }
+    }
+
+    /// Keep withdrawals open while paused, so that users can exit during an incident.
+    /// Works while paused.
+    #[payable]
+    #[access_control_any(roles(Role::DAO, Role::UnpauseManager))]
+    pub fn enable_exit_only_mode(&mut self) {
+        assert_one_yocto();
+        self.internal_set_exit_only_mode(true);
+    }
+
+    #[payable]
+    #[access_control_any(roles(Role::DAO, Role::PauseManager, Role::UnpauseManager))]
+    pub fn disable_exit_only_mode(&mut self) {
+        assert_one_yocto();
+        self.internal_set_exit_only_mode(false);
+    }
+}
@@ -0,0 +1,129 @@
+use crate::*;
+
+/// Max number of price samples kept in history
+pub const PRICE_HISTORY_SIZE: u32 = 180;
+/// Default window of the APR view
+pub const DEFAULT_APR_WINDOW_SEC: u64 = 7 * 24 * 3600;
+pub const NUM_SEC_IN_YEAR: u128 = 365 * 24 * 3600;
+
+#[near(serializers = [borsh, json])]
+#[derive(Clone)]
+pub struct PriceSample {
+    pub epoch: EpochHeight,
+    pub timestamp: U64,
+    /// NEAR value of one LST
+    pub ft_price: U128,
+    pub total_staked: U128,
+    pub total_supply: U128,
+}
+
+#[near(serializers = [json])]
+pub struct AprView {
+    /// Annualized growth of the LST price, in basis points
+    pub apr_bps: u32,
+    pub from: PriceSample,
+    pub to: PriceSample,
+}
+
+impl Contract {
+    /// Record the current LST price. At most one sample is kept per block.
+    pub(crate) fn internal_record_price_sample(&mut self) {
+        if self.data().token.total_supply == 0 {
+            return;
+        }
+        let now = env::block_timestamp();
+        if let Some(latest) = self.data().price_history.latest() {
+            if latest.timestamp.0 == now {
+                return;
+            }
+        }
+        let sample = PriceSample {
+            epoch: get_epoch_height(),
+            timestamp: now.into(),
+            ft_price: self
+                .staked_amount_from_num_shares_rounded_down(ONE_NEAR)
+                .into(),
+            total_staked: self.vested_total_staked_amount().into(),
+            total_supply: self.data().token.total_supply.into(),
+        };
+        self.data_mut().price_history.push(sample);
+    }
+
+    /// Samples within the window, from the latest to the oldest.
+    /// The sample right before the window is included as the price at its start.
+    fn price_samples_in_window(&self, window_sec: u64) -> Vec<&PriceSample> {
+        let start = env::block_timestamp().saturating_sub(window_sec * 1_000_000_000);
+        let mut samples = vec![];
+        for sample in self.data().price_history.iter_latest() {
+            samples.push(sample);
+            if sample.timestamp.0 <= start {
+                break;
+            }
+        }
+        samples
+    }
+}
+
+#[near]
+impl Contract {
+    /// Returns the latest price samples, from the latest to the oldest
+    pub fn get_price_history(&self, limit: Option<u32>) -> Vec<PriceSample> {
+        self.data()
+            .price_history
+            .iter_latest()
+            .take(limit.unwrap_or(PRICE_HISTORY_SIZE) as usize)
+            .cloned()
+            .collect()
+    }
+
+    /// Time weighted average LST price over the last `window_sec` seconds.
+    /// Each sample holds until the next one, and the latest until now.
+    /// When history does not cover the window, the covered part is averaged.
+    pub fn get_twap(&self, window_sec: u64) -> U128 {
+        require!(window_sec > 0, ERR_INVALID_PRICE_WINDOW);
+        let now = env::block_timestamp();
+        let start = now.saturating_sub(window_sec * 1_000_000_000);
+
+        let mut end = now;
+        let mut weighted_sum = U256::zero();
+        let mut total_duration = 0u64;
+        for sample in self.price_samples_in_window(window_sec) {
+            let from = sample.timestamp.0.max(start);
+            let duration = end - from;
+            weighted_sum += U256::from(sample.ft_price.0) * U256::from(duration);
+            total_duration += duration;
+            end = from;
+        }
+
+        if total_duration == 0 {
+            return self
+                .data()
+                .price_history
+                .latest()
+                .map(|sample| sample.ft_price)
+                .unwrap_or_else(|| self.ft_price());
+        }
+        (weighted_sum / U256::from(total_duration)).as_u128().into()
+    }
+
+    /// APR derived from the LST price growth over the last `window_sec` seconds,
+    /// 7 days by default. None if there are not enough samples.
+    pub fn get_apr(&self, window_sec: Option<u64>) -> Option<AprView> {
+        let samples = self.price_samples_in_window(window_sec.unwrap_or(DEFAULT_APR_WINDOW_SEC));
+        let (to, from) = (*samples.first()?, *samples.last()?);
+        let elapsed_sec = ((to.timestamp.0 - from.timestamp.0) / 1_000_000_000) as u128;
+        if elapsed_sec == 0 || from.ft_price.0 == 0 {
+            return None;
+        }
+        let growth = to.ft_price.0.saturating_sub(from.ft_price.0);
+        let apr_bps =
+            U256::from(growth) * U256::from(FULL_BASIS_POINTS) * U256::from(NUM_SEC_IN_YEAR)
+                / U256::from(from.ft_price.0)
+                / U256::from(elapsed_sec);
+        Some(AprView {
+            apr_bps: apr_bps.min(U256::from(u32::MAX)).as_u32(),
+            from: from.clone(),
+            to: to.clone(),
+        })
+    }
+}
@@ -0,0 +1,507 @@
+use crate::*;
+
+#[near(serializers = [borsh, json])]
+#[derive(Clone, Copy, PartialEq)]
+pub enum RedelegationStatus {
+    /// Waiting to be unstaked from the source validator
+    Pending,
+    /// Unstake is in progress
+    Unstaking,
+    /// Unstaked from the source validator, waiting for the unlock
+    Unstaked,
+    /// Withdraw is in progress
+    Withdrawing,
+    /// Withdrawn to the contract, waiting to be staked on the target validator
+    Withdrawn,
+    /// Stake is in progress
+    Staking,
+}
+
+/// Stake moving from one validator to another
+#[near(serializers = [borsh])]
+#[derive(Clone)]
+pub struct Redelegation {
+    pub from_validator_id: AccountId,
+    pub to_validator_id: AccountId,
+    pub amount: u128,
+    pub status: RedelegationStatus,
+    /// The epoch when the stake was unstaked from the source validator
+    pub unstaked_epoch: EpochHeight,
+}
+
+#[near(serializers = [json])]
+pub struct RedelegationView {
+    pub redelegation_id: u64,
+    pub from_validator_id: AccountId,
+    pub to_validator_id: AccountId,
+    pub amount: U128,
+    pub status: RedelegationStatus,
+    pub unstaked_epoch: U64,
+}
+
+impl Redelegation {
+    fn get_view(&self, redelegation_id: u64) -> RedelegationView {
+        RedelegationView {
+            redelegation_id,
+            from_validator_id: self.from_validator_id.clone(),
+            to_validator_id: self.to_validator_id.clone(),
+            amount: self.amount.into(),
+            status: self.status,
+            unstaked_epoch: self.unstaked_epoch.into(),
+        }
+    }
+}
+
+impl Contract {
+    fn internal_get_redelegation(&self, redelegation_id: u64) -> Redelegation {
+        self.data()
+            .redelegations
+            .get(&redelegation_id)
+            .cloned()
+            .expect(ERR_REDELEGATION_NOT_EXIST)
+    }
+
+    fn internal_save_redelegation(&mut self, redelegation_id: u64, redelegation: Redelegation) {
+        self.data_mut()
+            .redelegations
+            .insert(redelegation_id, redelegation);
+    }
+
+    fn internal_redelegation_unstake(&mut self, redelegation_id: u64) -> Promise {
+        let mut redelegation = self.internal_get_redelegation(redelegation_id);
+        require!(
+            redelegation.status == RedelegationStatus::Pending,
+            ERR_BAD_REDELEGATION_STATUS
+        );
+
+        let mut validator = self
+            .data()
+            .validator_pool
+            .get_validator(&redelegation.from_validator_id)
+            .expect(ERR_VALIDATOR_NOT_EXIST);
+        require!(!validator.draining, ERR_DRAINING);
+
+        redelegation.status = RedelegationStatus::Unstaking;
+        let amount = redelegation.amount;
+        self.internal_save_redelegation(redelegation_id, redelegation);
+
+        let config = *self.internal_config();
+        validator
+            .unstake(&mut self.data_mut().validator_pool, amount, &config)
+            .then(
+                Self::ext(env::current_account_id())
+                    .with_static_gas(config.gas.unstaked_callback())
+                    .with_unused_gas_weight(0)
+                    .validator_redelegation_unstaked_callback(redelegation_id),
+            )
+    }
+}
+
+#[near]
+impl Contract {
+    /// Move stake from one validator to another.
+    /// The stake is unstaked from the source validator right away if possible,
+    /// and after the unlock, keepers withdraw it with `redelegation_withdraw`
+    /// and stake it on the target validator with `redelegation_stake`.
+    /// Once staked on the target validator, the amount is moved from the base stake
+    /// of the source validator to the base stake of the target validator.
+    ///
+    /// Returns the redelegation ID.
+    #[payable]
+    #[access_control_any(roles(Role::OpManager, Role::DAO))]
+    #[pause]
+    pub fn redelegate(
+        &mut self,
+        from_validator_id: AccountId,
+        to_validator_id: AccountId,
+        amount: U128,
+    ) -> U64 {
+        assert_one_yocto();
+        self.assert_scope_not_paused(PauseScope::ValidatorAdmin);
+        self.assert_not_emergency_mode();
+        let amount: u128 = amount.into();
+        require!(amount > 0, ERR_NON_POSITIVE_REDELEGATION_AMOUNT);
+        require!(
+            from_validator_id != to_validator_id,
+            ERR_SAME_REDELEGATION_VALIDATOR
+        );
+
+        let from_validator = self
+            .data()
+            .validator_pool
+            .get_validator(&from_validator_id)
+            .expect(ERR_VALIDATOR_NOT_EXIST);
+        require!(
+            self.data()
+                .validator_pool
+                .get_validator(&to_validator_id)
+                .is_some(),
+            ERR_VALIDATOR_NOT_EXIST
+        );
+        require!(
+            amount <= from_validator.staked_amount,
+            format!(
+                "{}. staked: {}, requested: {}",
+                ERR_VALIDATOR_UNSTAKE_AMOUNT, from_validator.staked_amount, amount
+            )
+        );
+
+        let redelegation_id = self.data().next_redelegation_id;
+        self.data_mut().next_redelegation_id += 1;
+        self.internal_save_redelegation(
+            redelegation_id,
+            Redelegation {
+                from_validator_id: from_validator_id.clone(),
+                to_validator_id: to_validator_id.clone(),
+                amount,
+                status: RedelegationStatus::Pending,
+                unstaked_epoch: 0,
+            },
+        );
+
+        Event::RedelegationCreated {
+            redelegation_id,
+            from_validator_id: &from_validator_id,
+            to_validator_id: &to_validator_id,
+            amount: &U128(amount),
+        }
+        .emit();
+
+        // the source validator might be locked by a previous unstake,
+        // in which case the redelegation stays pending until `redelegation_unstake`
+        let config = self.internal_config();
+        let min_gas = config.gas.redelegate.as_gas() + config.gas.unstake();
+        if !from_validator.pending_release(config)
+            && !from_validator.is_executing()
+            && env::prepaid_gas().as_gas() >= min_gas
+        {
+            self.internal_redelegation_unstake(redelegation_id);
+        }
+
+        redelegation_id.into()
+    }
+
+    /// Unstake a pending redelegation from its source validator.
+    #[pause]
+    pub fn redelegation_unstake(&mut self, redelegation_id: U64) -> Promise {
+        let gas = self.internal_config().gas;
+        let min_gas = gas.redelegate.as_gas() + gas.unstake();
+        require!(
+            env::prepaid_gas().as_gas() >= min_gas,
+            format!("{}. require at least {:?}", ERR_NO_ENOUGH_GAS, min_gas)
+        );
+
+        self.internal_redelegation_unstake(redelegation_id.into())
+    }
+
+    /// Withdraw an unstaked redelegation from its source validator after the unlock.
+    #[pause]
+    pub fn redelegation_withdraw(&mut self, redelegation_id: U64) -> Promise {
+        let config = *self.internal_config();
+        let min_gas = config.gas.redelegate.as_gas() + config.gas.withdraw();
+        require!(
+            env::prepaid_gas().as_gas() >= min_gas,
+            format!("{}. require at least {:?}", ERR_NO_ENOUGH_GAS, min_gas)
+        );
+
+        let redelegation_id: u64 = redelegation_id.into();
+        let mut redelegation = self.internal_get_redelegation(redelegation_id);
+        require!(
+            redelegation.status == RedelegationStatus::Unstaked,
+            ERR_BAD_REDELEGATION_STATUS
+        );
+
+        let mut validator = self
+            .data()
+            .validator_pool
+            .get_validator(&redelegation.from_validator_id)
+            .expect(ERR_VALIDATOR_NOT_EXIST);
+        // the unstaked amount might be a little bit less than requested due to rounding
+        let amount = min(redelegation.amount, validator.unstaked_amount);
+        validator.redelegation_unstaked_amount -= redelegation.amount;
+
+        redelegation.status = RedelegationStatus::Withdrawing;
+        redelegation.amount = amount;
+        self.internal_save_redelegation(redelegation_id, redelegation);
+
+        validator
+            .withdraw(&mut self.data_mut().validator_pool, amount, &config)
+            .then(
+                Self::ext(env::current_account_id())
+                    .with_static_gas(config.gas.cb_validator_withdraw)
+                    .with_unused_gas_weight(0)
+                    .validator_redelegation_withdraw_callback(redelegation_id),
+            )
+    }
+
+    /// Stake a withdrawn redelegation on its target validator.
+    #[pause]
+    pub fn redelegation_stake(&mut self, redelegation_id: U64) -> PromiseOrValue<()> {
+        let config = *self.internal_config();
+        let min_gas = config.gas.redelegate.as_gas() + config.gas.stake();
+        require!(
+            env::prepaid_gas().as_gas() >= min_gas,
+            format!("{}. require at least {:?}", ERR_NO_ENOUGH_GAS, min_gas)
+        );
+
+        let redelegation_id: u64 = redelegation_id.into();
+        let mut redelegation = self.internal_get_redelegation(redelegation_id);
+        require!(
+            redelegation.status == RedelegationStatus::Withdrawn,
+            ERR_BAD_REDELEGATION_STATUS
+        );
+
+        let target_validator = if self.is_emergency_mode() {
+            None
+        } else {
+            self.data()
+                .validator_pool
+                .get_validator(&redelegation.to_validator_id)
+        };
+        let Some(mut validator) = target_validator else {
+            // target validator has been removed, or the protocol winds down,
+            // leave the NEAR to regular epoch stake
+            self.data_mut().redelegation_in_transit_amount -= redelegation.amount;
+            self.data_mut().stake_amount_to_settle += redelegation.amount;
+            self.data_mut().redelegations.remove(&redelegation_id);
+
+            Event::RedelegationCancelled {
+                redelegation_id,
+                from_validator_id: &redelegation.from_validator_id,
+                to_validator_id: &redelegation.to_validator_id,
+                amount: &U128(redelegation.amount),
+            }
+            .emit();
+            return PromiseOrValue::Value(());
+        };
+
+        redelegation.status = RedelegationStatus::Staking;
+        let amount = redelegation.amount;
+        self.internal_save_redelegation(redelegation_id, redelegation);
+
+        validator
+            .deposit_and_stake(&mut self.data_mut().validator_pool, amount, &config)
+            .then(
+                Self::ext(env::current_account_id())
+                    .with_static_gas(config.gas.staked_callback())
+                    .with_unused_gas_weight(0)
+                    .validator_redelegation_staked_callback(redelegation_id),
+            )
+            .into()
+    }
+
+    /// Cancel a redelegation that has not been unstaked yet.
+    #[payable]
+    #[access_control_any(roles(Role::OpManager, Role::DAO))]
+    #[pause]
+    pub fn cancel_redelegation(&mut self, redelegation_id: U64) {
+        assert_one_yocto();
+        self.assert_scope_not_paused(PauseScope::ValidatorAdmin);
+        let redelegation_id: u64 = redelegation_id.into();
+        let redelegation = self.internal_get_redelegation(redelegation_id);
+        require!(
+            redelegation.status == RedelegationStatus::Pending,
+            ERR_BAD_REDELEGATION_STATUS
+        );
+        self.data_mut().redelegations.remove(&redelegation_id);
+
+        Event::RedelegationCancelled {
+            redelegation_id,
+            from_validator_id: &redelegation.from_validator_id,
+            to_validator_id: &redelegation.to_validator_id,
+            amount: &U128(redelegation.amount),
+        }
+        .emit();
+    }
+
+    pub fn get_redelegation(&self, redelegation_id: U64) -> Option<RedelegationView> {
+        let redelegation_id: u64 = redelegation_id.into();
+        self.data()
+            .redelegations
+            .get(&redelegation_id)
+            .map(|redelegation| redelegation.get_view(redelegation_id))
+    }
+
+    pub fn get_redelegations(
+        &self,
+        from_index: Option<usize>,
+        limit: Option<usize>,
+    ) -> Vec<RedelegationView> {
+        let skip_n = from_index.unwrap_or(0);
+        let take_n = limit.unwrap_or(self.data().redelegations.len() as usize);
+        self.data()
+            .redelegations
+            .iter()
+            .skip(skip_n)
+            .take(take_n)
+            .map(|(redelegation_id, redelegation)| redelegation.get_view(*redelegation_id))
+            .collect()
+    }
+}
+
+/// callbacks
+#[near]
+impl Contract {
+    #[private]
+    pub fn validator_redelegation_unstaked_callback(
+        &mut self,
+        redelegation_id: u64,
+    ) -> PromiseOrValue<()> {
+        let mut redelegation = self.internal_get_redelegation(redelegation_id);
+        let validator_id = redelegation.from_validator_id.clone();
+        let mut validator = self
+            .data_mut()
+            .validator_pool
+            .get_validator(&validator_id)
+            .unwrap_or_else(|| panic!("{}: {}", ERR_VALIDATOR_NOT_EXIST, &validator_id));
+
+        if is_promise_success() {
+            validator.redelegation_unstaked_amount += redelegation.amount;
+            validator.on_unstake_success(&mut self.data_mut().validator_pool, redelegation.amount);
+
+            redelegation.status = RedelegationStatus::Unstaked;
+            redelegation.unstaked_epoch = get_epoch_height();
+            let amount = redelegation.amount;
+            self.internal_save_redelegation(redelegation_id, redelegation);
+
+            Event::RedelegationUnstakeSuccess {
+                redelegation_id,
+                validator_id: &validator_id,
+                amount: &U128(amount),
+            }
+            .emit();
+
+            let config = *self.internal_config();
+            validator
+                .sync_account_balance(&mut self.data_mut().validator_pool, true, &config)
+                .then(
+                    Self::ext(env::current_account_id())
+                        .with_static_gas(config.gas.cb_validator_sync_balance)
+                        .with_unused_gas_weight(0)
+                        .validator_get_account_callback(validator_id),
+                )
+                .into()
+        } else {
+            validator.on_unstake_failed(&mut self.data_mut().validator_pool);
+
+            redelegation.status = RedelegationStatus::Pending;
+            let amount = redelegation.amount;
+            self.internal_save_redelegation(redelegation_id, redelegation);
+
+            Event::RedelegationUnstakeFailed {
+                redelegation_id,
+                validator_id: &validator_id,
+                amount: &U128(amount),
+            }
+            .emit();
+
+            PromiseOrValue::Value(())
+        }
+    }
+
+    #[private]
+    pub fn validator_redelegation_withdraw_callback(&mut self, redelegation_id: u64) {
+        let mut redelegation = self.internal_get_redelegation(redelegation_id);
+        let validator_id = redelegation.from_validator_id.clone();
+        let amount = redelegation.amount;
+        let mut validator = self
+            .data_mut()
+            .validator_pool
+            .get_validator(&validator_id)
+            .unwrap_or_else(|| panic!("{}: {}", ERR_VALIDATOR_NOT_EXIST, &validator_id));
+
+        if is_promise_success() {
+            validator.on_withdraw_success(&mut self.data_mut().validator_pool);
+
+            // withdrawn NEAR is kept out of epoch stake until it reaches the target validator
+            self.data_mut().redelegation_in_transit_amount += amount;
+            redelegation.status = RedelegationStatus::Withdrawn;
+            self.internal_save_redelegation(redelegation_id, redelegation);
+
+            Event::RedelegationWithdrawSuccess {
+                redelegation_id,
+                validator_id: &validator_id,
+                amount: &U128(amount),
+            }
+            .emit();
+        } else {
+            validator.redelegation_unstaked_amount += amount;
+            validator.on_withdraw_failed(&mut self.data_mut().validator_pool, amount);
+
+            redelegation.status = RedelegationStatus::Unstaked;
+            self.internal_save_redelegation(redelegation_id, redelegation);
+
+            Event::RedelegationWithdrawFailed {
+                redelegation_id,
+                validator_id: &validator_id,
+                amount: &U128(amount),
+            }
+            .emit();
+        }
+    }
+
+    #[private]
+    pub fn validator_redelegation_staked_callback(
+        &mut self,
+        redelegation_id: u64,
+    ) -> PromiseOrValue<()> {
+        let mut redelegation = self.internal_get_redelegation(redelegation_id);
+        let validator_id = redelegation.to_validator_id.clone();
+        let amount = redelegation.amount;
+        let mut validator = self
+            .data_mut()
+            .validator_pool
+            .get_validator(&validator_id)
+            .unwrap_or_else(|| panic!("{}: {}", ERR_VALIDATOR_NOT_EXIST, &validator_id));
+
+        if is_promise_success() {
+            validator.on_stake_success(&mut self.data_mut().validator_pool, amount);
+            self.data_mut().validator_pool.move_base_stake_amount(
+                &redelegation.from_validator_id,
+                &validator_id,
+                amount,
+            );
+            let mut validator = self
+                .data()
+                .validator_pool
+                .get_validator(&validator_id)
+                .unwrap();
+
+            self.data_mut().redelegation_in_transit_amount -= amount;
+            self.data_mut().redelegations.remove(&redelegation_id);
+
+            Event::RedelegationCompleted {
+                redelegation_id,
+                from_validator_id: &redelegation.from_validator_id,
+                to_validator_id: &validator_id,
+                amount: &U128(amount),
+            }
+            .emit();
+
+            let config = *self.internal_config();
+            validator
+                .sync_account_balance(&mut self.data_mut().validator_pool, true, &config)
+                .then(
+                    Self::ext(env::current_account_id())
+                        .with_static_gas(config.gas.cb_validator_sync_balance)
+                        .with_unused_gas_weight(0)
+                        .validator_get_account_callback(validator_id),
+                )
+                .into()
+        } else {
+            validator.on_stake_failed(&mut self.data_mut().validator_pool);
+
+            redelegation.status = RedelegationStatus::Withdrawn;
+            self.internal_save_redelegation(redelegation_id, redelegation);
+
+            Event::RedelegationStakeFailed {
+                redelegation_id,
+                validator_id: &validator_id,
+                amount: &U128(amount),
+            }
+            .emit();
+
+            PromiseOrValue::Value(())
+        }
+    }
+}
@@ -0,0 +1,341 @@
+use crate::*;
+
+/// Precision of the referral reward accumulator
+const REFERRAL_REWARD_PRECISION: u128 = 1_000_000_000_000_000_000;
+/// Min referred stake in LST shares for referral rewards to be shared
+const MIN_REFERRAL_ACTIVE_SHARES: u128 = ONE_NEAR;
+
+#[near(serializers = [borsh])]
+#[derive(Clone, Default)]
+pub struct Referrer {
+    /// Total NEAR deposited by referred accounts
+    pub total_referred_amount: u128,
+    /// LST shares currently held by referred accounts
+    pub active_shares: u128,
+    pub referred_accounts: u64,
+    /// The accumulator value this referrer was last settled at
+    pub reward_per_share_paid: u128,
+    /// Earned LST shares not claimed yet
+    pub unclaimed_shares: u128,
+    pub claimed_shares: u128,
+}
+
+#[near(serializers = [borsh])]
+#[derive(Clone)]
+pub struct ReferredAccount {
+    pub referrer_id: AccountId,
+    /// LST shares of the account counted in the active shares of the referrer
+    pub shares: u128,
+}
+
+#[near(serializers = [borsh])]
+pub struct Referrals {
+    pub referrers: IterableMap<AccountId, Referrer>,
+    pub referred_accounts: LookupMap<AccountId, ReferredAccount>,
+    pub total_active_shares: u128,
+    /// Accumulated LST rewards per active share, scaled by the precision
+    pub reward_per_share: u128,
+    /// LST held by the contract for referrers
+    pub reward_pool_shares: u128,
+}
+
+impl Referrals {
+    pub fn new() -> Self {
+        Self {
+            referrers: IterableMap::new(StorageKey::Referrers),
+            referred_accounts: LookupMap::new(StorageKey::ReferredAccounts),
+            total_active_shares: 0,
+            reward_per_share: 0,
+            reward_pool_shares: 0,
+        }
+    }
+
+    /// Move the rewards accrued since the last settlement into unclaimed shares
+    fn settle(&self, referrer: &mut Referrer) {
+        let earned_shares = (U256::from(referrer.active_shares)
+            * U256::from(self.reward_per_share - referrer.reward_per_share_paid)
+            / U256::from(REFERRAL_REWARD_PRECISION))
+        .as_u128();
+        referrer.unclaimed_shares += earned_shares;
+        referrer.reward_per_share_paid = self.reward_per_share;
+    }
+}
+
+impl Default for Referrals {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[near(serializers = [json])]
+pub struct ReferrerView {
+    pub referrer_id: AccountId,
+    pub total_referred_amount: U128,
+    pub active_shares: U128,
+    pub active_stake_amount: U128,
+    pub referred_accounts: u64,
+    pub unclaimed_shares: U128,
+    pub claimed_shares: U128,
+}
+
+/// Optional message of `ft_on_transfer`
+#[near(serializers = [json])]
+#[derive(Default)]
+pub struct FtOnTransferMsg {
+    pub referrer_id: Option<AccountId>,
+}
+
+impl Contract {
+    /// Bind the account to the referrer on its first referred deposit,
+    /// and record the deposit for the referrer the account is bound to.
+    pub(crate) fn internal_on_referred_deposit(
+        &mut self,
+        account_id: &AccountId,
+        referrer_id: Option<AccountId>,
+        amount: u128,
+    ) {
+        if let Some(referrer_id) = referrer_id {
+            if &referrer_id != account_id
+                && self.data().referrals.referrers.get(&referrer_id).is_some()
+                && self
+                    .data()
+                    .referrals
+                    .referred_accounts
+                    .get(account_id)
+                    .is_none()
+            {
+                self.data_mut().referrals.referred_accounts.insert(
+                    account_id.clone(),
+                    ReferredAccount {
+                        referrer_id: referrer_id.clone(),
+                        shares: 0,
+                    },
+                );
+                let mut referrer = self
+                    .data()
+                    .referrals
+                    .referrers
+                    .get(&referrer_id)
+                    .cloned()
+                    .unwrap_or_default();
+                referrer.referred_accounts += 1;
+                self.data_mut()
+                    .referrals
+                    .referrers
+                    .insert(referrer_id.clone(), referrer);
+                Event::ReferralBound {
+                    account_id,
+                    referrer_id: &referrer_id,
+                }
+                .emit();
+                self.internal_sync_referred_shares(account_id);
+            }
+        }
+
+        let Some(referred) = self
+            .data()
+            .referrals
+            .referred_accounts
+            .get(account_id)
+            .cloned()
+        else {
+            return;
+        };
+        let mut referrer = self
+            .data()
+            .referrals
+            .referrers
+            .get(&referred.referrer_id)
+            .cloned()
+            .unwrap_or_default();
+        referrer.total_referred_amount += amount;
+        self.data_mut()
+            .referrals
+            .referrers
+            .insert(referred.referrer_id.clone(), referrer);
+        Event::ReferredDeposit {
+            account_id,
+            referrer_id: &referred.referrer_id,
+            amount: &U128(amount),
+        }
+        .emit();
+    }
+
+    /// Count the LST balance of a referred account in the active shares of its referrer.
+    pub(crate) fn internal_sync_referred_shares(&mut self, account_id: &AccountId) {
+        let Some(mut referred) = self
+            .data()
+            .referrals
+            .referred_accounts
+            .get(account_id)
+            .cloned()
+        else {
+            return;
+        };
+        let balance = self.data().token.accounts.get(account_id).unwrap_or(0);
+        if balance == referred.shares {
+            return;
+        }
+
+        let referrals = &mut self.data_mut().referrals;
+        let mut referrer = referrals
+            .referrers
+            .get(&referred.referrer_id)
+            .cloned()
+            .unwrap_or_default();
+        referrals.settle(&mut referrer);
+        referrer.active_shares = referrer.active_shares - referred.shares + balance;
+        referrals.total_active_shares = referrals.total_active_shares - referred.shares + balance;
+        referrals
+            .referrers
+            .insert(referred.referrer_id.clone(), referrer);
+        referred.shares = balance;
+        referrals
+            .referred_accounts
+            .insert(account_id.clone(), referred);
+    }
+
+    /// The part of reward bps shared with referrers, carved out of the protocol fee.
+    /// Zero when there is not enough referred stake to share with.
+    pub(crate) fn referral_reward_bps(&self, fee_config: &FeeConfig) -> u32 {
+        if self.data().referrals.total_active_shares < MIN_REFERRAL_ACTIVE_SHARES {
+            return 0;
+        }
+        fee_config
+            .protocol_fee
+            .as_ref()
+            .map(|protocol_fee| {
+                (protocol_fee.bps as u128 * fee_config.referral_share_bps as u128
+                    / FULL_BASIS_POINTS as u128) as u32
+            })
+            .unwrap_or(0)
+    }
+
+    /// Mint the referral rewards to the contract and share them
+    /// among referrers in proportion to their active shares.
+    pub(crate) fn internal_distribute_referral_rewards(&mut self, reward_shares: u128) {
+        if reward_shares == 0 {
+            return;
+        }
+        self.mint_lst(
+            &env::current_account_id(),
+            reward_shares,
+            Some("referral rewards"),
+        );
+        let referrals = &mut self.data_mut().referrals;
+        referrals.reward_pool_shares += reward_shares;
+        referrals.reward_per_share += (U256::from(reward_shares)
+            * U256::from(REFERRAL_REWARD_PRECISION)
+            / U256::from(referrals.total_active_shares))
+        .as_u128();
+
+        Event::ReferralRewardsDistributed {
+            reward_shares: &U128(reward_shares),
+            total_active_shares: &U128(self.data().referrals.total_active_shares),
+        }
+        .emit();
+    }
+}
+
+#[near]
+impl Contract {
+    /// Register the predecessor as a referrer.
+    #[pause]
+    pub fn register_referrer(&mut self) {
+        let referrer_id = env::predecessor_account_id();
+        require!(
+            self.data().token.accounts.get(&referrer_id).is_some(),
+            ERR_ACCOUNT_NOT_REGISTERED
+        );
+        require!(
+            self.data().referrals.referrers.get(&referrer_id).is_none(),
+            ERR_REFERRER_ALREADY_EXIST
+        );
+        let referrer = Referrer {
+            reward_per_share_paid: self.data().referrals.reward_per_share,
+            ..Default::default()
+        };
+        self.data_mut()
+            .referrals
+            .referrers
+            .insert(referrer_id.clone(), referrer);
+        Event::ReferrerRegistered {
+            referrer_id: &referrer_id,
+        }
+        .emit();
+    }
+
+    /// Claim the LST earned by the predecessor as a referrer.
+    #[pause]
+    pub fn claim_referral_rewards(&mut self) -> U128 {
+        let referrer_id = env::predecessor_account_id();
+        let mut referrer = self
+            .data()
+            .referrals
+            .referrers
+            .get(&referrer_id)
+            .cloned()
+            .expect(ERR_REFERRER_NOT_EXIST);
+        self.data().referrals.settle(&mut referrer);
+        let shares = min(
+            referrer.unclaimed_shares,
+            self.data().referrals.reward_pool_shares,
+        );
+        require!(shares > 0, ERR_NOTHING_TO_CLAIM);
+
+        referrer.unclaimed_shares -= shares;
+        referrer.claimed_shares += shares;
+        self.data_mut()
+            .referrals
+            .referrers
+            .insert(referrer_id.clone(), referrer);
+        self.data_mut().referrals.reward_pool_shares -= shares;
+
+        self.data_mut().token.internal_transfer(
+            &env::current_account_id(),
+            &referrer_id,
+            shares,
+            Some("referral rewards".to_string()),
+        );
+        self.internal_on_lst_balance_changed(&env::current_account_id());
+        self.internal_on_lst_balance_changed(&referrer_id);
+
+        Event::ReferralRewardsClaimed {
+            referrer_id: &referrer_id,
+            shares: &U128(shares),
+        }
+        .emit();
+        shares.into()
+    }
+
+    pub fn get_referrer(&self, referrer_id: AccountId) -> Option<ReferrerView> {
+        self.data()
+            .referrals
+            .referrers
+            .get(&referrer_id)
+            .cloned()
+            .map(|mut referrer| {
+                self.data().referrals.settle(&mut referrer);
+                ReferrerView {
+                    referrer_id: referrer_id.clone(),
+                    total_referred_amount: referrer.total_referred_amount.into(),
+                    active_shares: referrer.active_shares.into(),
+                    active_stake_amount: self
+                        .staked_amount_from_num_shares_rounded_down(referrer.active_shares)
+                        .into(),
+                    referred_accounts: referrer.referred_accounts,
+                    unclaimed_shares: referrer.unclaimed_shares.into(),
+                    claimed_shares: referrer.claimed_shares.into(),
+                }
+            })
+    }
+
+    /// Returns the referrer the account is bound to
+    pub fn get_referral(&self, account_id: AccountId) -> Option<AccountId> {
+        self.data()
+            .referrals
+            .referred_accounts
+            .get(&account_id)
+            .map(|referred| referred.referrer_id.clone())
+    }
+}
@@ -0,0 +1,181 @@
+use crate::*;
+
+/// Upper bound of the configurable max reward APR
+pub const MAX_REWARD_APR_BPS_LIMIT: u32 = 10 * FULL_BASIS_POINTS;
+/// Approximate length of an epoch, used to turn elapsed epochs into time
+pub const NUM_SEC_IN_EPOCH: u128 = 12 * 3600;
+
+/// Sanity bound of the rewards a validator can report in a single update.
+/// Rewards above the cap are quarantined until an Accountant reviews them.
+#[near(serializers = [borsh])]
+pub struct RewardCap {
+    /// Max APR of rewards relative to the validator balance. None disables the cap.
+    pub max_apr_bps: Option<u32>,
+    pub quarantined_rewards: IterableMap<AccountId, u128>,
+    pub total_quarantined: u128,
+}
+
+impl RewardCap {
+    pub fn new() -> Self {
+        Self {
+            max_apr_bps: None,
+            quarantined_rewards: IterableMap::new(StorageKey::QuarantinedRewards),
+            total_quarantined: 0,
+        }
+    }
+
+    /// Max rewards accrued on the balance over the elapsed epochs,
+    /// counting at least one epoch.
+    pub fn max_rewards(&self, balance: u128, elapsed_epochs: EpochHeight) -> Option<u128> {
+        self.max_apr_bps.map(|max_apr_bps| {
+            let elapsed_sec = NUM_SEC_IN_EPOCH * elapsed_epochs.max(1) as u128;
+            (U256::from(balance) * U256::from(max_apr_bps) * U256::from(elapsed_sec)
+                / U256::from(FULL_BASIS_POINTS)
+                / U256::from(NUM_SEC_IN_YEAR))
+            .as_u128()
+        })
+    }
+}
+
+impl Default for RewardCap {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[near(serializers = [json])]
+pub struct RewardCapView {
+    pub max_apr_bps: Option<u32>,
+    pub total_quarantined: U128,
+    pub quarantined_rewards: HashMap<AccountId, U128>,
+}
+
+impl Contract {
+    /// Split the rewards reported by the validator into the part to credit
+    /// and the part above the cap, which is quarantined.
+    pub(crate) fn internal_cap_rewards(&mut self, validator: &Validator, rewards: u128) -> u128 {
+        let elapsed_epochs = get_epoch_height().saturating_sub(validator.rewards_updated_epoch);
+        let Some(max_rewards) = self
+            .data()
+            .reward_cap
+            .max_rewards(validator.total_balance(), elapsed_epochs)
+        else {
+            return rewards;
+        };
+        if rewards <= max_rewards {
+            return rewards;
+        }
+
+        let quarantined = rewards - max_rewards;
+        let reward_cap = &mut self.data_mut().reward_cap;
+        let validator_quarantined = reward_cap
+            .quarantined_rewards
+            .get(&validator.account_id)
+            .copied()
+            .unwrap_or(0);
+        reward_cap.quarantined_rewards.insert(
+            validator.account_id.clone(),
+            validator_quarantined + quarantined,
+        );
+        reward_cap.total_quarantined += quarantined;
+
+        Event::RewardsQuarantined {
+            validator_id: &validator.account_id,
+            rewards: &U128(rewards),
+            max_rewards: &U128(max_rewards),
+            quarantined: &U128(quarantined),
+        }
+        .emit();
+        max_rewards
+    }
+
+    fn internal_take_quarantined_rewards(&mut self, validator_id: &AccountId, amount: u128) {
+        let reward_cap = &mut self.data_mut().reward_cap;
+        let quarantined = reward_cap
+            .quarantined_rewards
+            .get(validator_id)
+            .copied()
+            .expect(ERR_NO_QUARANTINED_REWARDS);
+        require!(
+            amount > 0 && amount <= quarantined,
+            ERR_INVALID_QUARANTINED_AMOUNT
+        );
+        if amount == quarantined {
+            reward_cap.quarantined_rewards.remove(validator_id);
+        } else {
+            reward_cap
+                .quarantined_rewards
+                .insert(validator_id.clone(), quarantined - amount);
+        }
+        reward_cap.total_quarantined -= amount;
+    }
+}
+
+#[near]
+impl Contract {
+    /// Set the max APR of rewards a validator can report in an update.
+    /// None disables the cap.
+    #[payable]
+    #[access_control_any(roles(Role::DAO))]
+    #[pause]
+    pub fn set_max_reward_apr(&mut self, max_apr_bps: Option<u32>) {
+        assert_one_yocto();
+        if let Some(max_apr_bps) = max_apr_bps {
+            require!(
+                max_apr_bps > 0 && max_apr_bps <= MAX_REWARD_APR_BPS_LIMIT,
+                ERR_INVALID_MAX_REWARD_APR
+            );
+        }
+        Event::SetMaxRewardApr {
+            old_max_apr_bps: self.data().reward_cap.max_apr_bps,
+            new_max_apr_bps: max_apr_bps,
+        }
+        .emit();
+        self.data_mut().reward_cap.max_apr_bps = max_apr_bps;
+    }
+
+    /// Credit reviewed quarantined rewards of the validator as staking rewards.
+    #[payable]
+    #[access_control_any(roles(Role::Accountant, Role::DAO))]
+    #[pause]
+    pub fn release_quarantined_rewards(&mut self, validator_id: AccountId, amount: U128) {
+        assert_one_yocto();
+        self.internal_take_quarantined_rewards(&validator_id, amount.0);
+        Event::QuarantinedRewardsReleased {
+            validator_id: &validator_id,
+            amount: &amount,
+        }
+        .emit();
+        self.internal_credit_staking_rewards(amount.0);
+        self.internal_record_price_sample();
+    }
+
+    /// Drop reviewed quarantined rewards of the validator without crediting them.
+    /// The NEAR stays in the staked amount of the validator but out of the total
+    /// staked NEAR, so it backs no LST, also once a drain restakes it elsewhere.
+    #[payable]
+    #[access_control_any(roles(Role::Accountant, Role::DAO))]
+    #[pause]
+    pub fn discard_quarantined_rewards(&mut self, validator_id: AccountId, amount: U128) {
+        assert_one_yocto();
+        self.internal_take_quarantined_rewards(&validator_id, amount.0);
+        Event::QuarantinedRewardsDiscarded {
+            validator_id: &validator_id,
+            amount: &amount,
+        }
+        .emit();
+    }
+
+    pub fn get_reward_cap(&self) -> RewardCapView {
+        let reward_cap = &self.data().reward_cap;
+        RewardCapView {
+            max_apr_bps: reward_cap.max_apr_bps,
+            total_quarantined: reward_cap.total_quarantined.into(),
+            quarantined_rewards: reward_cap
+                .quarantined_rewards
+                .iter()
+                .map(|(validator_id, amount)| (validator_id.clone(), U128(*amount)))
+                .collect(),
+        }
+    }
+}
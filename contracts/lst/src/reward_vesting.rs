@@ -0,0 +1,106 @@
+use crate::*;
+
+/// Max window over which rewards are vested
+pub const MAX_REWARD_VESTING_PERIOD_SEC: u64 = 2 * 24 * 3600;
+
+/// Linear vesting of staking rewards. Rewards are added to the total staked
+/// amount at once, but the share price only counts the vested part, so the
+/// price moves smoothly instead of jumping on each rewards update.
+#[near(serializers = [borsh])]
+#[derive(Default)]
+pub struct RewardVesting {
+    pub period_sec: u64,
+    /// Rewards vesting in the current schedule
+    pub amount: u128,
+    pub start_timestamp: Timestamp,
+    pub end_timestamp: Timestamp,
+}
+
+#[near(serializers = [json])]
+pub struct RewardVestingView {
+    pub period_sec: u64,
+    pub vesting_amount: U128,
+    pub unvested_amount: U128,
+    pub start_timestamp: U64,
+    pub end_timestamp: U64,
+}
+
+impl RewardVesting {
+    pub fn unvested_amount(&self, now: Timestamp) -> u128 {
+        if self.amount == 0 || now >= self.end_timestamp {
+            return 0;
+        }
+        let remaining = self.end_timestamp - now;
+        let duration = self.end_timestamp - self.start_timestamp;
+        (U256::from(self.amount) * U256::from(remaining) / U256::from(duration)).as_u128()
+    }
+
+    /// Restart the schedule with the new rewards and the part not vested yet.
+    pub fn add_rewards(&mut self, rewards: u128, now: Timestamp) {
+        if self.period_sec == 0 {
+            return;
+        }
+        self.amount = self.unvested_amount(now) + rewards;
+        self.start_timestamp = now;
+        self.end_timestamp = now + self.period_sec * 1_000_000_000;
+    }
+
+    /// Take a loss out of the rewards not vested yet, which keep their end time.
+    /// Returns the part of the loss they don't cover.
+    pub fn take_loss(&mut self, loss: u128, now: Timestamp) -> u128 {
+        let unvested_amount = self.unvested_amount(now);
+        let covered_loss = min(loss, unvested_amount);
+        if covered_loss > 0 {
+            self.amount = unvested_amount - covered_loss;
+            self.start_timestamp = now;
+        }
+        loss - covered_loss
+    }
+}
+
+impl Contract {
+    /// Total staked amount excluding rewards that have not vested yet,
+    /// which is what the share price is based on.
+    pub(crate) fn vested_total_staked_amount(&self) -> u128 {
+        self.data().total_staked_asset_in_near.saturating_sub(
+            self.data()
+                .reward_vesting
+                .unvested_amount(env::block_timestamp()),
+        )
+    }
+}
+
+#[near]
+impl Contract {
+    /// Set the window over which new rewards are vested. 0 disables vesting.
+    /// Rewards that are already vesting keep their schedule.
+    #[payable]
+    #[access_control_any(roles(Role::DAO))]
+    #[pause]
+    pub fn set_reward_vesting_period(&mut self, period_sec: u64) {
+        assert_one_yocto();
+        require!(
+            period_sec <= MAX_REWARD_VESTING_PERIOD_SEC,
+            ERR_REWARD_VESTING_PERIOD_TOO_LONG
+        );
+        Event::SetRewardVestingPeriod {
+            old_period_sec: self.data().reward_vesting.period_sec,
+            new_period_sec: period_sec,
+        }
+        .emit();
+        self.data_mut().reward_vesting.period_sec = period_sec;
+    }
+
+    pub fn get_reward_vesting(&self) -> RewardVestingView {
+        let reward_vesting = &self.data().reward_vesting;
+        RewardVestingView {
+            period_sec: reward_vesting.period_sec,
+            vesting_amount: reward_vesting.amount.into(),
+            unvested_amount: reward_vesting
+                .unvested_amount(env::block_timestamp())
+                .into(),
+            start_timestamp: reward_vesting.start_timestamp.into(),
+            end_timestamp: reward_vesting.end_timestamp.into(),
+        }
+    }
+}
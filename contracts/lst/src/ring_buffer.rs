@@ -0,0 +1,55 @@
+use crate::*;
+use near_sdk::{store::Vector, IntoStorageKey};
+
+/// Fixed capacity history that overwrites its oldest entry once full
+#[near(serializers = [borsh])]
+pub struct RingBuffer<T>
+where
+    T: BorshSerialize + BorshDeserialize,
+{
+    entries: Vector<T>,
+    capacity: u32,
+    /// The index the next entry is written to
+    next: u32,
+}
+
+impl<T> RingBuffer<T>
+where
+    T: BorshSerialize + BorshDeserialize,
+{
+    pub fn new<S: IntoStorageKey>(prefix: S, capacity: u32) -> Self {
+        require!(capacity > 0);
+        Self {
+            entries: Vector::new(prefix),
+            capacity,
+            next: 0,
+        }
+    }
+
+    pub fn len(&self) -> u32 {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn push(&mut self, entry: T) {
+        if self.entries.len() < self.capacity {
+            self.entries.push(entry);
+        } else {
+            self.entries.set(self.next, entry);
+        }
+        self.next = (self.next + 1) % self.capacity;
+    }
+
+    /// Returns the entries from the latest to the oldest
+    pub fn iter_latest(&self) -> impl Iterator<Item = &T> {
+        let len = self.entries.len();
+        (0..len).filter_map(move |i| self.entries.get((self.next + len - 1 - i) % len))
+    }
+
+    pub fn latest(&self) -> Option<&T> {
+        self.iter_latest().next()
+    }
+}
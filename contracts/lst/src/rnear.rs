@@ -60,7 +60,8 @@ impl FungibleTokenReceiver for Contract {
         amount: U128,
         msg: String,
     ) -> PromiseOrValue<U128> {
-        let _ = msg;
+        // any other msg, e.g. one meant for an older version, deposits without a referrer
+        let msg = serde_json::from_str::<FtOnTransferMsg>(&msg).unwrap_or_default();
 
         assert!(env::predecessor_account_id() == self.data().rnear_contract_id);
 
@@ -71,6 +72,7 @@ impl FungibleTokenReceiver for Contract {
         let near_amount = self.internal_convert_rnear_to_near(rnear_amount);
 
         self.internal_deposit(near_amount);
+        self.internal_on_referred_deposit(&account_id, msg.referrer_id, near_amount);
         self.internal_rnear_stake(near_amount, rnear_amount);
 
         PromiseOrValue::Value(U128(0))
@@ -41,7 +41,8 @@ impl Contract {
 
     pub fn get_reward_fee_fraction(&self) -> RewardFeeFraction {
         RewardFeeFraction {
-            numerator: self.data().beneficiaries.values().sum(),
+            numerator: self.data().beneficiaries.values().sum::<u32>()
+                + self.effective_fee_config().reward_fee_bps(),
             denominator: 10000,
         }
     }
@@ -51,9 +52,10 @@ impl Contract {
         panic!("no need to specify public key for liquid staking pool");
     }
 
-    /// Returns true if the staking is paused
+    /// Returns true if the staking is paused.
+    /// Paused scopes are reported by `get_pause_status`.
     pub fn is_paused(&self) -> bool {
-        self.pa_is_paused("ALL".to_string())
+        self.pa_is_paused(PAUSE_ALL.to_string())
     }
 
     /// Returns human readable representation of the account for the given account ID.
@@ -66,13 +68,13 @@ impl Contract {
             staked_balance: self
                 .staked_amount_from_num_shares_rounded_down(stake_shares.into())
                 .into(),
-            can_withdraw: account.last_unstake_request_epoch_height <= get_epoch_height(),
+            can_withdraw: self.is_unstaked_available(&account, account.unstaked),
         }
     }
 
     /// Returns the number of accounts that have positive balance on this staking pool.
     pub fn get_number_of_accounts(&self) -> u64 {
-        self.data().accounts.len() as u64
+        (self.data().accounts.len() + self.data().legacy_accounts.len()) as u64
     }
 
     /// Returns the list of accounts
@@ -83,15 +85,22 @@ impl Contract {
         self.data()
             .accounts
             .keys()
+            .chain(self.data().legacy_accounts.keys())
             .skip(skip_n)
             .take(take_n)
             .map(|k| self.get_account(k.clone()))
             .collect()
     }
 
-    /// Please notice ping() is not available for liquid staking.
-    /// Keep here for interface consistency.
-    pub fn ping(&mut self) {}
+    /// Alias of `epoch_run` with the default steps, for interface consistency.
+    /// Does nothing when epoch actions are paused, so callers never fail on it.
+    pub fn ping(&mut self) {
+        if !self.pa_is_paused("epoch_run".to_string())
+            && !self.is_scope_paused(PauseScope::EpochActions)
+        {
+            self.epoch_run(None);
+        }
+    }
 
     /// Deposits the attached amount into the inner account of the predecessor.
     /// will charge standard FT storage fee if needed.
@@ -100,9 +109,7 @@ impl Contract {
         let amount = env::attached_deposit().as_yoctonear();
         let account_id = env::predecessor_account_id();
         let storage_used = if self.storage_balance_of(account_id.clone()).is_none() {
-            self.data_mut()
-                .accounts
-                .insert(account_id.clone(), Account::default());
+            self.internal_save_account(&account_id, &Account::default());
             self.data_mut().token.internal_register_account(&account_id);
             self.storage_balance_bounds().min.as_yoctonear()
         } else {
@@ -116,20 +123,19 @@ impl Contract {
     /// Returns the received LST amount
     #[payable]
     pub fn deposit_and_stake(&mut self) -> U128 {
-        let amount = env::attached_deposit().as_yoctonear();
-        let account_id = env::predecessor_account_id();
-        let storage_used = if self.storage_balance_of(account_id.clone()).is_none() {
-            self.data_mut()
-                .accounts
-                .insert(account_id.clone(), Account::default());
-            self.data_mut().token.internal_register_account(&account_id);
-            self.storage_balance_bounds().min.as_yoctonear()
-        } else {
-            // log!("already registered.");
-            0
-        };
-        self.internal_deposit(amount - storage_used);
-        self.internal_stake(amount - storage_used).into()
+        self.internal_deposit_and_stake(None, None)
+    }
+
+    /// Same as `deposit_and_stake`, and also sets the validator the staked NEAR is directed to,
+    /// and the referrer the account is bound to on its first referred deposit.
+    /// Returns the received LST amount
+    #[payable]
+    pub fn deposit_and_stake_with_options(
+        &mut self,
+        validator_id: Option<AccountId>,
+        referrer_id: Option<AccountId>,
+    ) -> U128 {
+        self.internal_deposit_and_stake(validator_id, referrer_id)
     }
 
     /// Withdraws the entire unstaked balance from the predecessor account.
@@ -177,3 +183,28 @@ impl Contract {
         self.internal_unstake(amount.into());
     }
 }
+
+impl Contract {
+    fn internal_deposit_and_stake(
+        &mut self,
+        validator_id: Option<AccountId>,
+        referrer_id: Option<AccountId>,
+    ) -> U128 {
+        let amount = env::attached_deposit().as_yoctonear();
+        let account_id = env::predecessor_account_id();
+        let storage_used = if self.storage_balance_of(account_id.clone()).is_none() {
+            self.internal_save_account(&account_id, &Account::default());
+            self.data_mut().token.internal_register_account(&account_id);
+            self.storage_balance_bounds().min.as_yoctonear()
+        } else {
+            // log!("already registered.");
+            0
+        };
+        if validator_id.is_some() {
+            self.internal_set_validator_preference(&account_id, validator_id);
+        }
+        self.internal_deposit(amount - storage_used);
+        self.internal_on_referred_deposit(&account_id, referrer_id, amount - storage_used);
+        self.internal_stake(amount - storage_used).into()
+    }
+}
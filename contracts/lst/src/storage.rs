@@ -9,10 +9,8 @@ impl StorageManagement for Contract {
         registration_only: Option<bool>,
     ) -> StorageBalance {
         let acc_id = account_id.clone().unwrap_or(env::predecessor_account_id());
-        if self.data().accounts.get(&acc_id).is_none() {
-            self.data_mut()
-                .accounts
-                .insert(acc_id.clone(), Account::default());
+        if !self.internal_has_account(&acc_id) {
+            self.internal_save_account(&acc_id, &Account::default());
         }
         self.data_mut().token.storage_deposit(account_id, registration_only)
     }
@@ -29,7 +27,8 @@ impl StorageManagement for Contract {
         require!(force.is_none() || force.unwrap() == false, ERR_FORCE_UNGREGISTER);
         if let Some((account_id, balance)) = self.data_mut().token.internal_storage_unregister(None) {
             // still need to check account
-            let account = self.data_mut().accounts.remove(&account_id).unwrap_or(Account::default());
+            let account = self.internal_remove_account(&account_id);
+            self.data_mut().directed_stakes.remove(&account_id);
             require!(account.unstaked == 0, ERR_UNREGISTER_POSITIVE_UNSTAKED);
             log!("Closed @{} with {}", account_id, balance);
             true
@@ -0,0 +1,297 @@
+use crate::*;
+
+/// Max delay of a timelocked action
+pub const MAX_TIMELOCK_DELAY_SEC: u64 = 30 * 24 * 3600;
+/// Delay of setting a beneficiary unless set otherwise, since it raises
+/// the reward fee like an announced fee config does
+pub const DEFAULT_SET_BENEFICIARY_DELAY_SEC: u64 = 2 * 24 * 3600;
+
+/// Sensitive admin actions that can be queued behind a delay
+#[near(serializers = [borsh, json])]
+#[derive(Clone)]
+pub enum TimelockAction {
+    UpdateWeights {
+        validator_ids: Vec<AccountId>,
+        weights: Vec<u16>,
+    },
+    UpdateBaseStakeAmounts {
+        validator_ids: Vec<AccountId>,
+        amounts: Vec<U128>,
+    },
+    SetWhitelistContractId {
+        account_id: AccountId,
+    },
+    SetBeneficiary {
+        account_id: AccountId,
+        bps: u32,
+    },
+    RemoveBeneficiary {
+        account_id: AccountId,
+    },
+}
+
+#[near(serializers = [borsh, json])]
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum TimelockActionKind {
+    UpdateWeights,
+    UpdateBaseStakeAmounts,
+    SetWhitelistContractId,
+    SetBeneficiary,
+    RemoveBeneficiary,
+}
+
+impl TimelockActionKind {
+    pub const ALL: [TimelockActionKind; 5] = [
+        TimelockActionKind::UpdateWeights,
+        TimelockActionKind::UpdateBaseStakeAmounts,
+        TimelockActionKind::SetWhitelistContractId,
+        TimelockActionKind::SetBeneficiary,
+        TimelockActionKind::RemoveBeneficiary,
+    ];
+
+    /// Delay of the action kind until one is set
+    pub fn default_delay_sec(&self) -> u64 {
+        match self {
+            TimelockActionKind::SetBeneficiary => DEFAULT_SET_BENEFICIARY_DELAY_SEC,
+            _ => 0,
+        }
+    }
+
+    /// Roles allowed to queue the action, same as calling it directly
+    pub fn roles(&self) -> Vec<Role> {
+        match self {
+            TimelockActionKind::UpdateWeights | TimelockActionKind::UpdateBaseStakeAmounts => {
+                vec![Role::OpManager, Role::DAO]
+            }
+            TimelockActionKind::SetWhitelistContractId => {
+                vec![Role::WhitelistManager, Role::DAO]
+            }
+            TimelockActionKind::SetBeneficiary | TimelockActionKind::RemoveBeneficiary => {
+                vec![Role::BeneficiaryManager, Role::DAO]
+            }
+        }
+    }
+}
+
+impl TimelockAction {
+    pub fn kind(&self) -> TimelockActionKind {
+        match self {
+            TimelockAction::UpdateWeights { .. } => TimelockActionKind::UpdateWeights,
+            TimelockAction::UpdateBaseStakeAmounts { .. } => {
+                TimelockActionKind::UpdateBaseStakeAmounts
+            }
+            TimelockAction::SetWhitelistContractId { .. } => {
+                TimelockActionKind::SetWhitelistContractId
+            }
+            TimelockAction::SetBeneficiary { .. } => TimelockActionKind::SetBeneficiary,
+            TimelockAction::RemoveBeneficiary { .. } => TimelockActionKind::RemoveBeneficiary,
+        }
+    }
+}
+
+#[near(serializers = [borsh, json])]
+#[derive(Clone)]
+pub struct TimelockProposal {
+    pub id: u64,
+    pub action: TimelockAction,
+    pub proposer_id: AccountId,
+    pub queued_at: U64,
+    /// The earliest time the action can be executed
+    pub eta: U64,
+}
+
+#[near(serializers = [json])]
+pub struct TimelockDelay {
+    pub kind: TimelockActionKind,
+    pub delay_sec: u64,
+}
+
+#[near(serializers = [borsh])]
+pub struct Timelock {
+    pub delays: LookupMap<TimelockActionKind, u64>,
+    pub queue: IterableMap<u64, TimelockProposal>,
+    pub next_id: u64,
+}
+
+impl Timelock {
+    pub fn new() -> Self {
+        Self {
+            delays: LookupMap::new(StorageKey::TimelockDelays),
+            queue: IterableMap::new(StorageKey::TimelockQueue),
+            next_id: 0,
+        }
+    }
+
+    pub fn delay_sec(&self, kind: TimelockActionKind) -> u64 {
+        self.delays
+            .get(&kind)
+            .copied()
+            .unwrap_or_else(|| kind.default_delay_sec())
+    }
+}
+
+impl Default for Timelock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Contract {
+    /// Actions with a delay must go through the timelock queue.
+    pub(crate) fn assert_no_timelock(&self, kind: TimelockActionKind) {
+        require!(
+            self.data().timelock.delay_sec(kind) == 0,
+            ERR_TIMELOCK_REQUIRED
+        );
+    }
+
+    pub(crate) fn internal_queue_timelock_action(
+        &mut self,
+        action: TimelockAction,
+        proposer_id: AccountId,
+    ) -> u64 {
+        let now = env::block_timestamp();
+        let eta = now + self.data().timelock.delay_sec(action.kind()) * 1_000_000_000;
+        let id = self.data().timelock.next_id;
+        let proposal = TimelockProposal {
+            id,
+            action,
+            proposer_id,
+            queued_at: now.into(),
+            eta: eta.into(),
+        };
+        Event::TimelockQueued {
+            proposal: &proposal,
+        }
+        .emit();
+        self.data_mut().timelock.queue.insert(id, proposal);
+        self.data_mut().timelock.next_id += 1;
+        id
+    }
+
+    fn internal_execute_timelock_action(&mut self, action: TimelockAction) {
+        match action {
+            TimelockAction::UpdateWeights {
+                validator_ids,
+                weights,
+            } => self.internal_update_weights(validator_ids, weights),
+            TimelockAction::UpdateBaseStakeAmounts {
+                validator_ids,
+                amounts,
+            } => self.internal_update_base_stake_amounts(validator_ids, amounts),
+            TimelockAction::SetWhitelistContractId { account_id } => {
+                self.internal_set_whitelist_contract_id(account_id)
+            }
+            TimelockAction::SetBeneficiary { account_id, bps } => {
+                self.internal_set_beneficiary(account_id, bps)
+            }
+            TimelockAction::RemoveBeneficiary { account_id } => {
+                self.internal_remove_beneficiary(account_id)
+            }
+        }
+    }
+}
+
+#[near]
+impl Contract {
+    /// Queue an action to be executed after its delay.
+    /// The caller needs a role that could perform the action directly.
+    #[payable]
+    #[pause]
+    pub fn queue_timelock_action(&mut self, action: TimelockAction) -> u64 {
+        assert_one_yocto();
+        let proposer_id = env::predecessor_account_id();
+        let kind = action.kind();
+        require!(
+            self.acl_has_any_role(
+                kind.roles().into_iter().map(|role| role.into()).collect(),
+                proposer_id.clone()
+            ),
+            ERR_TIMELOCK_NOT_ALLOWED
+        );
+        // weight updates can't skip the approvals through the queue
+        if kind == TimelockActionKind::UpdateWeights {
+            self.assert_no_approval_required();
+        }
+        self.internal_queue_timelock_action(action, proposer_id)
+    }
+
+    #[payable]
+    #[access_control_any(roles(Role::DAO))]
+    #[pause]
+    pub fn cancel_timelock_action(&mut self, id: u64) {
+        assert_one_yocto();
+        self.data_mut()
+            .timelock
+            .queue
+            .remove(&id)
+            .expect(ERR_TIMELOCK_PROPOSAL_NOT_EXIST);
+        Event::TimelockCancelled { id }.emit();
+    }
+
+    /// Execute a queued action once its delay has passed. Anyone can call it.
+    #[pause]
+    pub fn execute_timelock_action(&mut self, id: u64) {
+        let proposal = self
+            .data()
+            .timelock
+            .queue
+            .get(&id)
+            .cloned()
+            .expect(ERR_TIMELOCK_PROPOSAL_NOT_EXIST);
+        require!(
+            env::block_timestamp() >= proposal.eta.0,
+            ERR_TIMELOCK_NOT_READY
+        );
+        self.data_mut().timelock.queue.remove(&id);
+        Event::TimelockExecuted { id }.emit();
+        self.internal_execute_timelock_action(proposal.action);
+    }
+
+    /// Set the delay of an action kind. Actions with a delay can
+    /// only be performed through the queue. Queued actions keep their eta.
+    #[payable]
+    #[access_control_any(roles(Role::DAO))]
+    #[pause]
+    pub fn set_timelock_delay(&mut self, kind: TimelockActionKind, delay_sec: u64) {
+        assert_one_yocto();
+        require!(
+            delay_sec <= MAX_TIMELOCK_DELAY_SEC,
+            ERR_TIMELOCK_DELAY_TOO_LONG
+        );
+        Event::SetTimelockDelay {
+            kind,
+            old_delay_sec: self.data().timelock.delay_sec(kind),
+            new_delay_sec: delay_sec,
+        }
+        .emit();
+        self.data_mut().timelock.delays.insert(kind, delay_sec);
+    }
+
+    pub fn get_timelock_delays(&self) -> Vec<TimelockDelay> {
+        TimelockActionKind::ALL
+            .into_iter()
+            .map(|kind| TimelockDelay {
+                kind,
+                delay_sec: self.data().timelock.delay_sec(kind),
+            })
+            .collect()
+    }
+
+    pub fn get_timelock_queue(
+        &self,
+        from_index: Option<usize>,
+        limit: Option<usize>,
+    ) -> Vec<TimelockProposal> {
+        let skip_n = from_index.unwrap_or(0);
+        let take_n = limit.unwrap_or(self.data().timelock.queue.len() as usize);
+        self.data()
+            .timelock
+            .queue
+            .values()
+            .skip(skip_n)
+            .take(take_n)
+            .cloned()
+            .collect()
+    }
+}
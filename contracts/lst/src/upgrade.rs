@@ -1,5 +1,95 @@
 use crate::*;
 
+/// Contract state of v1.0.1
+#[near(serializers = [borsh])]
+pub struct ContractDataV0 {
+    pub token: FungibleToken,
+    pub metadata: LazyOption<FungibleTokenMetadata>,
+    owner_id: AccountId,
+    total_staked_asset_in_near: Balance,
+    accounts: IterableMap<AccountId, AccountV0>,
+    account_storage_usage: StorageUsage,
+    beneficiaries: IterableMap<AccountId, u32>,
+    validator_pool: ValidatorPoolV0,
+    rnear_contract_id: TokenId,
+    rnear_price: EstimatedBalance,
+    rnear_balance: Balance,
+    wnear_contract_id: TokenId,
+    burrow_contract_id: AccountId,
+    whitelist_account_id: Option<AccountId>,
+    epoch_requested_stake_amount: u128,
+    epoch_requested_unstake_amount: u128,
+    stake_amount_to_settle: u128,
+    unstake_amount_to_settle: u128,
+    last_settlement_epoch: EpochHeight,
+    last_settlement_initiated_epoch: EpochHeight,
+}
+
+impl From<ContractDataV0> for ContractData {
+    fn from(data: ContractDataV0) -> Self {
+        // v1.0.1 holds the NEAR of unstaked balances in the contract balance,
+        // next to the NEAR waiting to be staked
+        let withdrawal_reserve = env::account_balance()
+            .saturating_sub(env::storage_byte_cost().saturating_mul(env::storage_usage() as u128))
+            .saturating_sub(Config::default().contract_min_reserve_balance())
+            .as_yoctonear()
+            .saturating_sub(data.epoch_requested_stake_amount + data.stake_amount_to_settle);
+        Self {
+            token: data.token,
+            metadata: data.metadata,
+            owner_id: data.owner_id,
+            total_staked_asset_in_near: data.total_staked_asset_in_near,
+            accounts: IterableMap::new(StorageKey::VersionedAccounts),
+            legacy_accounts: data.accounts,
+            account_storage_usage: data.account_storage_usage,
+            beneficiaries: data.beneficiaries,
+            validator_pool: data.validator_pool.into(),
+            rnear_contract_id: data.rnear_contract_id,
+            rnear_price: data.rnear_price,
+            rnear_balance: data.rnear_balance,
+            wnear_contract_id: data.wnear_contract_id,
+            burrow_contract_id: data.burrow_contract_id,
+            whitelist_account_id: data.whitelist_account_id,
+            epoch_requested_stake_amount: data.epoch_requested_stake_amount,
+            epoch_requested_unstake_amount: data.epoch_requested_unstake_amount,
+            stake_amount_to_settle: data.stake_amount_to_settle,
+            unstake_amount_to_settle: data.unstake_amount_to_settle,
+            last_settlement_epoch: data.last_settlement_epoch,
+            last_settlement_initiated_epoch: data.last_settlement_initiated_epoch,
+            withdrawal_reserve,
+            withdrawal_reserve_advance: 0,
+            unstake_queue: UnstakeQueue::new(),
+            directed_stakes: LookupMap::new(StorageKey::DirectedStakes),
+            partners: IterableMap::new(StorageKey::Partners),
+            redelegations: IterableMap::new(StorageKey::Redelegations),
+            next_redelegation_id: 0,
+            redelegation_in_transit_amount: 0,
+            keeper_rewards: KeeperRewards::new(),
+            fee_config: FeeConfig::default(),
+            pending_fee_config: None,
+            fee_change_delay_epochs: DEFAULT_FEE_CHANGE_DELAY_EPOCHS,
+            pending_fee_change_delay: None,
+            beneficiary_accounts: IterableMap::new(StorageKey::BeneficiaryAccounts),
+            beneficiary_payout_history: RingBuffer::new(
+                StorageKey::BeneficiaryPayoutHistory,
+                BENEFICIARY_PAYOUT_HISTORY_SIZE,
+            ),
+            reward_vesting: RewardVesting::default(),
+            referrals: Referrals::new(),
+            price_history: RingBuffer::new(StorageKey::PriceHistory, PRICE_HISTORY_SIZE),
+            reward_cap: RewardCap::new(),
+            owner_proposal: None,
+            timelock: Timelock::new(),
+            emergency: None,
+            flow_limits: FlowLimits::default(),
+            accounting: Accounting::default(),
+            config: VersionedConfig::V1(Config::default()),
+            approvals: Approvals::new(),
+            exit_only_mode: false,
+        }
+    }
+}
+
 #[near]
 impl Contract {
     /// A method to migrate a state during the contract upgrade.
@@ -7,11 +97,28 @@ impl Contract {
     #[private]
     #[init(ignore_state)]
     pub fn migrate_state() -> Self {
-        let mut contract: Contract =
-            env::state_read().expect("ContractStateIsMissing");
+        let mut contract: Contract = env::state_read().expect("ContractStateIsMissing");
+        let from_v0 = matches!(contract.data, VersionedContractData::V0(_));
         contract.data = match contract.data {
-            VersionedContractData::Current(data) => VersionedContractData::Current(data),
+            VersionedContractData::V0(data) => VersionedContractData::V1(data.into()),
+            VersionedContractData::V1(data) => VersionedContractData::V1(data),
         };
+
+        if from_v0 {
+            // validators are converted at once, so that the conversion sees the migration block
+            contract.data_mut().validator_pool.migrate_validators();
+
+            // owner-only methods moved onto roles, which the current owner keeps
+            let owner_id = contract.data().owner_id.clone();
+            for role in [Role::BeneficiaryManager, Role::WhitelistManager] {
+                contract
+                    .acl_get_or_init()
+                    .grant_role_unchecked(role, &owner_id);
+            }
+        }
+
+        // the account layout may have changed
+        contract.measure_storage_usage();
         contract
     }
 
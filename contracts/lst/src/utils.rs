@@ -7,39 +7,9 @@ pub const NO_DEPOSIT: u128 = 0;
 pub const ONE_NEAR: u128 = 1_000_000_000_000_000_000_000_000;
 pub const INIT_STAKING_AMOUNT: u128 = 10 * ONE_NEAR;
 pub const INIT_STORAGE_OCCUPY: u128 = 10 * ONE_NEAR;
-/// The number of epochs required for the locked balance to become unlocked.
-/// NOTE: The actual number of epochs when the funds are unlocked is 3. But there is a corner case
-/// when the unstaking promise can arrive at the next epoch, while the inner state is already
-/// updated in the previous epoch. It will not unlock the funds for 4 epochs.
-pub const NUM_EPOCHS_TO_UNLOCK: EpochHeight = 4;
 /// Full basis points, i.e. 10,000
 pub const FULL_BASIS_POINTS: u32 = 10_000;
 
-pub const MAX_BENEFICIARIES: u32 = 10;
-
-/// min NEAR balance this contract should hold in order to cover storage
-pub const CONTRACT_MIN_RESERVE_BALANCE: NearToken = NearToken::from_yoctonear(ONE_NEAR);
-
-pub const GAS_EPOCH_STAKE: Gas = Gas::from_tgas(75);
-pub const GAS_EPOCH_UNSTAKE: Gas = Gas::from_tgas(75);
-pub const GAS_EPOCH_UPDATE_REWARDS: Gas = Gas::from_tgas(75);
-pub const GAS_EPOCH_WITHDRAW: Gas = Gas::from_tgas(75);
-pub const GAS_SYNC_BALANCE: Gas = Gas::from_tgas(75);
-pub const GAS_DRAIN_UNSTAKE: Gas = Gas::from_tgas(75);
-pub const GAS_DRAIN_WITHDRAW: Gas = Gas::from_tgas(75);
-pub const GAS_EXT_DEPOSIT_AND_STAKE: Gas = Gas::from_tgas(75);
-pub const GAS_EXT_UNSTAKE: Gas = Gas::from_tgas(75);
-pub const GAS_EXT_GET_BALANCE: Gas = Gas::from_tgas(25);
-pub const GAS_EXT_GET_ACCOUNT: Gas = Gas::from_tgas(25);
-pub const GAS_EXT_WITHDRAW: Gas = Gas::from_tgas(75);
-pub const GAS_EXT_WHITELIST: Gas = Gas::from_tgas(10);
-pub const GAS_CB_VALIDATOR_SYNC_BALANCE: Gas = Gas::from_tgas(25);
-pub const GAS_CB_VALIDATOR_STAKED: Gas = Gas::from_tgas(25);
-pub const GAS_CB_VALIDATOR_UNSTAKED: Gas = Gas::from_tgas(25);
-pub const GAS_CB_VALIDATOR_GET_BALANCE: Gas = Gas::from_tgas(25);
-pub const GAS_CB_VALIDATOR_WITHDRAW: Gas = Gas::from_tgas(25);
-pub const GAS_CB_WHITELIST: Gas = Gas::from_tgas(15);
-
 construct_uint! {
     /// 256-bit unsigned integer.
     #[near(serializers = [borsh, json])]
@@ -7,6 +7,8 @@ pub struct CandidateValidator {
 
 #[near(serializers = [borsh])]
 pub enum VersionedValidator {
+    /// Validators stored by v1.0.1, saved as `Current` when the state is migrated
+    V0(ValidatorV0),
     Current(Validator),
 }
 
@@ -16,6 +18,26 @@ impl From<Validator> for VersionedValidator {
     }
 }
 
+#[near(serializers = [borsh, json])]
+#[derive(Clone, Copy, PartialEq)]
+pub enum ExecutionKind {
+    Stake,
+    Unstake,
+    GetBalance,
+    SyncBalance,
+    Withdraw,
+}
+
+/// The action a validator is executing, and the block height it started
+#[near(serializers = [borsh, json])]
+#[derive(Clone)]
+pub struct ExecutionInfo {
+    pub kind: ExecutionKind,
+    pub block_height: BlockHeight,
+    /// The amount of a stake, unstake or withdraw
+    pub amount: U128,
+}
+
 #[near(serializers = [borsh, json])]
 #[derive(Clone)]
 pub struct Validator {
@@ -27,6 +49,14 @@ pub struct Validator {
 
     /// The base stake amount on this validator.
     pub base_stake_amount: u128,
+    /// The amount of LST shares whose holders chose to stake on this validator.
+    pub directed_shares: u128,
+    /// The part of unstaked amount that belongs to redelegations,
+    /// which is not withdrawn by epoch withdraw.
+    pub redelegation_unstaked_amount: u128,
+
+    /// the epoch when rewards were last refreshed from this validator
+    pub rewards_updated_epoch: EpochHeight,
 
     /// the epoch num when latest unstake action happened on this validator
     pub unstake_fired_epoch: EpochHeight,
@@ -36,13 +66,55 @@ pub struct Validator {
 
     /// Whether the validator is in draining process
     pub draining: bool,
-    /// Whether the validator is executing actions
+    /// The action the validator is executing, if any
+    pub executing: Option<ExecutionInfo>,
+}
+
+/// Validator layout of v1.0.1
+#[near(serializers = [borsh])]
+#[derive(Clone)]
+pub struct ValidatorV0 {
+    pub account_id: AccountId,
+    pub weight: u16,
+    pub staked_amount: u128,
+    pub unstaked_amount: u128,
+    pub base_stake_amount: u128,
+    pub unstake_fired_epoch: EpochHeight,
+    pub last_unstake_fired_epoch: EpochHeight,
+    pub draining: bool,
     pub executing: bool,
 }
 
+impl From<ValidatorV0> for Validator {
+    fn from(v: ValidatorV0) -> Self {
+        Self {
+            account_id: v.account_id,
+            weight: v.weight,
+            staked_amount: v.staked_amount,
+            unstaked_amount: v.unstaked_amount,
+            base_stake_amount: v.base_stake_amount,
+            directed_shares: 0,
+            redelegation_unstaked_amount: 0,
+            // the reward cap counts the epochs since the migration
+            rewards_updated_epoch: get_epoch_height(),
+            unstake_fired_epoch: v.unstake_fired_epoch,
+            last_unstake_fired_epoch: v.last_unstake_fired_epoch,
+            draining: v.draining,
+            // The kind of an old execution is unknown. It is treated as a balance sync
+            // started at the migration, so that it can be reset after the execution timeout.
+            executing: v.executing.then_some(ExecutionInfo {
+                kind: ExecutionKind::SyncBalance,
+                block_height: env::block_height(),
+                amount: U128(0),
+            }),
+        }
+    }
+}
+
 impl From<&VersionedValidator> for Validator {
     fn from(value: &VersionedValidator) -> Self {
         match value {
+            VersionedValidator::V0(v) => v.clone().into(),
             VersionedValidator::Current(v) => v.clone(),
         }
     }
@@ -51,7 +123,8 @@ impl From<&VersionedValidator> for Validator {
 impl From<VersionedValidator> for Validator {
     fn from(value: VersionedValidator) -> Self {
         match value {
-            VersionedValidator::Current(v) => v.clone(),
+            VersionedValidator::V0(v) => v.into(),
+            VersionedValidator::Current(v) => v,
         }
     }
 }
@@ -61,11 +134,14 @@ pub struct ValidatorInfo {
     pub account_id: AccountId,
     pub weight: u16,
     pub base_stake_amount: U128,
+    pub directed_shares: U128,
+    pub directed_stake_amount: U128,
     pub target_stake_amount: U128,
     pub staked_amount: U128,
     pub unstaked_amount: U128,
     pub pending_release: bool,
     pub draining: bool,
+    pub executing: Option<ExecutionInfo>,
 }
 
 impl Validator {
@@ -74,53 +150,103 @@ impl Validator {
             account_id,
             weight,
             base_stake_amount: 0,
+            directed_shares: 0,
+            redelegation_unstaked_amount: 0,
             staked_amount: 0,
             unstaked_amount: 0,
+            // nothing is staked before, so there are no rewards to cap yet
+            rewards_updated_epoch: get_epoch_height(),
             unstake_fired_epoch: 0,
             last_unstake_fired_epoch: 0,
             draining: false,
-            executing: false,
+            executing: None,
         }
     }
 
-    pub fn get_info(&self, pool: &ValidatorPool, total_staked_near_amount: u128) -> ValidatorInfo {
+    pub fn get_info(
+        &self,
+        pool: &ValidatorPool,
+        total_staked_near_amount: u128,
+        total_share_amount: u128,
+        config: &Config,
+    ) -> ValidatorInfo {
         ValidatorInfo {
             account_id: self.account_id.clone(),
             weight: self.weight,
             base_stake_amount: self.base_stake_amount.into(),
+            directed_shares: self.directed_shares.into(),
+            directed_stake_amount: pool
+                .validator_directed_stake_amount(total_staked_near_amount, total_share_amount, self)
+                .into(),
             target_stake_amount: pool
-                .validator_target_stake_amount(total_staked_near_amount, self)
+                .validator_target_stake_amount(total_staked_near_amount, total_share_amount, self)
                 .into(),
             staked_amount: self.staked_amount.into(),
             unstaked_amount: self.unstaked_amount.into(),
-            pending_release: self.pending_release(),
+            pending_release: self.pending_release(config),
             draining: self.draining,
+            executing: self.executing.clone(),
         }
     }
 
+    pub fn is_executing(&self) -> bool {
+        self.executing.is_some()
+    }
+
+    /// whether the validator has been executing an action for too long,
+    /// which means the callback never finished.
+    pub fn is_stuck(&self, config: &Config) -> bool {
+        self.executing.as_ref().is_some_and(|execution| {
+            env::block_height() >= execution.block_height + config.execution_timeout_blocks
+        })
+    }
+
     pub fn total_balance(&self) -> u128 {
         self.staked_amount + self.unstaked_amount
     }
 
+    /// The unstaked amount that can be withdrawn by epoch withdraw.
+    /// Redelegated NEAR is withdrawn by redelegation_withdraw.
+    pub fn withdrawable_amount(&self) -> u128 {
+        self.unstaked_amount
+            .saturating_sub(self.redelegation_unstaked_amount)
+    }
+
+    /// whether rewards have not been refreshed in the current epoch
+    pub fn need_rewards_update(&self) -> bool {
+        self.rewards_updated_epoch < get_epoch_height() && self.total_balance() > 0
+    }
+
+    /// whether there is matured unstaked balance to withdraw
+    pub fn need_withdraw(&self, config: &Config) -> bool {
+        !self.draining && !self.pending_release(config) && self.withdrawable_amount() > 0
+    }
+
     /// whether the validator is in unstake releasing period.
-    pub fn pending_release(&self) -> bool {
+    pub fn pending_release(&self, config: &Config) -> bool {
         let current_epoch = get_epoch_height();
         current_epoch >= self.unstake_fired_epoch
-            && current_epoch < self.unstake_fired_epoch + NUM_EPOCHS_TO_UNLOCK
+            && current_epoch < self.unstake_fired_epoch + config.num_epochs_to_unlock
     }
 
-    pub fn deposit_and_stake(&mut self, pool: &mut ValidatorPool, amount: u128) -> Promise {
-        self.pre_execution(pool);
+    pub fn deposit_and_stake(
+        &mut self,
+        pool: &mut ValidatorPool,
+        amount: u128,
+        config: &Config,
+    ) -> Promise {
+        self.pre_execution(pool, ExecutionKind::Stake, amount);
 
         ext_staking_pool::ext(self.account_id.clone())
             .with_attached_deposit(NearToken::from_yoctonear(amount))
-            .with_static_gas(GAS_EXT_DEPOSIT_AND_STAKE)
+            .with_static_gas(config.gas.ext_deposit_and_stake)
             .deposit_and_stake()
     }
 
     pub fn on_stake_success(&mut self, pool: &mut ValidatorPool, amount: u128) {
         // Do not call post_execution() here because we need to sync account balance after stake
         self.staked_amount += amount;
+        self.on_execution_settled();
         pool.save_validator(self);
     }
 
@@ -128,9 +254,12 @@ impl Validator {
         self.post_execution(pool);
     }
 
-    pub fn unstake(&mut self, pool: &mut ValidatorPool, amount: u128) -> Promise {
+    pub fn unstake(&mut self, pool: &mut ValidatorPool, amount: u128, config: &Config) -> Promise {
         // avoid unstake from a validator which is pending release
-        require!(!self.pending_release(), ERR_VALIDATOR_UNSTAKE_WHEN_LOCKED);
+        require!(
+            !self.pending_release(config),
+            ERR_VALIDATOR_UNSTAKE_WHEN_LOCKED
+        );
 
         require!(
             amount <= self.staked_amount,
@@ -140,7 +269,7 @@ impl Validator {
             )
         );
 
-        self.pre_execution(pool);
+        self.pre_execution(pool, ExecutionKind::Unstake, amount);
 
         self.last_unstake_fired_epoch = self.unstake_fired_epoch;
         self.unstake_fired_epoch = get_epoch_height();
@@ -148,7 +277,7 @@ impl Validator {
         pool.save_validator(self);
 
         ext_staking_pool::ext(self.account_id.clone())
-            .with_static_gas(GAS_EXT_UNSTAKE)
+            .with_static_gas(config.gas.ext_unstake)
             .unstake(amount.into())
     }
 
@@ -156,6 +285,7 @@ impl Validator {
         // Do not call post_execution() here because we need to sync account balance after unstake
         self.staked_amount -= amount;
         self.unstaked_amount += amount;
+        self.on_execution_settled();
         pool.save_validator(self);
     }
 
@@ -166,11 +296,11 @@ impl Validator {
         pool.save_validator(self);
     }
 
-    pub fn refresh_total_balance(&mut self, pool: &mut ValidatorPool) -> Promise {
-        self.pre_execution(pool);
+    pub fn refresh_total_balance(&mut self, pool: &mut ValidatorPool, config: &Config) -> Promise {
+        self.pre_execution(pool, ExecutionKind::GetBalance, 0);
 
         ext_staking_pool::ext(self.account_id.clone())
-            .with_static_gas(GAS_EXT_GET_BALANCE)
+            .with_static_gas(config.gas.ext_get_balance)
             .get_account_total_balance(env::current_account_id())
     }
 
@@ -181,6 +311,15 @@ impl Validator {
         self.sync_base_stake_amount(pool, new_total_balance);
         // update staked amount
         self.staked_amount = new_total_balance - self.unstaked_amount;
+        self.rewards_updated_epoch = get_epoch_height();
+        pool.save_validator(self);
+    }
+
+    /// The validator reported less than its known balance. The known balance is
+    /// kept, so that later rewards make up for the shortfall before they are credited.
+    pub fn on_total_balance_shortfall(&mut self, pool: &mut ValidatorPool) {
+        self.post_execution(pool);
+        self.rewards_updated_epoch = get_epoch_height();
         pool.save_validator(self);
     }
 
@@ -196,15 +335,21 @@ impl Validator {
     /// Params:
     /// - pool: validator pool
     /// - post_action: sync balance is called after stake or unstake
-    pub fn sync_account_balance(&mut self, pool: &mut ValidatorPool, post_action: bool) -> Promise {
+    /// - config: contract config
+    pub fn sync_account_balance(
+        &mut self,
+        pool: &mut ValidatorPool,
+        post_action: bool,
+        config: &Config,
+    ) -> Promise {
         if post_action {
-            require!(self.executing, ERR_VALIDATOR_SYNC_BALANCE_NOT_EXPECTED);
+            require!(self.is_executing(), ERR_VALIDATOR_SYNC_BALANCE_NOT_EXPECTED);
         } else {
-            self.pre_execution(pool);
+            self.pre_execution(pool, ExecutionKind::SyncBalance, 0);
         }
 
         ext_staking_pool::ext(self.account_id.clone())
-            .with_static_gas(GAS_EXT_GET_ACCOUNT)
+            .with_static_gas(config.gas.ext_get_account)
             .get_account(env::current_account_id())
     }
 
@@ -231,20 +376,23 @@ impl Validator {
         self.post_execution(pool);
     }
 
-    pub fn withdraw(&mut self, pool: &mut ValidatorPool, amount: u128) -> Promise {
-        self.pre_execution(pool);
+    pub fn withdraw(&mut self, pool: &mut ValidatorPool, amount: u128, config: &Config) -> Promise {
+        self.pre_execution(pool, ExecutionKind::Withdraw, amount);
 
         require!(
             self.unstaked_amount >= amount,
             ERR_NO_ENOUGH_WITHDRAW_BALANCE
         );
-        require!(!self.pending_release(), ERR_VALIDATOR_WITHDRAW_WHEN_LOCKED);
+        require!(
+            !self.pending_release(config),
+            ERR_VALIDATOR_WITHDRAW_WHEN_LOCKED
+        );
 
         self.unstaked_amount -= amount;
         pool.save_validator(self);
 
         ext_staking_pool::ext(self.account_id.clone())
-            .with_static_gas(GAS_EXT_WITHDRAW)
+            .with_static_gas(config.gas.ext_withdraw)
             .withdraw(amount.into())
     }
 
@@ -278,14 +426,42 @@ impl Validator {
         }
     }
 
-    fn pre_execution(&mut self, pool: &mut ValidatorPool) {
-        require!(!self.executing, ERR_VALIDATOR_ALREADY_EXECUTING_ACTION);
-        self.executing = true;
+    /// Take over a stuck execution and sync balances with the validator.
+    /// The execution flag is cleared once the balances are synced.
+    pub fn reset_stuck_execution(&mut self, pool: &mut ValidatorPool, config: &Config) -> Promise {
+        require!(self.is_stuck(config), ERR_VALIDATOR_NOT_STUCK);
+        self.executing = Some(ExecutionInfo {
+            kind: ExecutionKind::SyncBalance,
+            block_height: env::block_height(),
+            amount: U128(0),
+        });
+        pool.save_validator(self);
+
+        ext_staking_pool::ext(self.account_id.clone())
+            .with_static_gas(config.gas.ext_get_account)
+            .get_account(env::current_account_id())
+    }
+
+    fn pre_execution(&mut self, pool: &mut ValidatorPool, kind: ExecutionKind, amount: u128) {
+        require!(!self.is_executing(), ERR_VALIDATOR_ALREADY_EXECUTING_ACTION);
+        self.executing = Some(ExecutionInfo {
+            kind,
+            block_height: env::block_height(),
+            amount: U128(amount),
+        });
         pool.save_validator(self);
     }
 
+    /// The action has been applied to the balances, only the balance sync is left.
+    fn on_execution_settled(&mut self) {
+        if let Some(execution) = self.executing.as_mut() {
+            execution.kind = ExecutionKind::SyncBalance;
+            execution.amount = U128(0);
+        }
+    }
+
     fn post_execution(&mut self, pool: &mut ValidatorPool) {
-        self.executing = false;
+        self.executing = None;
         pool.save_validator(self);
     }
 }
@@ -1,7 +1,6 @@
 use crate::*;
 use std::cmp::{max, min, Ordering};
 
-const STAKE_SMALL_CHANGE_AMOUNT: u128 = ONE_NEAR;
 const MAX_UPDATE_WEIGHTS_COUNT: usize = 300;
 
 #[ext_contract(ext_staking_pool)]
@@ -39,6 +38,28 @@ pub struct ValidatorPool {
     pub validators: IterableMap<AccountId, VersionedValidator>,
     pub total_weight: u16,
     pub total_base_stake_amount: u128,
+    /// Total directed shares on validators with positive weight
+    pub total_directed_shares: u128,
+}
+
+/// Validator pool layout of v1.0.1, converted by `migrate_state`
+#[near(serializers = [borsh])]
+pub struct ValidatorPoolV0 {
+    pub validators: IterableMap<AccountId, VersionedValidator>,
+    pub total_weight: u16,
+    pub total_base_stake_amount: u128,
+}
+
+impl From<ValidatorPoolV0> for ValidatorPool {
+    fn from(pool: ValidatorPoolV0) -> Self {
+        // no directed stake existed before
+        Self {
+            validators: pool.validators,
+            total_weight: pool.total_weight,
+            total_base_stake_amount: pool.total_base_stake_amount,
+            total_directed_shares: 0,
+        }
+    }
 }
 
 impl Default for ValidatorPool {
@@ -53,6 +74,7 @@ impl ValidatorPool {
             validators: IterableMap::new(StorageKey::Validators),
             total_weight: 0,
             total_base_stake_amount: 0,
+            total_directed_shares: 0,
         }
     }
 
@@ -80,57 +102,93 @@ impl ValidatorPool {
             .collect()
     }
 
+    /// The NEAR amount that LST holders directed to this validator.
+    /// Directed stake only applies to validators with positive weight,
+    /// otherwise it falls back to the pool weights.
+    pub fn validator_directed_stake_amount(
+        &self,
+        total_staked_near_amount: u128,
+        total_share_amount: u128,
+        validator: &Validator,
+    ) -> u128 {
+        if validator.weight == 0 || total_share_amount == 0 {
+            return 0;
+        }
+        (U256::from(validator.directed_shares) * U256::from(total_staked_near_amount)
+            / U256::from(total_share_amount))
+        .as_u128()
+    }
+
     pub fn validator_target_stake_amount(
         &self,
         total_staked_near_amount: u128,
+        total_share_amount: u128,
         validator: &Validator,
     ) -> u128 {
-        let base_stake_amount = if total_staked_near_amount >= self.total_base_stake_amount {
-            validator.base_stake_amount
+        // Base stake amounts and directed stake amounts are both reserved before
+        // the rest is distributed by weights.
+        let total_directed_stake_amount = if total_share_amount == 0 {
+            0
+        } else {
+            (U256::from(self.total_directed_shares) * U256::from(total_staked_near_amount)
+                / U256::from(total_share_amount))
+            .as_u128()
+        };
+        let total_reserved_stake_amount =
+            self.total_base_stake_amount + total_directed_stake_amount;
+        let reserved_stake_amount = validator.base_stake_amount
+            + self.validator_directed_stake_amount(
+                total_staked_near_amount,
+                total_share_amount,
+                validator,
+            );
+
+        let reserved_stake_amount = if total_staked_near_amount >= total_reserved_stake_amount {
+            reserved_stake_amount
         } else {
-            (U256::from(validator.base_stake_amount) * U256::from(total_staked_near_amount)
-                / U256::from(self.total_base_stake_amount))
+            (U256::from(reserved_stake_amount) * U256::from(total_staked_near_amount)
+                / U256::from(total_reserved_stake_amount))
             .as_u128()
         };
-        // If not enough staked NEAR, satisfy the base stake amount first (set dynamic stake amount to 0)
+        // If not enough staked NEAR, satisfy the reserved stake amount first (set dynamic stake amount to 0)
         let dynamic_stake_amount =
-            if validator.weight == 0 || total_staked_near_amount <= self.total_base_stake_amount {
+            if validator.weight == 0 || total_staked_near_amount <= total_reserved_stake_amount {
                 0
             } else {
-                (U256::from(total_staked_near_amount - self.total_base_stake_amount)
+                (U256::from(total_staked_near_amount - total_reserved_stake_amount)
                     * U256::from(validator.weight)
                     / U256::from(self.total_weight))
                 .as_u128()
             };
-        base_stake_amount + dynamic_stake_amount
+        reserved_stake_amount + dynamic_stake_amount
     }
 
-    pub fn get_num_epoch_to_unstake(&self, amount: u128) -> EpochHeight {
+    pub fn get_num_epoch_to_unstake(&self, amount: u128, config: &Config) -> EpochHeight {
         let mut available_amount: u128 = 0;
         let mut total_staked_amount: u128 = 0;
         for validator in self.validators.values() {
             let validator: Validator = validator.into();
             total_staked_amount += validator.staked_amount;
 
-            if !validator.pending_release() && validator.staked_amount > 0 {
+            if !validator.pending_release(config) && validator.staked_amount > 0 {
                 available_amount += validator.staked_amount;
             }
 
             // found enough balance to unstake from available validators
             if available_amount >= amount {
-                return NUM_EPOCHS_TO_UNLOCK;
+                return config.num_epochs_to_unlock;
             }
         }
 
         // nothing is actually staked, all balance should be available now
         // still leave a buffer for the user
         if total_staked_amount == 0 {
-            return NUM_EPOCHS_TO_UNLOCK;
+            return config.num_epochs_to_unlock;
         }
 
         // no enough available validators to unstake
         // double the unstake waiting time
-        2 * NUM_EPOCHS_TO_UNLOCK
+        2 * config.num_epochs_to_unlock
     }
 }
 
@@ -139,14 +197,23 @@ impl ValidatorPool {
         &self,
         amount: u128,
         total_staked_near_amount: u128,
+        total_share_amount: u128,
+        config: &Config,
     ) -> Option<CandidateValidator> {
         let mut candidate = None;
         let mut max_delta: u128 = 0;
 
         for (_, validator) in self.validators.iter() {
-            let validator = validator.into();
-            let target_amount =
-                self.validator_target_stake_amount(total_staked_near_amount, &validator);
+            let validator: Validator = validator.into();
+            // validators executing other actions can't be staked to
+            if validator.is_executing() {
+                continue;
+            }
+            let target_amount = self.validator_target_stake_amount(
+                total_staked_near_amount,
+                total_share_amount,
+                &validator,
+            );
             if validator.staked_amount < target_amount {
                 let delta = target_amount - validator.staked_amount;
                 if delta > max_delta {
@@ -158,7 +225,7 @@ impl ValidatorPool {
 
         let mut amount_to_stake: u128 = min(amount, max_delta);
 
-        if amount_to_stake > 0 && amount - amount_to_stake < STAKE_SMALL_CHANGE_AMOUNT {
+        if amount_to_stake > 0 && amount - amount_to_stake < config.stake_small_change_amount.0 {
             amount_to_stake = amount;
         }
 
@@ -171,18 +238,24 @@ impl ValidatorPool {
     fn filter_candidate_validators(
         &self,
         total_staked_near_amount: u128,
+        total_share_amount: u128,
+        config: &Config,
     ) -> Vec<(Validator, u128, u128)> {
         self.validators
             .values()
             .map(|versioned_validator| {
                 let validator = Validator::from(versioned_validator);
-                let target_amount =
-                    self.validator_target_stake_amount(total_staked_near_amount, &validator);
+                let target_amount = self.validator_target_stake_amount(
+                    total_staked_near_amount,
+                    total_share_amount,
+                    &validator,
+                );
                 (validator, target_amount)
             })
             .filter(|(validator, target_amount)| {
-                // validator is not in pending release
-                !validator.pending_release()
+                // validator is not in pending release or executing other actions
+                !validator.pending_release(config)
+                    && !validator.is_executing()
                     // delta must > 0
                     && validator.staked_amount > *target_amount
             })
@@ -237,8 +310,11 @@ impl ValidatorPool {
         &self,
         total_amount_to_unstake: u128,
         total_staked_near_amount: u128,
+        total_share_amount: u128,
+        config: &Config,
     ) -> Option<CandidateValidator> {
-        let mut candidate_validators = self.filter_candidate_validators(total_staked_near_amount);
+        let mut candidate_validators =
+            self.filter_candidate_validators(total_staked_near_amount, total_share_amount, config);
         if candidate_validators.is_empty() {
             return None;
         }
@@ -289,6 +365,19 @@ impl ValidatorPool {
             .insert(validator.account_id.clone(), validator.clone().into());
     }
 
+    /// Save validators stored by v1.0.1 in the current layout
+    pub fn migrate_validators(&mut self) {
+        let validators: Vec<Validator> = self
+            .validators
+            .values()
+            .filter(|v| matches!(v, VersionedValidator::V0(_)))
+            .map(|v| v.into())
+            .collect();
+        for validator in validators.iter() {
+            self.save_validator(validator);
+        }
+    }
+
     pub fn add_validator(&mut self, validator_id: &AccountId, weight: u16) -> Validator {
         require!(
             self.get_validator(validator_id).is_none(),
@@ -320,12 +409,17 @@ impl ValidatorPool {
 
         // make sure this validator is not used at all
         require!(
-            validator.staked_amount == 0 && validator.unstaked_amount == 0 && !validator.executing,
+            validator.staked_amount == 0
+                && validator.unstaked_amount == 0
+                && !validator.is_executing(),
             ERR_VALIDATOR_IN_USE
         );
 
         self.total_weight -= validator.weight;
         self.total_base_stake_amount -= validator.base_stake_amount;
+        if validator.weight > 0 {
+            self.total_directed_shares -= validator.directed_shares;
+        }
 
         Event::ValidatorRemoved {
             account_id: validator_id,
@@ -345,6 +439,12 @@ impl ValidatorPool {
         let old_weight = validator.weight;
         // update total weight
         self.total_weight = self.total_weight + weight - old_weight;
+        // directed stake only counts on validators with positive weight
+        if old_weight == 0 && weight > 0 {
+            self.total_directed_shares += validator.directed_shares;
+        } else if old_weight > 0 && weight == 0 {
+            self.total_directed_shares -= validator.directed_shares;
+        }
 
         validator.weight = weight;
         self.validators
@@ -353,6 +453,50 @@ impl ValidatorPool {
         old_weight
     }
 
+    pub fn update_directed_shares(
+        &mut self,
+        validator_id: &AccountId,
+        old_shares: u128,
+        new_shares: u128,
+    ) {
+        let mut validator: Validator = self
+            .validators
+            .get(validator_id)
+            .expect(ERR_VALIDATOR_NOT_EXIST)
+            .into();
+
+        let old_directed_shares = validator.directed_shares;
+        validator.directed_shares = old_directed_shares.saturating_sub(old_shares) + new_shares;
+        if validator.weight > 0 {
+            self.total_directed_shares =
+                self.total_directed_shares + validator.directed_shares - old_directed_shares;
+        }
+        self.validators
+            .insert(validator_id.clone(), validator.into());
+    }
+
+    /// Move base stake along with redelegated stake, so that the targets keep it on
+    /// the destination. The source gives up no more than its own base stake amount.
+    pub fn move_base_stake_amount(
+        &mut self,
+        from_validator_id: &AccountId,
+        to_validator_id: &AccountId,
+        amount: u128,
+    ) {
+        if let Some(from_validator) = self.get_validator(from_validator_id) {
+            if from_validator.base_stake_amount > 0 {
+                self.update_base_stake_amount(
+                    from_validator_id,
+                    from_validator.base_stake_amount.saturating_sub(amount),
+                );
+            }
+        }
+        let to_validator = self
+            .get_validator(to_validator_id)
+            .expect(ERR_VALIDATOR_NOT_EXIST);
+        self.update_base_stake_amount(to_validator_id, to_validator.base_stake_amount + amount);
+    }
+
     pub fn update_base_stake_amount(&mut self, validator_id: &AccountId, amount: u128) {
         let mut validator: Validator = self
             .validators
@@ -385,6 +529,7 @@ impl Contract {
     #[pause]
     pub fn add_validator(&mut self, validator_id: AccountId, weight: u16) {
         assert_one_yocto();
+        self.assert_scope_not_paused(PauseScope::ValidatorAdmin);
         self.add_whitelisted_validator(&validator_id, weight);
     }
 
@@ -393,6 +538,7 @@ impl Contract {
     #[pause]
     pub fn add_validators(&mut self, validator_ids: Vec<AccountId>, weights: Vec<u16>) {
         assert_one_yocto();
+        self.assert_scope_not_paused(PauseScope::ValidatorAdmin);
         require!(validator_ids.len() == weights.len(), ERR_BAD_VALIDATOR_LIST);
         for i in 0..validator_ids.len() {
             self.add_whitelisted_validator(&validator_ids[i], weights[i]);
@@ -404,6 +550,8 @@ impl Contract {
     #[pause]
     pub fn remove_validator(&mut self, validator_id: AccountId) -> Validator {
         assert_one_yocto();
+        self.assert_scope_not_paused(PauseScope::ValidatorAdmin);
+        self.assert_no_approval_required();
         self.data_mut()
             .validator_pool
             .remove_validator(&validator_id)
@@ -414,16 +562,9 @@ impl Contract {
     #[pause]
     pub fn update_weight(&mut self, validator_id: AccountId, weight: u16) {
         assert_one_yocto();
-        let old_weight = self
-            .data_mut()
-            .validator_pool
-            .update_weight(&validator_id, weight);
-        Event::ValidatorsUpdatedWeights {
-            account_ids: vec![&validator_id],
-            old_weights: vec![old_weight],
-            new_weights: vec![weight],
-        }
-        .emit();
+        self.assert_no_timelock(TimelockActionKind::UpdateWeights);
+        self.assert_no_approval_required();
+        self.internal_update_weights(vec![validator_id], vec![weight]);
     }
 
     #[payable]
@@ -431,36 +572,9 @@ impl Contract {
     #[pause]
     pub fn update_weights(&mut self, validator_ids: Vec<AccountId>, weights: Vec<u16>) {
         assert_one_yocto();
-        require!(validator_ids.len() == weights.len(), ERR_BAD_VALIDATOR_LIST);
-
-        require!(
-            validator_ids.len() <= MAX_UPDATE_WEIGHTS_COUNT,
-            format!(
-                "The number of validators to be updated at a time cannot exceed {}",
-                MAX_UPDATE_WEIGHTS_COUNT
-            )
-        );
-
-        let mut account_ids = Vec::new();
-        let mut old_weights = Vec::new();
-        let mut new_weights = Vec::new();
-
-        for i in 0..validator_ids.len() {
-            let old_weight = self
-                .data_mut()
-                .validator_pool
-                .update_weight(&validator_ids[i], weights[i]);
-            account_ids.push(&validator_ids[i]);
-            old_weights.push(old_weight);
-            new_weights.push(weights[i]);
-        }
-
-        Event::ValidatorsUpdatedWeights {
-            account_ids,
-            old_weights,
-            new_weights,
-        }
-        .emit();
+        self.assert_no_timelock(TimelockActionKind::UpdateWeights);
+        self.assert_no_approval_required();
+        self.internal_update_weights(validator_ids, weights);
     }
 
     #[payable]
@@ -468,19 +582,14 @@ impl Contract {
     #[pause]
     pub fn update_base_stake_amounts(&mut self, validator_ids: Vec<AccountId>, amounts: Vec<U128>) {
         assert_one_yocto();
-        require!(validator_ids.len() == amounts.len(), ERR_BAD_VALIDATOR_LIST);
-        for i in 0..validator_ids.len() {
-            self.data_mut()
-                .validator_pool
-                .update_base_stake_amount(&validator_ids[i], amounts[i].into());
-        }
+        self.assert_no_timelock(TimelockActionKind::UpdateBaseStakeAmounts);
+        self.internal_update_base_stake_amounts(validator_ids, amounts);
     }
 
     #[pause]
     pub fn sync_balance_from_validator(&mut self, validator_id: AccountId) {
-        let min_gas = GAS_SYNC_BALANCE.as_gas()
-            + GAS_EXT_GET_ACCOUNT.as_gas()
-            + GAS_CB_VALIDATOR_SYNC_BALANCE.as_gas();
+        let config = *self.internal_config();
+        let min_gas = config.gas.sync_balance.as_gas() + config.gas.sync_account_balance();
         require!(
             env::prepaid_gas().as_gas() >= min_gas,
             format!("{}. require at least {:?}", ERR_NO_ENOUGH_GAS, min_gas)
@@ -493,15 +602,70 @@ impl Contract {
             .expect(ERR_VALIDATOR_NOT_EXIST);
 
         validator
-            .sync_account_balance(&mut self.data_mut().validator_pool, false)
+            .sync_account_balance(&mut self.data_mut().validator_pool, false, &config)
             .then(
                 Self::ext(env::current_account_id())
-                    .with_static_gas(GAS_CB_VALIDATOR_SYNC_BALANCE)
+                    .with_static_gas(config.gas.cb_validator_sync_balance)
                     .with_unused_gas_weight(0)
                     .validator_get_account_callback(validator.account_id),
             );
     }
 
+    /// Recover a validator whose execution flag was left set by a callback
+    /// that never finished. Allowed only after the execution timeout.
+    /// Balances are synced with the validator before the flag is cleared.
+    #[access_control_any(roles(Role::OpManager, Role::DAO))]
+    #[pause]
+    pub fn reset_stuck_validator(&mut self, validator_id: AccountId) -> Promise {
+        let config = *self.internal_config();
+        let min_gas = config.gas.sync_balance.as_gas() + config.gas.sync_account_balance();
+        require!(
+            env::prepaid_gas().as_gas() >= min_gas,
+            format!("{}. require at least {:?}", ERR_NO_ENOUGH_GAS, min_gas)
+        );
+
+        let mut validator = self
+            .data_mut()
+            .validator_pool
+            .get_validator(&validator_id)
+            .expect(ERR_VALIDATOR_NOT_EXIST);
+        let execution = validator.executing.clone().expect(ERR_VALIDATOR_NOT_STUCK);
+
+        Event::ResetStuckValidatorAttempt {
+            validator_id: &validator_id,
+            kind: execution.kind,
+            block_height: execution.block_height,
+        }
+        .emit();
+
+        validator
+            .reset_stuck_execution(&mut self.data_mut().validator_pool, &config)
+            .then(
+                Self::ext(env::current_account_id())
+                    .with_static_gas(config.gas.cb_validator_sync_balance)
+                    .with_unused_gas_weight(0)
+                    .validator_reset_stuck_callback(validator.account_id, execution),
+            )
+    }
+
+    /// Returns validators that have been executing an action longer than the timeout.
+    pub fn get_stuck_validators(&self) -> Vec<ValidatorInfo> {
+        self.data()
+            .validator_pool
+            .get_validators(None, None)
+            .iter()
+            .filter(|v| v.is_stuck(self.internal_config()))
+            .map(|v| {
+                v.get_info(
+                    &self.data().validator_pool,
+                    self.data().total_staked_asset_in_near,
+                    self.data().token.total_supply,
+                    self.internal_config(),
+                )
+            })
+            .collect()
+    }
+
     /// This method is designed to drain a validator.
     /// The weight of target validator should be set to 0 before calling this.
     /// And a following call to drain_withdraw MUST be made after 4 epochs.
@@ -510,21 +674,46 @@ impl Contract {
     #[pause]
     pub fn drain_unstake(&mut self, validator_id: AccountId) -> Promise {
         assert_one_yocto();
+        self.assert_scope_not_paused(PauseScope::ValidatorAdmin);
+        self.assert_no_approval_required();
+        // make sure enough gas was given
+        let gas = self.internal_config().gas;
+        let min_gas = gas.drain_unstake.as_gas() + gas.unstake();
+        require!(
+            env::prepaid_gas().as_gas() >= min_gas,
+            format!("{}. require at least {:?}", ERR_NO_ENOUGH_GAS, min_gas)
+        );
+
+        self.internal_drain_unstake(&validator_id, None)
+    }
+
+    /// Withdraw from a drained validator
+    #[pause]
+    pub fn drain_withdraw(&mut self, validator_id: AccountId) {
         // make sure enough gas was given
-        let min_gas = GAS_DRAIN_UNSTAKE.as_gas()
-            + GAS_EXT_UNSTAKE.as_gas()
-            + GAS_CB_VALIDATOR_UNSTAKED.as_gas()
-            + GAS_SYNC_BALANCE.as_gas()
-            + GAS_CB_VALIDATOR_SYNC_BALANCE.as_gas();
+        let gas = self.internal_config().gas;
+        let min_gas = gas.drain_withdraw.as_gas() + gas.withdraw();
         require!(
             env::prepaid_gas().as_gas() >= min_gas,
             format!("{}. require at least {:?}", ERR_NO_ENOUGH_GAS, min_gas)
         );
 
+        self.internal_drain_withdraw(&validator_id, None);
+    }
+}
+
+impl Contract {
+    /// The keeper, if any, is rewarded once the unstake succeeds.
+    pub(crate) fn internal_drain_unstake(
+        &mut self,
+        validator_id: &AccountId,
+        keeper_id: Option<AccountId>,
+    ) -> Promise {
+        let config = *self.internal_config();
         let mut validator = self
             .data_mut()
             .validator_pool
-            .get_validator(&validator_id)
+            .get_validator(validator_id)
             .expect(ERR_VALIDATOR_NOT_EXIST);
 
         // make sure the validator:
@@ -539,7 +728,7 @@ impl Contract {
             ERR_NON_ZERO_BASE_STAKE_AMOUNT
         );
         require!(
-            !validator.pending_release(),
+            !validator.pending_release(&config),
             ERR_VALIDATOR_UNSTAKE_WHEN_LOCKED
         );
         // in practice we allow 1 NEAR due to the precision of stake operation
@@ -552,44 +741,37 @@ impl Contract {
         let unstake_amount = validator.staked_amount;
 
         Event::DrainUnstakeAttempt {
-            validator_id: &validator_id,
+            validator_id,
             amount: &U128(unstake_amount),
         }
         .emit();
 
         // perform actual unstake
         validator
-            .unstake(&mut self.data_mut().validator_pool, unstake_amount)
+            .unstake(&mut self.data_mut().validator_pool, unstake_amount, &config)
             .then(
                 Self::ext(env::current_account_id())
-                    .with_static_gas(
-                        GAS_CB_VALIDATOR_UNSTAKED
-                            .checked_add(GAS_SYNC_BALANCE)
-                            .unwrap()
-                            .checked_add(GAS_CB_VALIDATOR_SYNC_BALANCE)
-                            .unwrap(),
-                    )
+                    .with_static_gas(config.gas.unstaked_callback())
                     .with_unused_gas_weight(0)
-                    .validator_drain_unstaked_callback(validator.account_id, unstake_amount.into()),
+                    .validator_drain_unstaked_callback(
+                        validator.account_id,
+                        unstake_amount.into(),
+                        keeper_id,
+                    ),
             )
     }
 
-    /// Withdraw from a drained validator
-    #[pause]
-    pub fn drain_withdraw(&mut self, validator_id: AccountId) {
-        // make sure enough gas was given
-        let min_gas = GAS_DRAIN_WITHDRAW.as_gas()
-            + GAS_EXT_WITHDRAW.as_gas()
-            + GAS_CB_VALIDATOR_WITHDRAW.as_gas();
-        require!(
-            env::prepaid_gas().as_gas() >= min_gas,
-            format!("{}. require at least {:?}", ERR_NO_ENOUGH_GAS, min_gas)
-        );
-
+    /// The keeper, if any, is rewarded once the withdrawal succeeds.
+    pub(crate) fn internal_drain_withdraw(
+        &mut self,
+        validator_id: &AccountId,
+        keeper_id: Option<AccountId>,
+    ) {
+        let config = *self.internal_config();
         let mut validator = self
             .data_mut()
             .validator_pool
-            .get_validator(&validator_id)
+            .get_validator(validator_id)
             .expect(ERR_VALIDATOR_NOT_EXIST);
 
         // make sure the validator:
@@ -605,7 +787,7 @@ impl Contract {
         );
         require!(validator.staked_amount == 0, ERR_NON_ZERO_STAKED_AMOUNT);
         require!(
-            !validator.pending_release(),
+            !validator.pending_release(&config),
             ERR_VALIDATOR_WITHDRAW_WHEN_LOCKED
         );
         require!(validator.draining, ERR_NOT_IN_DRAINING);
@@ -613,18 +795,22 @@ impl Contract {
         let amount = validator.unstaked_amount;
 
         Event::DrainWithdrawAttempt {
-            validator_id: &validator_id,
+            validator_id,
             amount: &U128(amount),
         }
         .emit();
 
         validator
-            .withdraw(&mut self.data_mut().validator_pool, amount)
+            .withdraw(&mut self.data_mut().validator_pool, amount, &config)
             .then(
                 Self::ext(env::current_account_id())
-                    .with_static_gas(GAS_CB_VALIDATOR_WITHDRAW)
+                    .with_static_gas(config.gas.cb_validator_withdraw)
                     .with_unused_gas_weight(0)
-                    .validator_drain_withdraw_callback(validator.account_id.clone(), amount.into()),
+                    .validator_drain_withdraw_callback(
+                        validator.account_id.clone(),
+                        amount.into(),
+                        keeper_id,
+                    ),
             );
     }
 }
@@ -653,13 +839,139 @@ impl Contract {
     }
 }
 
+impl Contract {
+    /// Settle the contract totals for an action whose callback never ran, from the
+    /// validator balances before and after the reset. The part of the balance change
+    /// not explained by the action is taken as rewards or a loss of the validator.
+    fn internal_settle_stuck_execution(
+        &mut self,
+        validator: &Validator,
+        execution: &ExecutionInfo,
+        account: &HumanReadableAccount,
+    ) {
+        let amount = execution.amount.0;
+        let old_total_balance = validator.total_balance();
+        let new_total_balance = account.staked_balance.0 + account.unstaked_balance.0;
+        let total_balance_increase = new_total_balance.saturating_sub(old_total_balance);
+        // the amount the action moved, and the balance change it explains
+        let (settled_amount, expected_increase) = match execution.kind {
+            ExecutionKind::Stake => {
+                // a stake lands in full or not at all, and the NEAR of a failed one
+                // is back on this contract. The rest of the increase is rewards.
+                let staked_amount = if total_balance_increase >= amount {
+                    amount
+                } else {
+                    0
+                };
+                self.data_mut().stake_amount_to_settle += amount - staked_amount;
+                (staked_amount, staked_amount)
+            }
+            ExecutionKind::Unstake => {
+                let unstaked_amount = min(
+                    account
+                        .unstaked_balance
+                        .0
+                        .saturating_sub(validator.unstaked_amount),
+                    amount,
+                );
+                self.data_mut().unstake_amount_to_settle += amount - unstaked_amount;
+                (unstaked_amount, 0)
+            }
+            ExecutionKind::Withdraw => {
+                // the amount was deducted from the validator when the withdraw started
+                let withdrawn_amount = amount - min(total_balance_increase, amount);
+                self.internal_credit_withdrawal_reserve(withdrawn_amount);
+                (withdrawn_amount, amount - withdrawn_amount)
+            }
+            ExecutionKind::GetBalance | ExecutionKind::SyncBalance => (0, 0),
+        };
+
+        let expected_total_balance = old_total_balance + expected_increase;
+        let rewards = new_total_balance.saturating_sub(expected_total_balance);
+        let loss = expected_total_balance.saturating_sub(new_total_balance);
+        if rewards > 0 {
+            let rewards = self.internal_cap_rewards(validator, rewards);
+            if rewards > 0 {
+                self.internal_credit_staking_rewards(rewards);
+            }
+        }
+        if loss > 0 {
+            // rewards that are not vested yet absorb the loss first,
+            // only the rest lowers the share price
+            let price_loss = self
+                .data_mut()
+                .reward_vesting
+                .take_loss(loss, env::block_timestamp());
+            log!("Loss of {} taken out of the share price", price_loss);
+            self.data_mut().total_staked_asset_in_near -= loss;
+        }
+
+        Event::StuckExecutionSettled {
+            validator_id: &validator.account_id,
+            kind: execution.kind,
+            amount: &U128(amount),
+            settled_amount: &U128(settled_amount),
+            rewards: &U128(rewards),
+            loss: &U128(loss),
+        }
+        .emit();
+    }
+}
+
 #[near]
 impl Contract {
+    /// Unlike `validator_get_account_callback`, balances are taken as they are,
+    /// since the stuck action might have moved any amount on the validator.
+    /// The contract totals are then settled for the stuck action.
+    #[private]
+    pub fn validator_reset_stuck_callback(
+        &mut self,
+        validator_id: AccountId,
+        execution: ExecutionInfo,
+        #[callback_result] result: Result<HumanReadableAccount, PromiseError>,
+    ) -> bool {
+        let mut validator = self
+            .data_mut()
+            .validator_pool
+            .get_validator(&validator_id)
+            .unwrap_or_else(|| panic!("{}: {}", ERR_VALIDATOR_NOT_EXIST, &validator_id));
+
+        match result {
+            Ok(account) => {
+                Event::ResetStuckValidatorSuccess {
+                    validator_id: &validator_id,
+                    old_staked_balance: &validator.staked_amount.into(),
+                    old_unstaked_balance: &validator.unstaked_amount.into(),
+                    new_staked_balance: &account.staked_balance,
+                    new_unstaked_balance: &account.unstaked_balance,
+                }
+                .emit();
+                let old_validator = validator.clone();
+                validator.on_sync_account_balance_success(
+                    &mut self.data_mut().validator_pool,
+                    account.staked_balance.0,
+                    account.unstaked_balance.0,
+                );
+                self.internal_settle_stuck_execution(&old_validator, &execution, &account);
+                true
+            }
+            Err(_) => {
+                // keep the flag, it can be reset again after another timeout
+                Event::ResetStuckValidatorFailed {
+                    validator_id: &validator_id,
+                }
+                .emit();
+                false
+            }
+        }
+    }
+
     #[private]
     pub fn validator_drain_unstaked_callback(
         &mut self,
         validator_id: AccountId,
         amount: U128,
+        keeper_id: Option<AccountId>,
     ) -> PromiseOrValue<()> {
         let amount = amount.into();
         let mut validator = self
@@ -678,11 +990,16 @@ impl Contract {
             }
             .emit();
 
+            if let Some(keeper_id) = keeper_id {
+                self.internal_reward_keeper(&keeper_id, 1);
+            }
+
+            let config = *self.internal_config();
             validator
-                .sync_account_balance(&mut self.data_mut().validator_pool, true)
+                .sync_account_balance(&mut self.data_mut().validator_pool, true, &config)
                 .then(
                     Self::ext(env::current_account_id())
-                        .with_static_gas(GAS_CB_VALIDATOR_SYNC_BALANCE)
+                        .with_static_gas(config.gas.cb_validator_sync_balance)
                         .with_unused_gas_weight(0)
                         .validator_get_account_callback(validator_id),
                 )
@@ -702,7 +1019,12 @@ impl Contract {
     }
 
     #[private]
-    pub fn validator_drain_withdraw_callback(&mut self, validator_id: AccountId, amount: U128) {
+    pub fn validator_drain_withdraw_callback(
+        &mut self,
+        validator_id: AccountId,
+        amount: U128,
+        keeper_id: Option<AccountId>,
+    ) {
         let amount = amount.into();
         let mut validator = self
             .data_mut()
@@ -720,8 +1042,17 @@ impl Contract {
             }
             .emit();
 
-            // those funds need to be restaked, so we add them back to epoch request
-            self.data_mut().epoch_requested_stake_amount += amount;
+            if let Some(keeper_id) = keeper_id {
+                self.internal_reward_keeper(&keeper_id, 1);
+            }
+
+            if self.is_emergency_mode() {
+                // in emergency mode the funds are kept to pay out LST holders
+                self.internal_emergency_settle(amount);
+            } else {
+                // those funds need to be restaked, so we add them back to epoch request
+                self.data_mut().epoch_requested_stake_amount += amount;
+            }
         } else {
             // withdraw failed, revert
             validator.on_withdraw_failed(&mut self.data_mut().validator_pool, amount);
@@ -742,16 +1073,69 @@ impl Contract {
             .whitelist_account_id
             .as_ref()
             .expect(ERR_VALIDATOR_WHITELIST_NOT_SET);
+        let gas = self.internal_config().gas;
 
         ext_whitelist::ext(whitelist_id.clone())
-            .with_static_gas(GAS_EXT_WHITELIST)
+            .with_static_gas(gas.ext_whitelist)
             .with_unused_gas_weight(0)
             .is_whitelisted(validator_id.clone())
             .then(
                 Self::ext(env::current_account_id())
-                    .with_static_gas(GAS_CB_WHITELIST)
+                    .with_static_gas(gas.cb_whitelist)
                     .with_unused_gas_weight(0)
                     .is_whitelisted_callback(validator_id.clone(), weight),
             );
     }
+
+    pub(crate) fn internal_update_weights(
+        &mut self,
+        validator_ids: Vec<AccountId>,
+        weights: Vec<u16>,
+    ) {
+        self.assert_scope_not_paused(PauseScope::ValidatorAdmin);
+        require!(validator_ids.len() == weights.len(), ERR_BAD_VALIDATOR_LIST);
+
+        require!(
+            validator_ids.len() <= MAX_UPDATE_WEIGHTS_COUNT,
+            format!(
+                "The number of validators to be updated at a time cannot exceed {}",
+                MAX_UPDATE_WEIGHTS_COUNT
+            )
+        );
+
+        let mut account_ids = Vec::new();
+        let mut old_weights = Vec::new();
+        let mut new_weights = Vec::new();
+
+        for i in 0..validator_ids.len() {
+            let old_weight = self
+                .data_mut()
+                .validator_pool
+                .update_weight(&validator_ids[i], weights[i]);
+            account_ids.push(&validator_ids[i]);
+            old_weights.push(old_weight);
+            new_weights.push(weights[i]);
+        }
+
+        Event::ValidatorsUpdatedWeights {
+            account_ids,
+            old_weights,
+            new_weights,
+        }
+        .emit();
+    }
+
+    pub(crate) fn internal_update_base_stake_amounts(
+        &mut self,
+        validator_ids: Vec<AccountId>,
+        amounts: Vec<U128>,
+    ) {
+        self.assert_scope_not_paused(PauseScope::ValidatorAdmin);
+        require!(validator_ids.len() == amounts.len(), ERR_BAD_VALIDATOR_LIST);
+        for i in 0..validator_ids.len() {
+            self.data_mut()
+                .validator_pool
+                .update_base_stake_amount(&validator_ids[i], amounts[i].into());
+        }
+    }
 }
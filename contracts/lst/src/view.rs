@@ -1,5 +1,8 @@
 use crate::*;
 
+/// Max accounts listed for each role by `get_contract_info`
+const MAX_ROLE_GRANTEES_IN_VIEW: u64 = 100;
+
 #[near(serializers = [json])]
 pub struct Summary {
     /// Total amount of LST that was minted (minus burned).
@@ -23,6 +26,40 @@ pub struct Summary {
     pub epoch_requested_stake_amount: U128,
     /// Amount of NEAR that is requested to unstake by all users during the last epoch
     pub epoch_requested_unstake_amount: U128,
+    /// Amount of NEAR set aside in the contract for users' withdrawals
+    pub withdrawal_reserve: U128,
+    /// Part of the withdrawal reserve that is not covered by the contract balance
+    pub withdrawal_reserve_shortfall: U128,
+    /// Amount of NEAR paid out for withdrawals before it was set aside in the reserve
+    pub withdrawal_reserve_advance: U128,
+    /// Amount of NEAR withdrawn for redelegations and not yet staked on the target validators
+    pub redelegation_in_transit_amount: U128,
+    /// Rewards included in the total staked amount but not in the share price yet
+    pub unvested_rewards: U128,
+}
+
+#[near(serializers = [json])]
+pub struct RoleGrantees {
+    pub role: String,
+    pub account_ids: Vec<AccountId>,
+}
+
+/// How the deployment is wired
+#[near(serializers = [json])]
+pub struct ContractInfo {
+    pub version: String,
+    pub contract_id: AccountId,
+    pub owner_id: AccountId,
+    pub rnear_contract_id: AccountId,
+    pub wnear_contract_id: AccountId,
+    pub burrow_contract_id: AccountId,
+    pub whitelist_account_id: Option<AccountId>,
+    pub last_settlement_epoch: EpochHeight,
+    pub last_settlement_initiated_epoch: EpochHeight,
+    pub pause_status: PauseStatus,
+    pub super_admins: Vec<AccountId>,
+    /// Grantees of each role in the ACL
+    pub roles: Vec<RoleGrantees>,
 }
 
 #[near]
@@ -51,6 +88,23 @@ impl Contract {
                 .into(),
             epoch_requested_stake_amount: self.data().epoch_requested_stake_amount.into(),
             epoch_requested_unstake_amount: self.data().epoch_requested_unstake_amount.into(),
+            withdrawal_reserve: self.data().withdrawal_reserve.into(),
+            withdrawal_reserve_shortfall: self
+                .data()
+                .withdrawal_reserve
+                .saturating_sub(
+                    env::account_balance()
+                        .saturating_sub(self.internal_config().contract_min_reserve_balance())
+                        .as_yoctonear(),
+                )
+                .into(),
+            withdrawal_reserve_advance: self.data().withdrawal_reserve_advance.into(),
+            redelegation_in_transit_amount: self.data().redelegation_in_transit_amount.into(),
+            unvested_rewards: self
+                .data()
+                .reward_vesting
+                .unvested_amount(env::block_timestamp())
+                .into(),
         }
     }
 
@@ -64,7 +118,8 @@ impl Contract {
                 )
                 .into(),
             last_unstake_request_epoch_height: account.last_unstake_request_epoch_height,
-            can_withdraw: account.last_unstake_request_epoch_height <= get_epoch_height(),
+            can_withdraw: self.is_unstaked_available(&account, account.unstaked),
+            matched_unstaked_balance: account.matched_unstaked.into(),
             account_id,
         }
     }
@@ -73,6 +128,34 @@ impl Contract {
         self.assert_can_withdraw(&account_id, amount.0);
     }
 
+    pub fn get_contract_info(&self) -> ContractInfo {
+        ContractInfo {
+            version: self.get_version(),
+            contract_id: env::current_account_id(),
+            owner_id: self.data().owner_id.clone(),
+            rnear_contract_id: self.data().rnear_contract_id.clone(),
+            wnear_contract_id: self.data().wnear_contract_id.clone(),
+            burrow_contract_id: self.data().burrow_contract_id.clone(),
+            whitelist_account_id: self.data().whitelist_account_id.clone(),
+            last_settlement_epoch: self.data().last_settlement_epoch,
+            last_settlement_initiated_epoch: self.data().last_settlement_initiated_epoch,
+            pause_status: self.get_pause_status(),
+            super_admins: self.acl_get_super_admins(0, MAX_ROLE_GRANTEES_IN_VIEW),
+            roles: self
+                .acl_role_variants()
+                .into_iter()
+                .map(|role| RoleGrantees {
+                    role: role.to_string(),
+                    account_ids: self.acl_get_grantees(
+                        role.to_string(),
+                        0,
+                        MAX_ROLE_GRANTEES_IN_VIEW,
+                    ),
+                })
+                .collect(),
+        }
+    }
+
     pub fn get_total_weight(&self) -> u16 {
         self.data().validator_pool.total_weight
     }
@@ -85,6 +168,8 @@ impl Contract {
             .get_info(
                 &self.data().validator_pool,
                 self.data().total_staked_asset_in_near,
+                self.data().token.total_supply,
+                self.internal_config(),
             )
     }
 
@@ -101,6 +186,8 @@ impl Contract {
                 v.get_info(
                     &self.data().validator_pool,
                     self.data().total_staked_asset_in_near,
+                    self.data().token.total_supply,
+                    self.internal_config(),
                 )
             })
             .collect()
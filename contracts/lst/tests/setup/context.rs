@@ -95,6 +95,24 @@ impl Context {
         }
     }
 
+    /// Deploys the contracts with validators `v1`, `v2`, ... of the given weights added
+    pub async fn with_validators(
+        worker: &Worker<Sandbox>,
+        weights: &[u16],
+    ) -> (Self, Vec<MockValidatorContract>) {
+        let context = Self::new(worker, None).await;
+        check!(context.mock_whitelist.allow_all(&context.root));
+        let mut validators = vec![];
+        for (i, weight) in weights.iter().enumerate() {
+            let validator = context.create_validator(&format!("v{}", i + 1)).await;
+            check!(context
+                .lst_contract
+                .add_validator(&context.root, validator.0.id(), *weight));
+            validators.push(validator);
+        }
+        (context, validators)
+    }
+
     pub async fn create_validator(&self, validator_id: &str) -> MockValidatorContract {
         let (v_contract,) = tokio::join!(async {
             let v = self
@@ -4,6 +4,17 @@ pub const NUM_EPOCHS_TO_UNLOCK: u64 = 4;
 pub const FT_STORAGE_DEPOSIT: u128 = 1_250_000_000_000_000_000_000;
 /// helper functions
 impl Context {
+    /// Registers alice and bob, and stakes the given NEAR amount for alice
+    pub async fn register_and_stake(&self, alice_amount: u128) {
+        check!(self
+            .lst_contract
+            .storage_deposit(&self.alice, None, FT_STORAGE_DEPOSIT));
+        check!(self
+            .lst_contract
+            .storage_deposit(&self.bob, None, FT_STORAGE_DEPOSIT));
+        check!(self.lst_contract.deposit_and_stake(&self.alice, alice_amount));
+    }
+
     pub async fn op_epoch_stake_all(&self) {
         let mut repeat = true;
         while repeat {
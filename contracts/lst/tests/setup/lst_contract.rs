@@ -24,6 +24,52 @@ impl LstContract {
             .json::<U128>()
     }
 
+    pub async fn get_price_history(&self, limit: Option<u32>) -> Result<Vec<lst::PriceSample>> {
+        self.0
+            .call("get_price_history")
+            .args_json(json!({
+                "limit": limit
+            }))
+            .view()
+            .await
+            .unwrap()
+            .json::<Vec<lst::PriceSample>>()
+    }
+
+    pub async fn get_twap(&self, window_sec: u64) -> Result<U128> {
+        self.0
+            .call("get_twap")
+            .args_json(json!({
+                "window_sec": window_sec
+            }))
+            .view()
+            .await
+            .unwrap()
+            .json::<U128>()
+    }
+
+    pub async fn get_apr(&self, window_sec: Option<u64>) -> Result<Option<lst::AprView>> {
+        self.0
+            .call("get_apr")
+            .args_json(json!({
+                "window_sec": window_sec
+            }))
+            .view()
+            .await
+            .unwrap()
+            .json::<Option<lst::AprView>>()
+    }
+
+    pub async fn get_summary(&self) -> Result<lst::Summary> {
+        self.0
+            .call("get_summary")
+            .args_json(json!({}))
+            .view()
+            .await
+            .unwrap()
+            .json::<lst::Summary>()
+    }
+
     pub async fn get_account_details(&self, account_id: &AccountId) ->Result<AccountDetailsView> {
         self.0
             .call("get_account_details")
@@ -180,299 +226,1176 @@ impl LstContract {
 
 /// owner and manager interfaces
 impl LstContract {
-    pub async fn set_beneficiary(
+    pub async fn propose_owner(
         &self,
         caller: &Account,
-        account_id: &AccountId,
-        bps: u32,
+        new_owner_id: &AccountId,
+        expiry_sec: Option<u64>,
     ) -> Result<ExecutionFinalResult> {
         caller
-            .call(self.0.id(), "set_beneficiary")
+            .call(self.0.id(), "propose_owner")
             .args_json(json!({
-                "account_id": account_id,
-                "bps": bps
+                "new_owner_id": new_owner_id,
+                "expiry_sec": expiry_sec
             }))
             .deposit(NearToken::from_yoctonear(1))
             .max_gas()
             .transact()
             .await
     }
-}
 
-/// test stub
-impl LstContract {
-    pub async fn set_epoch_height(
+    pub async fn cancel_owner_proposal(&self, caller: &Account) -> Result<ExecutionFinalResult> {
+        caller
+            .call(self.0.id(), "cancel_owner_proposal")
+            .args_json(json!({}))
+            .deposit(NearToken::from_yoctonear(1))
+            .max_gas()
+            .transact()
+            .await
+    }
+
+    pub async fn accept_owner(&self, caller: &Account) -> Result<ExecutionFinalResult> {
+        caller
+            .call(self.0.id(), "accept_owner")
+            .args_json(json!({}))
+            .deposit(NearToken::from_yoctonear(1))
+            .max_gas()
+            .transact()
+            .await
+    }
+
+    pub async fn get_owner(&self) -> Result<AccountId> {
+        self.0
+            .call("get_owner")
+            .args_json(json!({}))
+            .view()
+            .await
+            .unwrap()
+            .json::<AccountId>()
+    }
+
+    pub async fn get_owner_proposal(&self) -> Result<Option<lst::OwnerProposal>> {
+        self.0
+            .call("get_owner_proposal")
+            .args_json(json!({}))
+            .view()
+            .await
+            .unwrap()
+            .json::<Option<lst::OwnerProposal>>()
+    }
+
+    pub async fn get_beneficiaries(&self) -> Result<HashMap<AccountId, u32>> {
+        self.0
+            .call("get_beneficiaries")
+            .args_json(json!({}))
+            .view()
+            .await
+            .unwrap()
+            .json::<HashMap<AccountId, u32>>()
+    }
+
+    pub async fn set_timelock_delay(
         &self,
         caller: &Account,
-        epoch: u64,
+        kind: &str,
+        delay_sec: u64,
     ) -> Result<ExecutionFinalResult> {
         caller
-            .call(self.0.id(), "set_epoch_height")
+            .call(self.0.id(), "set_timelock_delay")
             .args_json(json!({
-                "epoch": epoch
+                "kind": kind,
+                "delay_sec": delay_sec
             }))
+            .deposit(NearToken::from_yoctonear(1))
             .max_gas()
             .transact()
             .await
     }
 
-    pub async fn add_epoch_rewards(
+    pub async fn queue_timelock_action(
         &self,
         caller: &Account,
-        amount: u128,
+        action: near_sdk::serde_json::Value,
     ) -> Result<ExecutionFinalResult> {
         caller
-            .call(self.0.id(), "add_epoch_rewards")
+            .call(self.0.id(), "queue_timelock_action")
             .args_json(json!({
-                "amount": amount.to_string()
+                "action": action
             }))
+            .deposit(NearToken::from_yoctonear(1))
             .max_gas()
             .transact()
             .await
     }
 
-    pub async fn read_epoch_height(&self) -> Result<u64> {
+    pub async fn cancel_timelock_action(&self, caller: &Account, id: u64) -> Result<ExecutionFinalResult> {
+        caller
+            .call(self.0.id(), "cancel_timelock_action")
+            .args_json(json!({
+                "id": id
+            }))
+            .deposit(NearToken::from_yoctonear(1))
+            .max_gas()
+            .transact()
+            .await
+    }
+
+    pub async fn execute_timelock_action(&self, caller: &Account, id: u64) -> Result<ExecutionFinalResult> {
+        caller
+            .call(self.0.id(), "execute_timelock_action")
+            .args_json(json!({
+                "id": id
+            }))
+            .max_gas()
+            .transact()
+            .await
+    }
+
+    pub async fn get_timelock_queue(&self) -> Result<Vec<lst::TimelockProposal>> {
         self.0
-            .call("read_epoch_height")
+            .call("get_timelock_queue")
             .args_json(json!({}))
             .view()
             .await
             .unwrap()
-            .json::<u64>()
+            .json::<Vec<lst::TimelockProposal>>()
     }
-}
 
-/// stake pool interfaces
-impl LstContract {
-    pub async fn deposit(
+    pub async fn propose_operator_action(
         &self,
         caller: &Account,
-        near_balance: u128,
+        action: near_sdk::serde_json::Value,
     ) -> Result<ExecutionFinalResult> {
         caller
-            .call(self.0.id(), "deposit")
-            .args_json(json!({}))
-            .deposit(NearToken::from_near(near_balance))
+            .call(self.0.id(), "propose_operator_action")
+            .args_json(json!({
+                "action": action
+            }))
+            .deposit(NearToken::from_yoctonear(1))
             .max_gas()
             .transact()
             .await
     }
 
-    pub async fn stake(
+    pub async fn approve_operator_action(&self, caller: &Account, id: u64) -> Result<ExecutionFinalResult> {
+        caller
+            .call(self.0.id(), "approve_operator_action")
+            .args_json(json!({
+                "id": id
+            }))
+            .deposit(NearToken::from_yoctonear(1))
+            .max_gas()
+            .transact()
+            .await
+    }
+
+    pub async fn execute_operator_action(&self, caller: &Account, id: u64) -> Result<ExecutionFinalResult> {
+        caller
+            .call(self.0.id(), "execute_operator_action")
+            .args_json(json!({
+                "id": id
+            }))
+            .max_gas()
+            .transact()
+            .await
+    }
+
+    pub async fn cancel_operator_action(&self, caller: &Account, id: u64) -> Result<ExecutionFinalResult> {
+        caller
+            .call(self.0.id(), "cancel_operator_action")
+            .args_json(json!({
+                "id": id
+            }))
+            .deposit(NearToken::from_yoctonear(1))
+            .max_gas()
+            .transact()
+            .await
+    }
+
+    pub async fn set_approval_config(
         &self,
         caller: &Account,
-        near_balance: u128,
+        threshold: u32,
+        proposal_ttl_sec: u64,
     ) -> Result<ExecutionFinalResult> {
         caller
-            .call(self.0.id(), "stake")
+            .call(self.0.id(), "set_approval_config")
             .args_json(json!({
-                "amount": NearToken::from_near(near_balance).as_yoctonear().to_string()
+                "config": {
+                    "threshold": threshold,
+                    "proposal_ttl_sec": proposal_ttl_sec,
+                }
             }))
+            .deposit(NearToken::from_yoctonear(1))
             .max_gas()
             .transact()
             .await
     }
 
-    pub async fn stake_all(&self, caller: &Account) -> Result<ExecutionFinalResult> {
-        caller
-            .call(self.0.id(), "stake_all")
+    pub async fn get_operator_proposals(&self) -> Result<Vec<lst::ApprovalProposal>> {
+        self.0
+            .call("get_operator_proposals")
+            .args_json(json!({}))
+            .view()
+            .await
+            .unwrap()
+            .json::<Vec<lst::ApprovalProposal>>()
+    }
+
+    pub async fn get_timelock_delays(&self) -> Result<Vec<lst::TimelockDelay>> {
+        self.0
+            .call("get_timelock_delays")
             .args_json(json!({}))
+            .view()
+            .await
+            .unwrap()
+            .json::<Vec<lst::TimelockDelay>>()
+    }
+
+    pub async fn set_beneficiary(
+        &self,
+        caller: &Account,
+        account_id: &AccountId,
+        bps: u32,
+    ) -> Result<ExecutionFinalResult> {
+        caller
+            .call(self.0.id(), "set_beneficiary")
+            .args_json(json!({
+                "account_id": account_id,
+                "bps": bps
+            }))
+            .deposit(NearToken::from_yoctonear(1))
             .max_gas()
             .transact()
             .await
     }
 
-    pub async fn deposit_and_stake(
+    pub async fn set_reward_vesting_period(
         &self,
         caller: &Account,
-        near_balance: u128,
+        period_sec: u64,
     ) -> Result<ExecutionFinalResult> {
         caller
-            .call(self.0.id(), "deposit_and_stake")
-            .args_json(json!({}))
-            .deposit(NearToken::from_near(near_balance))
+            .call(self.0.id(), "set_reward_vesting_period")
+            .args_json(json!({
+                "period_sec": period_sec
+            }))
+            .deposit(NearToken::from_yoctonear(1))
             .max_gas()
             .transact()
             .await
     }
 
-    pub async fn unstake(
+    pub async fn set_max_reward_apr(
         &self,
         caller: &Account,
-        near_balance: u128,
+        max_apr_bps: Option<u32>,
     ) -> Result<ExecutionFinalResult> {
         caller
-            .call(self.0.id(), "unstake")
+            .call(self.0.id(), "set_max_reward_apr")
             .args_json(json!({
-                "amount": NearToken::from_near(near_balance).as_yoctonear().to_string()
+                "max_apr_bps": max_apr_bps
             }))
+            .deposit(NearToken::from_yoctonear(1))
             .max_gas()
             .transact()
             .await
     }
 
-    pub async fn unstake_in_yocto(
+    pub async fn release_quarantined_rewards(
         &self,
         caller: &Account,
+        validator_id: &AccountId,
         amount: u128,
     ) -> Result<ExecutionFinalResult> {
         caller
-            .call(self.0.id(), "unstake")
+            .call(self.0.id(), "release_quarantined_rewards")
             .args_json(json!({
-                "amount": amount.to_string()
+                "validator_id": validator_id,
+                "amount": U128(amount)
             }))
+            .deposit(NearToken::from_yoctonear(1))
             .max_gas()
             .transact()
             .await
     }
 
-    pub async fn unstake_all(&self, caller: &Account) -> Result<ExecutionFinalResult> {
+    pub async fn discard_quarantined_rewards(
+        &self,
+        caller: &Account,
+        validator_id: &AccountId,
+        amount: u128,
+    ) -> Result<ExecutionFinalResult> {
         caller
-            .call(self.0.id(), "unstake_all")
-            .args_json(json!({}))
+            .call(self.0.id(), "discard_quarantined_rewards")
+            .args_json(json!({
+                "validator_id": validator_id,
+                "amount": U128(amount)
+            }))
+            .deposit(NearToken::from_yoctonear(1))
             .max_gas()
             .transact()
             .await
     }
 
-    pub async fn withdraw(
+    pub async fn get_reward_cap(&self) -> Result<lst::RewardCapView> {
+        self.0
+            .call("get_reward_cap")
+            .args_json(json!({}))
+            .view()
+            .await
+            .unwrap()
+            .json::<lst::RewardCapView>()
+    }
+
+    pub async fn get_reward_vesting(&self) -> Result<lst::RewardVestingView> {
+        self.0
+            .call("get_reward_vesting")
+            .args_json(json!({}))
+            .view()
+            .await
+            .unwrap()
+            .json::<lst::RewardVestingView>()
+    }
+
+    pub async fn set_beneficiary_payout(
         &self,
         caller: &Account,
-        near_balance: u128,
+        payout: &str,
     ) -> Result<ExecutionFinalResult> {
         caller
-            .call(self.0.id(), "withdraw")
-            .args_json(json!({
-                "amount": NearToken::from_near(near_balance).as_yoctonear().to_string()
-            }))
+            .call(self.0.id(), "set_beneficiary_payout")
+            .args_json(json!({
+                "payout": payout
+            }))
+            .max_gas()
+            .transact()
+            .await
+    }
+
+    pub async fn claim_beneficiary_payout(&self, caller: &Account) -> Result<ExecutionFinalResult> {
+        caller
+            .call(self.0.id(), "claim_beneficiary_payout")
+            .args_json(json!({}))
+            .max_gas()
+            .transact()
+            .await
+    }
+
+    pub async fn register_referrer(&self, caller: &Account) -> Result<ExecutionFinalResult> {
+        caller
+            .call(self.0.id(), "register_referrer")
+            .args_json(json!({}))
+            .max_gas()
+            .transact()
+            .await
+    }
+
+    pub async fn claim_referral_rewards(&self, caller: &Account) -> Result<ExecutionFinalResult> {
+        caller
+            .call(self.0.id(), "claim_referral_rewards")
+            .args_json(json!({}))
+            .max_gas()
+            .transact()
+            .await
+    }
+
+    pub async fn get_referrer(&self, referrer_id: &AccountId) -> Result<Option<lst::ReferrerView>> {
+        self.0
+            .call("get_referrer")
+            .args_json(json!({
+                "referrer_id": referrer_id
+            }))
+            .view()
+            .await
+            .unwrap()
+            .json::<Option<lst::ReferrerView>>()
+    }
+
+    pub async fn get_referral(&self, account_id: &AccountId) -> Result<Option<AccountId>> {
+        self.0
+            .call("get_referral")
+            .args_json(json!({
+                "account_id": account_id
+            }))
+            .view()
+            .await
+            .unwrap()
+            .json::<Option<AccountId>>()
+    }
+
+    pub async fn get_beneficiary_earnings(
+        &self,
+        account_id: &AccountId,
+    ) -> Result<Option<lst::BeneficiaryEarningsView>> {
+        self.0
+            .call("get_beneficiary_earnings")
+            .args_json(json!({
+                "account_id": account_id
+            }))
+            .view()
+            .await
+            .unwrap()
+            .json::<Option<lst::BeneficiaryEarningsView>>()
+    }
+
+    pub async fn get_beneficiary_payout_history(
+        &self,
+        account_id: Option<&AccountId>,
+    ) -> Result<Vec<lst::BeneficiaryPayoutRecord>> {
+        self.0
+            .call("get_beneficiary_payout_history")
+            .args_json(json!({
+                "account_id": account_id
+            }))
+            .view()
+            .await
+            .unwrap()
+            .json::<Vec<lst::BeneficiaryPayoutRecord>>()
+    }
+}
+
+/// test stub
+impl LstContract {
+    pub async fn set_epoch_height(
+        &self,
+        caller: &Account,
+        epoch: u64,
+    ) -> Result<ExecutionFinalResult> {
+        caller
+            .call(self.0.id(), "set_epoch_height")
+            .args_json(json!({
+                "epoch": epoch
+            }))
+            .max_gas()
+            .transact()
+            .await
+    }
+
+    pub async fn add_epoch_rewards(
+        &self,
+        caller: &Account,
+        amount: u128,
+    ) -> Result<ExecutionFinalResult> {
+        caller
+            .call(self.0.id(), "add_epoch_rewards")
+            .args_json(json!({
+                "amount": amount.to_string()
+            }))
+            .max_gas()
+            .transact()
+            .await
+    }
+
+    pub async fn read_epoch_height(&self) -> Result<u64> {
+        self.0
+            .call("read_epoch_height")
+            .args_json(json!({}))
+            .view()
+            .await
+            .unwrap()
+            .json::<u64>()
+    }
+}
+
+/// stake pool interfaces
+impl LstContract {
+    pub async fn deposit(
+        &self,
+        caller: &Account,
+        near_balance: u128,
+    ) -> Result<ExecutionFinalResult> {
+        caller
+            .call(self.0.id(), "deposit")
+            .args_json(json!({}))
+            .deposit(NearToken::from_near(near_balance))
+            .max_gas()
+            .transact()
+            .await
+    }
+
+    pub async fn stake(
+        &self,
+        caller: &Account,
+        near_balance: u128,
+    ) -> Result<ExecutionFinalResult> {
+        caller
+            .call(self.0.id(), "stake")
+            .args_json(json!({
+                "amount": NearToken::from_near(near_balance).as_yoctonear().to_string()
+            }))
+            .max_gas()
+            .transact()
+            .await
+    }
+
+    pub async fn stake_all(&self, caller: &Account) -> Result<ExecutionFinalResult> {
+        caller
+            .call(self.0.id(), "stake_all")
+            .args_json(json!({}))
+            .max_gas()
+            .transact()
+            .await
+    }
+
+    pub async fn deposit_and_stake(
+        &self,
+        caller: &Account,
+        near_balance: u128,
+    ) -> Result<ExecutionFinalResult> {
+        caller
+            .call(self.0.id(), "deposit_and_stake")
+            .args_json(json!({}))
+            .deposit(NearToken::from_near(near_balance))
+            .max_gas()
+            .transact()
+            .await
+    }
+
+    pub async fn deposit_and_stake_with_preference(
+        &self,
+        caller: &Account,
+        near_balance: u128,
+        validator_id: &AccountId,
+    ) -> Result<ExecutionFinalResult> {
+        caller
+            .call(self.0.id(), "deposit_and_stake_with_options")
+            .args_json(json!({
+                "validator_id": validator_id,
+            }))
+            .deposit(NearToken::from_near(near_balance))
+            .max_gas()
+            .transact()
+            .await
+    }
+
+    pub async fn deposit_and_stake_with_referrer(
+        &self,
+        caller: &Account,
+        near_balance: u128,
+        referrer_id: &AccountId,
+    ) -> Result<ExecutionFinalResult> {
+        caller
+            .call(self.0.id(), "deposit_and_stake_with_options")
+            .args_json(json!({
+                "referrer_id": referrer_id,
+            }))
+            .deposit(NearToken::from_near(near_balance))
+            .max_gas()
+            .transact()
+            .await
+    }
+
+    pub async fn unstake(
+        &self,
+        caller: &Account,
+        near_balance: u128,
+    ) -> Result<ExecutionFinalResult> {
+        caller
+            .call(self.0.id(), "unstake")
+            .args_json(json!({
+                "amount": NearToken::from_near(near_balance).as_yoctonear().to_string()
+            }))
+            .max_gas()
+            .transact()
+            .await
+    }
+
+    pub async fn unstake_in_yocto(
+        &self,
+        caller: &Account,
+        amount: u128,
+    ) -> Result<ExecutionFinalResult> {
+        caller
+            .call(self.0.id(), "unstake")
+            .args_json(json!({
+                "amount": amount.to_string()
+            }))
+            .max_gas()
+            .transact()
+            .await
+    }
+
+    pub async fn unstake_all(&self, caller: &Account) -> Result<ExecutionFinalResult> {
+        caller
+            .call(self.0.id(), "unstake_all")
+            .args_json(json!({}))
+            .max_gas()
+            .transact()
+            .await
+    }
+
+    pub async fn withdraw(
+        &self,
+        caller: &Account,
+        near_balance: u128,
+    ) -> Result<ExecutionFinalResult> {
+        caller
+            .call(self.0.id(), "withdraw")
+            .args_json(json!({
+                "amount": NearToken::from_near(near_balance).as_yoctonear().to_string()
+            }))
+            .max_gas()
+            .transact()
+            .await
+    }
+
+    pub async fn withdraw_all(&self, caller: &Account) -> Result<ExecutionFinalResult> {
+        caller
+            .call(self.0.id(), "withdraw_all")
+            .args_json(json!({}))
+            .max_gas()
+            .transact()
+            .await
+    }
+
+    pub async fn get_account_total_balance(&self, account_id: &AccountId) -> Result<U128> {
+        self.0
+            .call("get_account_total_balance")
+            .args_json(json!({
+                "account_id": account_id
+            }))
+            .view()
+            .await
+            .unwrap()
+            .json::<U128>()
+    }
+
+    pub async fn get_account_staked_balance(&self, account_id: &AccountId) -> Result<U128> {
+        self.0
+            .call("get_account_staked_balance")
+            .args_json(json!({
+                "account_id": account_id
+            }))
+            .view()
+            .await
+            .unwrap()
+            .json::<U128>()
+    }
+
+    pub async fn get_account_unstaked_balance(&self, account_id: &AccountId) -> Result<U128> {
+        self.0
+            .call("get_account_unstaked_balance")
+            .args_json(json!({
+                "account_id": account_id
+            }))
+            .view()
+            .await
+            .unwrap()
+            .json::<U128>()
+    }
+}
+
+/// validator related
+impl LstContract {
+    pub async fn get_validator(
+        &self,
+        validator_id: &AccountId,
+    ) -> Result<Option<lst::ValidatorInfo>> {
+        self.0
+            .call("get_validator")
+            .args_json(json!({
+                "validator_id": validator_id,
+            }))
+            .view()
+            .await
+            .unwrap()
+            .json::<Option<lst::ValidatorInfo>>()
+    }
+
+    pub async fn get_validators(
+        &self,
+        from_index: Option<usize>,
+        limit: Option<usize>,
+    ) -> Result<Vec<lst::ValidatorInfo>> {
+        let mut args = HashMap::new();
+        if from_index.is_some() {
+            args.insert("from_index", from_index.unwrap());
+        }
+        if limit.is_some() {
+            args.insert("limit", limit.unwrap());
+        }
+        self.0
+            .call("get_validators")
+            .args_json(json!(args))
+            .view()
+            .await
+            .unwrap()
+            .json::<Vec<lst::ValidatorInfo>>()
+    }
+
+    pub async fn set_validator_preference(
+        &self,
+        caller: &Account,
+        validator_id: Option<&AccountId>,
+        deposit_amount: u128,
+    ) -> Result<ExecutionFinalResult> {
+        caller
+            .call(self.0.id(), "set_validator_preference")
+            .args_json(json!({
+                "validator_id": validator_id,
+            }))
+            .deposit(NearToken::from_yoctonear(deposit_amount))
+            .max_gas()
+            .transact()
+            .await
+    }
+
+    pub async fn get_validator_preference(
+        &self,
+        account_id: &AccountId,
+    ) -> Result<Option<lst::DirectedStakeView>> {
+        self.0
+            .call("get_validator_preference")
+            .args_json(json!({
+                "account_id": account_id,
+            }))
+            .view()
+            .await
+            .unwrap()
+            .json::<Option<lst::DirectedStakeView>>()
+    }
+
+    pub async fn get_stuck_validators(&self) -> Result<Vec<lst::ValidatorInfo>> {
+        self.0
+            .call("get_stuck_validators")
+            .args_json(json!({}))
+            .view()
+            .await
+            .unwrap()
+            .json::<Vec<lst::ValidatorInfo>>()
+    }
+
+    pub async fn reset_stuck_validator(
+        &self,
+        caller: &Account,
+        validator_id: &AccountId,
+    ) -> Result<ExecutionFinalResult> {
+        caller
+            .call(self.0.id(), "reset_stuck_validator")
+            .args_json(json!({
+                "validator_id": validator_id,
+            }))
+            .max_gas()
+            .transact()
+            .await
+    }
+
+    pub async fn get_total_weight(&self) -> Result<u16> {
+        self.0
+            .call("get_total_weight")
+            .args_json(json!({}))
+            .view()
+            .await
+            .unwrap()
+            .json::<u16>()
+    }
+
+    pub async fn add_validator(
+        &self,
+        caller: &Account,
+        validator_id: &AccountId,
+        weight: u16,
+    ) -> Result<ExecutionFinalResult> {
+        caller
+            .call(self.0.id(), "add_validator")
+            .args_json(json!({
+                "validator_id": validator_id,
+                "weight": weight
+            }))
+            .max_gas()
+            .deposit(NearToken::from_yoctonear(1))
+            .transact()
+            .await
+    }
+
+    pub async fn add_validators(
+        &self,
+        caller: &Account,
+        validator_ids: Vec<&AccountId>,
+        weights: Vec<u16>,
+    ) -> Result<ExecutionFinalResult> {
+        caller
+            .call(self.0.id(), "add_validators")
+            .args_json(json!({
+                "validator_ids": validator_ids,
+                "weights": weights
+            }))
+            .max_gas()
+            .deposit(NearToken::from_yoctonear(1))
+            .transact()
+            .await
+    }
+
+    pub async fn remove_validator(
+        &self,
+        caller: &Account,
+        validator_id: &AccountId,
+    ) -> Result<ExecutionFinalResult> {
+        caller
+            .call(self.0.id(), "remove_validator")
+            .args_json(json!({
+                "validator_id": validator_id
+            }))
+            .max_gas()
+            .deposit(NearToken::from_yoctonear(1))
+            .transact()
+            .await
+    }
+
+    pub async fn update_weight(
+        &self,
+        caller: &Account,
+        validator_id: &AccountId,
+        weight: u16,
+    ) -> Result<ExecutionFinalResult> {
+        caller
+            .call(self.0.id(), "update_weight")
+            .args_json(json!({
+                "validator_id": validator_id,
+                "weight": weight
+            }))
+            .max_gas()
+            .deposit(NearToken::from_yoctonear(1))
+            .transact()
+            .await
+    }
+
+    pub async fn update_weights(
+        &self,
+        caller: &Account,
+        validator_ids: Vec<&AccountId>,
+        weights: Vec<u16>,
+    ) -> Result<ExecutionFinalResult> {
+        caller
+            .call(self.0.id(), "update_weights")
+            .args_json(json!({
+                "validator_ids": validator_ids,
+                "weights": weights
+            }))
+            .max_gas()
+            .deposit(NearToken::from_yoctonear(1))
+            .transact()
+            .await
+    }
+
+    pub async fn update_base_stake_amounts(
+        &self,
+        caller: &Account,
+        validator_ids: Vec<&AccountId>,
+        amounts: Vec<u128>,
+    ) -> Result<ExecutionFinalResult> {
+        caller
+            .call(self.0.id(), "update_base_stake_amounts")
+            .args_json(json!({
+                "validator_ids": validator_ids,
+                "amounts": amounts.iter().map(|x| x.to_string()).collect::<Vec<_>>()
+            }))
+            .max_gas()
+            .deposit(NearToken::from_yoctonear(1))
+            .transact()
+            .await
+    }
+
+    pub async fn redelegate(
+        &self,
+        caller: &Account,
+        from_validator_id: &AccountId,
+        to_validator_id: &AccountId,
+        amount: u128,
+    ) -> Result<ExecutionFinalResult> {
+        caller
+            .call(self.0.id(), "redelegate")
+            .args_json(json!({
+                "from_validator_id": from_validator_id,
+                "to_validator_id": to_validator_id,
+                "amount": amount.to_string(),
+            }))
+            .max_gas()
+            .deposit(NearToken::from_yoctonear(1))
+            .transact()
+            .await
+    }
+
+    pub async fn redelegation_withdraw(
+        &self,
+        caller: &Account,
+        redelegation_id: u64,
+    ) -> Result<ExecutionFinalResult> {
+        caller
+            .call(self.0.id(), "redelegation_withdraw")
+            .args_json(json!({
+                "redelegation_id": redelegation_id.to_string(),
+            }))
+            .max_gas()
+            .transact()
+            .await
+    }
+
+    pub async fn redelegation_stake(
+        &self,
+        caller: &Account,
+        redelegation_id: u64,
+    ) -> Result<ExecutionFinalResult> {
+        caller
+            .call(self.0.id(), "redelegation_stake")
+            .args_json(json!({
+                "redelegation_id": redelegation_id.to_string(),
+            }))
+            .max_gas()
+            .transact()
+            .await
+    }
+
+    pub async fn cancel_redelegation(
+        &self,
+        caller: &Account,
+        redelegation_id: u64,
+    ) -> Result<ExecutionFinalResult> {
+        caller
+            .call(self.0.id(), "cancel_redelegation")
+            .args_json(json!({
+                "redelegation_id": redelegation_id.to_string(),
+            }))
+            .max_gas()
+            .deposit(NearToken::from_yoctonear(1))
+            .transact()
+            .await
+    }
+
+    pub async fn get_redelegation(
+        &self,
+        redelegation_id: u64,
+    ) -> Result<Option<lst::RedelegationView>> {
+        self.0
+            .call("get_redelegation")
+            .args_json(json!({
+                "redelegation_id": redelegation_id.to_string(),
+            }))
+            .view()
+            .await
+            .unwrap()
+            .json::<Option<lst::RedelegationView>>()
+    }
+
+    pub async fn register_partner(
+        &self,
+        caller: &Account,
+        partner_id: &AccountId,
+        validator_id: &AccountId,
+    ) -> Result<ExecutionFinalResult> {
+        caller
+            .call(self.0.id(), "register_partner")
+            .args_json(json!({
+                "partner_id": partner_id,
+                "validator_id": validator_id,
+            }))
+            .max_gas()
+            .deposit(NearToken::from_yoctonear(1))
+            .transact()
+            .await
+    }
+
+    pub async fn deregister_partner(
+        &self,
+        caller: &Account,
+        partner_id: &AccountId,
+    ) -> Result<ExecutionFinalResult> {
+        caller
+            .call(self.0.id(), "deregister_partner")
+            .args_json(json!({
+                "partner_id": partner_id,
+            }))
+            .max_gas()
+            .deposit(NearToken::from_yoctonear(1))
+            .transact()
+            .await
+    }
+
+    pub async fn get_partner(&self, partner_id: &AccountId) -> Result<Option<lst::PartnerView>> {
+        self.0
+            .call("get_partner")
+            .args_json(json!({
+                "partner_id": partner_id,
+            }))
+            .view()
+            .await
+            .unwrap()
+            .json::<Option<lst::PartnerView>>()
+    }
+
+    pub async fn set_keeper_reward_config(
+        &self,
+        caller: &Account,
+        reward_bps: u32,
+        reward_per_action: u128,
+        max_reward_per_epoch: u128,
+    ) -> Result<ExecutionFinalResult> {
+        caller
+            .call(self.0.id(), "set_keeper_reward_config")
+            .args_json(json!({
+                "config": {
+                    "reward_bps": reward_bps,
+                    "reward_per_action": U128(reward_per_action),
+                    "max_reward_per_epoch": U128(max_reward_per_epoch),
+                }
+            }))
+            .max_gas()
+            .deposit(NearToken::from_yoctonear(1))
+            .transact()
+            .await
+    }
+
+    pub async fn get_keeper_reward_info(&self) -> Result<lst::KeeperRewardInfo> {
+        self.0
+            .call("get_keeper_reward_info")
+            .args_json(json!({}))
+            .view()
+            .await
+            .unwrap()
+            .json::<lst::KeeperRewardInfo>()
+    }
+
+    pub async fn get_keeper_earnings(&self, account_id: &AccountId) -> Result<U128> {
+        self.0
+            .call("get_keeper_earnings")
+            .args_json(json!({
+                "account_id": account_id,
+            }))
+            .view()
+            .await
+            .unwrap()
+            .json::<U128>()
+    }
+
+    pub async fn announce_fee_config(
+        &self,
+        caller: &Account,
+        config: near_sdk::serde_json::Value,
+    ) -> Result<ExecutionFinalResult> {
+        caller
+            .call(self.0.id(), "announce_fee_config")
+            .args_json(json!({
+                "config": config,
+            }))
+            .max_gas()
+            .deposit(NearToken::from_yoctonear(1))
+            .transact()
+            .await
+    }
+
+    pub async fn apply_fee_config(&self, caller: &Account) -> Result<ExecutionFinalResult> {
+        caller
+            .call(self.0.id(), "apply_fee_config")
+            .args_json(json!({}))
             .max_gas()
             .transact()
             .await
     }
 
-    pub async fn withdraw_all(&self, caller: &Account) -> Result<ExecutionFinalResult> {
+    pub async fn cancel_fee_config(&self, caller: &Account) -> Result<ExecutionFinalResult> {
         caller
-            .call(self.0.id(), "withdraw_all")
+            .call(self.0.id(), "cancel_fee_config")
             .args_json(json!({}))
             .max_gas()
+            .deposit(NearToken::from_yoctonear(1))
             .transact()
             .await
     }
 
-    pub async fn get_account_total_balance(&self, account_id: &AccountId) -> Result<U128> {
-        self.0
-            .call("get_account_total_balance")
+    pub async fn set_fee_change_delay(&self, caller: &Account, delay_epochs: u64) -> Result<ExecutionFinalResult> {
+        caller
+            .call(self.0.id(), "set_fee_change_delay")
             .args_json(json!({
-                "account_id": account_id
+                "delay_epochs": delay_epochs,
             }))
-            .view()
+            .max_gas()
+            .deposit(NearToken::from_yoctonear(1))
+            .transact()
             .await
-            .unwrap()
-            .json::<U128>()
     }
 
-    pub async fn get_account_staked_balance(&self, account_id: &AccountId) -> Result<U128> {
+    pub async fn get_fees(&self) -> Result<lst::FeesView> {
         self.0
-            .call("get_account_staked_balance")
-            .args_json(json!({
-                "account_id": account_id
-            }))
+            .call("get_fees")
+            .args_json(json!({}))
             .view()
             .await
             .unwrap()
-            .json::<U128>()
+            .json::<lst::FeesView>()
     }
 
-    pub async fn get_account_unstaked_balance(&self, account_id: &AccountId) -> Result<U128> {
-        self.0
-            .call("get_account_unstaked_balance")
+    pub async fn set_flow_limits(
+        &self,
+        caller: &Account,
+        config: near_sdk::serde_json::Value,
+    ) -> Result<ExecutionFinalResult> {
+        caller
+            .call(self.0.id(), "set_flow_limits")
             .args_json(json!({
-                "account_id": account_id
+                "config": config,
             }))
-            .view()
+            .max_gas()
+            .deposit(NearToken::from_yoctonear(1))
+            .transact()
             .await
-            .unwrap()
-            .json::<U128>()
     }
-}
 
-/// validator related
-impl LstContract {
-    pub async fn get_validator(
-        &self,
-        validator_id: &AccountId,
-    ) -> Result<Option<lst::ValidatorInfo>> {
+    pub async fn get_flow_limits(&self) -> Result<lst::FlowLimitsView> {
         self.0
-            .call("get_validator")
-            .args_json(json!({
-                "validator_id": validator_id,
-            }))
+            .call("get_flow_limits")
+            .args_json(json!({}))
             .view()
             .await
             .unwrap()
-            .json::<Option<lst::ValidatorInfo>>()
+            .json::<lst::FlowLimitsView>()
     }
 
-    pub async fn get_validators(
-        &self,
-        from_index: Option<usize>,
-        limit: Option<usize>,
-    ) -> Result<Vec<lst::ValidatorInfo>> {
-        let mut args = HashMap::new();
-        if from_index.is_some() {
-            args.insert("from_index", from_index.unwrap());
-        }
-        if limit.is_some() {
-            args.insert("limit", limit.unwrap());
-        }
+    pub async fn get_reward_fee_fraction(&self) -> Result<lst::RewardFeeFraction> {
         self.0
-            .call("get_validators")
-            .args_json(json!(args))
+            .call("get_reward_fee_fraction")
+            .args_json(json!({}))
             .view()
             .await
             .unwrap()
-            .json::<Vec<lst::ValidatorInfo>>()
+            .json::<lst::RewardFeeFraction>()
     }
 
-    pub async fn get_total_weight(&self) -> Result<u16> {
+    pub async fn get_pending_reward_fee_fraction(&self) -> Result<Option<lst::RewardFeeFraction>> {
         self.0
-            .call("get_total_weight")
+            .call("get_pending_reward_fee_fraction")
             .args_json(json!({}))
             .view()
             .await
             .unwrap()
-            .json::<u16>()
+            .json::<Option<lst::RewardFeeFraction>>()
     }
 
-    pub async fn add_validator(
+    pub async fn set_whitelist_contract_id(
         &self,
         caller: &Account,
-        validator_id: &AccountId,
-        weight: u16,
+        account_id: &AccountId,
     ) -> Result<ExecutionFinalResult> {
         caller
-            .call(self.0.id(), "add_validator")
+            .call(self.0.id(), "set_whitelist_contract_id")
             .args_json(json!({
-                "validator_id": validator_id,
-                "weight": weight
+                "account_id": account_id
             }))
-            .max_gas()
             .deposit(NearToken::from_yoctonear(1))
+            .max_gas()
             .transact()
             .await
     }
 
-    pub async fn add_validators(
+    pub async fn drain_unstake(
         &self,
         caller: &Account,
-        validator_ids: Vec<&AccountId>,
-        weights: Vec<u16>,
+        validator_id: &AccountId,
     ) -> Result<ExecutionFinalResult> {
         caller
-            .call(self.0.id(), "add_validators")
+            .call(self.0.id(), "drain_unstake")
             .args_json(json!({
-                "validator_ids": validator_ids,
-                "weights": weights
+                "validator_id": validator_id
             }))
             .max_gas()
             .deposit(NearToken::from_yoctonear(1))
@@ -480,85 +1403,122 @@ impl LstContract {
             .await
     }
 
-    pub async fn remove_validator(
+    pub async fn drain_withdraw(
         &self,
         caller: &Account,
         validator_id: &AccountId,
     ) -> Result<ExecutionFinalResult> {
         caller
-            .call(self.0.id(), "remove_validator")
+            .call(self.0.id(), "drain_withdraw")
             .args_json(json!({
                 "validator_id": validator_id
             }))
             .max_gas()
+            .transact()
+            .await
+    }
+
+    pub async fn enable_emergency_mode(&self, caller: &Account) -> Result<ExecutionFinalResult> {
+        caller
+            .call(self.0.id(), "enable_emergency_mode")
+            .args_json(json!({}))
             .deposit(NearToken::from_yoctonear(1))
+            .max_gas()
             .transact()
             .await
     }
 
-    pub async fn update_weight(
+    pub async fn emergency_unwind(
         &self,
         caller: &Account,
-        validator_id: &AccountId,
-        weight: u16,
+        max_steps: Option<u32>,
     ) -> Result<ExecutionFinalResult> {
         caller
-            .call(self.0.id(), "update_weight")
+            .call(self.0.id(), "emergency_unwind")
             .args_json(json!({
-                "validator_id": validator_id,
-                "weight": weight
+                "max_steps": max_steps
             }))
             .max_gas()
-            .deposit(NearToken::from_yoctonear(1))
             .transact()
             .await
     }
 
-    pub async fn update_weights(
+    pub async fn emergency_withdraw(
         &self,
         caller: &Account,
-        validator_ids: Vec<&AccountId>,
-        weights: Vec<u16>,
+        shares: Option<u128>,
     ) -> Result<ExecutionFinalResult> {
         caller
-            .call(self.0.id(), "update_weights")
+            .call(self.0.id(), "emergency_withdraw")
             .args_json(json!({
-                "validator_ids": validator_ids,
-                "weights": weights
+                "shares": shares.map(U128)
             }))
             .max_gas()
-            .deposit(NearToken::from_yoctonear(1))
             .transact()
             .await
     }
 
-    pub async fn update_base_stake_amounts(
+    pub async fn get_emergency_status(&self) -> Result<lst::EmergencyStatus> {
+        self.0
+            .call("get_emergency_status")
+            .args_json(json!({}))
+            .view()
+            .await
+            .unwrap()
+            .json::<lst::EmergencyStatus>()
+    }
+
+    pub async fn adjust_validator_amounts(
         &self,
         caller: &Account,
-        validator_ids: Vec<&AccountId>,
-        amounts: Vec<u128>,
+        validator_id: &AccountId,
+        staked_amount: u128,
+        unstaked_amount: u128,
+        reason: &str,
     ) -> Result<ExecutionFinalResult> {
         caller
-            .call(self.0.id(), "update_base_stake_amounts")
+            .call(self.0.id(), "adjust_validator_amounts")
             .args_json(json!({
-                "validator_ids": validator_ids,
-                "amounts": amounts.iter().map(|x| x.to_string()).collect::<Vec<_>>()
+                "validator_id": validator_id,
+                "staked_amount": U128(staked_amount),
+                "unstaked_amount": U128(unstaked_amount),
+                "reason": reason,
             }))
+            .deposit(NearToken::from_yoctonear(1))
             .max_gas()
+            .transact()
+            .await
+    }
+
+    pub async fn write_off_dust(
+        &self,
+        caller: &Account,
+        validator_id: &AccountId,
+        reason: &str,
+    ) -> Result<ExecutionFinalResult> {
+        caller
+            .call(self.0.id(), "write_off_dust")
+            .args_json(json!({
+                "validator_id": validator_id,
+                "reason": reason,
+            }))
             .deposit(NearToken::from_yoctonear(1))
+            .max_gas()
             .transact()
             .await
     }
 
-    pub async fn set_whitelist_contract_id(
+    pub async fn adjust_stake_amount_to_settle(
         &self,
         caller: &Account,
-        account_id: &AccountId,
+        amount: u128,
+        reason: &str,
     ) -> Result<ExecutionFinalResult> {
         caller
-            .call(self.0.id(), "set_whitelist_contract_id")
+            .call(self.0.id(), "adjust_stake_amount_to_settle")
             .args_json(json!({
-                "account_id": account_id
+                "amount": U128(amount),
+                "reason": reason,
             }))
             .deposit(NearToken::from_yoctonear(1))
             .max_gas()
@@ -566,36 +1526,77 @@ impl LstContract {
             .await
     }
 
-    pub async fn drain_unstake(
+    pub async fn set_accounting_limits(
         &self,
         caller: &Account,
-        validator_id: &AccountId,
+        max_adjustment_per_call: u128,
+        max_adjustment_per_epoch: u128,
     ) -> Result<ExecutionFinalResult> {
         caller
-            .call(self.0.id(), "drain_unstake")
+            .call(self.0.id(), "set_accounting_limits")
             .args_json(json!({
-                "validator_id": validator_id
+                "limits": {
+                    "max_adjustment_per_call": U128(max_adjustment_per_call),
+                    "max_adjustment_per_epoch": U128(max_adjustment_per_epoch),
+                }
             }))
+            .deposit(NearToken::from_yoctonear(1))
             .max_gas()
+            .transact()
+            .await
+    }
+
+    pub async fn get_accounting(&self) -> Result<lst::AccountingView> {
+        self.0
+            .call("get_accounting")
+            .args_json(json!({}))
+            .view()
+            .await
+            .unwrap()
+            .json::<lst::AccountingView>()
+    }
+
+    pub async fn set_config(
+        &self,
+        caller: &Account,
+        config: &lst::Config,
+    ) -> Result<ExecutionFinalResult> {
+        caller
+            .call(self.0.id(), "set_config")
+            .args_json(json!({
+                "config": config,
+            }))
             .deposit(NearToken::from_yoctonear(1))
+            .max_gas()
             .transact()
             .await
     }
 
-    pub async fn drain_withdraw(
+    pub async fn set_gas_config(
         &self,
         caller: &Account,
-        validator_id: &AccountId,
+        gas: &lst::GasConfig,
     ) -> Result<ExecutionFinalResult> {
         caller
-            .call(self.0.id(), "drain_withdraw")
+            .call(self.0.id(), "set_gas_config")
             .args_json(json!({
-                "validator_id": validator_id
+                "gas": gas,
             }))
+            .deposit(NearToken::from_yoctonear(1))
             .max_gas()
             .transact()
             .await
     }
+
+    pub async fn get_config(&self) -> Result<lst::Config> {
+        self.0
+            .call("get_config")
+            .args_json(json!({}))
+            .view()
+            .await
+            .unwrap()
+            .json::<lst::Config>()
+    }
 }
 
 /// epoch operation related
@@ -618,6 +1619,49 @@ impl LstContract {
             .await
     }
 
+    pub async fn epoch_run(&self, caller: &Account, max_steps: Option<u32>) -> Result<ExecutionFinalResult> {
+        caller
+            .call(self.0.id(), "epoch_run")
+            .args_json(json!({
+                "max_steps": max_steps
+            }))
+            .max_gas()
+            .transact()
+            .await
+    }
+
+    pub async fn preview_epoch_stake(&self) -> Result<lst::EpochActionPreview> {
+        self.0
+            .call("preview_epoch_stake")
+            .args_json(json!({}))
+            .view()
+            .await
+            .unwrap()
+            .json::<lst::EpochActionPreview>()
+    }
+
+    pub async fn preview_epoch_unstake(&self) -> Result<lst::EpochActionPreview> {
+        self.0
+            .call("preview_epoch_unstake")
+            .args_json(json!({}))
+            .view()
+            .await
+            .unwrap()
+            .json::<lst::EpochActionPreview>()
+    }
+
+    pub async fn preview_rewards(&self, validator_id: &AccountId) -> Result<lst::RewardsPreview> {
+        self.0
+            .call("preview_rewards")
+            .args_json(json!({
+                "validator_id": validator_id
+            }))
+            .view()
+            .await
+            .unwrap()
+            .json::<lst::RewardsPreview>()
+    }
+
     pub async fn epoch_update_rewards(&self, caller: &Account, validator_id: &AccountId) -> Result<ExecutionFinalResult> {
         caller
             .call(self.0.id(), "epoch_update_rewards")
@@ -802,6 +1846,56 @@ impl LstContract {
             .await
     }
 
+    pub async fn is_paused(&self) -> Result<bool> {
+        self.0
+            .call("is_paused")
+            .args_json(json!({}))
+            .view()
+            .await
+            .unwrap()
+            .json::<bool>()
+    }
+
+    pub async fn get_contract_info(&self) -> Result<lst::ContractInfo> {
+        self.0
+            .call("get_contract_info")
+            .args_json(json!({}))
+            .view()
+            .await
+            .unwrap()
+            .json::<lst::ContractInfo>()
+    }
+
+    pub async fn enable_exit_only_mode(&self, caller: &Account) -> Result<ExecutionFinalResult> {
+        caller
+            .call(self.0.id(), "enable_exit_only_mode")
+            .args_json(json!({}))
+            .deposit(NearToken::from_yoctonear(1))
+            .max_gas()
+            .transact()
+            .await
+    }
+
+    pub async fn disable_exit_only_mode(&self, caller: &Account) -> Result<ExecutionFinalResult> {
+        caller
+            .call(self.0.id(), "disable_exit_only_mode")
+            .args_json(json!({}))
+            .deposit(NearToken::from_yoctonear(1))
+            .max_gas()
+            .transact()
+            .await
+    }
+
+    pub async fn get_pause_status(&self) -> Result<lst::PauseStatus> {
+        self.0
+            .call("get_pause_status")
+            .args_json(json!({}))
+            .view()
+            .await
+            .unwrap()
+            .json::<lst::PauseStatus>()
+    }
+
     pub async fn pa_unpause_feature(
         &self,
         caller: &Account,
@@ -63,4 +63,15 @@ impl MockValidatorContract {
             .transact()
             .await
     }
+
+    pub async fn set_panic(&self, caller: &Account, panic: bool) -> Result<ExecutionFinalResult> {
+        caller
+            .call(self.0.id(), "set_panic")
+            .args_json(json!({
+                "panic": panic
+            }))
+            .max_gas()
+            .transact()
+            .await
+    }
 }
@@ -0,0 +1,50 @@
+mod setup;
+use setup::*;
+
+const ERR_PERM: &str = "Smart contract panicked: Insufficient permissions for method";
+
+fn near(amount: u128) -> u128 {
+    NearToken::from_near(amount).as_yoctonear()
+}
+
+#[tokio::test]
+async fn test_accounting_adjustments() {
+    let worker = near_workspaces::sandbox().await.unwrap();
+    let (context, validators) = Context::with_validators(&worker, &[10]).await;
+    let v1 = &validators[0];
+    context.register_and_stake(50).await;
+    context.op_epoch_stake_all().await;
+    context.check_validator_amount(&v1, near(60), 0, None, None).await;
+
+    check!(context.lst_contract.acl_grant_role(&context.root, "Accountant".to_string(), context.manager.id()));
+    check!(context.lst_contract.adjust_validator_amounts(&context.alice, v1.0.id(), near(55), 0, "audit"), ERR_PERM);
+
+    // reason is mandatory and each call is bounded
+    check!(context.lst_contract.adjust_validator_amounts(&context.manager, v1.0.id(), near(55), 0, " "), lst::ERR_INVALID_ADJUSTMENT_REASON);
+    check!(context.lst_contract.adjust_validator_amounts(&context.manager, v1.0.id(), near(40), 0, "audit"), lst::ERR_ADJUSTMENT_TOO_LARGE);
+    check!(context.lst_contract.adjust_validator_amounts(&context.manager, v1.0.id(), near(60), 0, "audit"), lst::ERR_NO_ADJUSTMENT);
+
+    check!(context.lst_contract.adjust_validator_amounts(&context.manager, v1.0.id(), near(55), 0, "audit"));
+    context.check_validator_amount(&v1, near(55), 0, None, None).await;
+    assert_eq!(context.lst_contract.get_accounting().await.unwrap().epoch_adjusted_amount.0, near(5));
+
+    // the per epoch limit counts all adjustments
+    check!(context.lst_contract.set_accounting_limits(&context.manager, near(10), near(12)), ERR_PERM);
+    check!(context.lst_contract.set_accounting_limits(&context.root, near(20), near(12)), lst::ERR_INVALID_ACCOUNTING_LIMITS);
+    check!(context.lst_contract.set_accounting_limits(&context.root, near(10), near(12)));
+    check!(context.lst_contract.adjust_validator_amounts(&context.manager, v1.0.id(), near(60), near(1) / 2, "audit"));
+    assert_eq!(context.lst_contract.get_accounting().await.unwrap().epoch_headroom.0, near(3) / 2);
+    check!(context.lst_contract.adjust_stake_amount_to_settle(&context.manager, near(2), "audit"), lst::ERR_EPOCH_ADJUSTMENT_LIMIT_EXCEEDED);
+
+    context.epoch_height_fast_forward(Some(1)).await;
+    assert_eq!(context.lst_contract.get_accounting().await.unwrap().epoch_adjusted_amount.0, 0);
+    check!(context.lst_contract.adjust_stake_amount_to_settle(&context.manager, near(2), "audit"));
+    check!(context.lst_contract.adjust_stake_amount_to_settle(&context.manager, 0, "audit"));
+
+    // dust on the validator is written off
+    check!(context.lst_contract.write_off_dust(&context.manager, v1.0.id(), "dust"));
+    context.check_validator_amount(&v1, near(60), 0, None, None).await;
+    check!(context.lst_contract.write_off_dust(&context.manager, v1.0.id(), "dust"), lst::ERR_NO_ADJUSTMENT);
+    check!(context.lst_contract.adjust_validator_amounts(&context.manager, v1.0.id(), near(60), near(1), "audit"));
+    check!(context.lst_contract.write_off_dust(&context.manager, v1.0.id(), "dust"), lst::ERR_NOT_DUST);
+}
@@ -0,0 +1,95 @@
+mod setup;
+use setup::*;
+
+const ERR_PERM: &str = "Smart contract panicked: Insufficient permissions for method";
+
+#[tokio::test]
+async fn test_operator_approvals() {
+    let worker = near_workspaces::sandbox().await.unwrap();
+    let (context, validators) = Context::with_validators(&worker, &[10, 10]).await;
+    let (v1, v2) = (&validators[0], &validators[1]);
+    check!(context.lst_contract.acl_grant_role(&context.root, "OpManager".to_string(), context.manager.id()));
+    check!(context.lst_contract.acl_grant_role(&context.root, "OpManager".to_string(), context.alice.id()));
+
+    let update_weights = json!({ "UpdateWeights": { "validator_ids": [v1.0.id()], "weights": [5] } });
+    check!(context.lst_contract.propose_operator_action(&context.manager, update_weights.clone()), lst::ERR_APPROVALS_NOT_ENABLED);
+
+    check!(context.lst_contract.set_approval_config(&context.manager, 2, 3600), ERR_PERM);
+    check!(context.lst_contract.set_approval_config(&context.root, 0, 3600), lst::ERR_INVALID_APPROVAL_CONFIG);
+    check!(context.lst_contract.set_approval_config(&context.root, 2, 3600));
+
+    // the actions can't be called directly any more
+    check!(context.lst_contract.update_weights(&context.manager, vec![v1.0.id()], vec![5]), lst::ERR_APPROVAL_REQUIRED);
+    check!(context.lst_contract.remove_validator(&context.manager, v2.0.id()), lst::ERR_APPROVAL_REQUIRED);
+    check!(context.lst_contract.queue_timelock_action(&context.manager, update_weights.clone()), lst::ERR_APPROVAL_REQUIRED);
+
+    check!(context.lst_contract.propose_operator_action(&context.bob, update_weights.clone()), lst::ERR_APPROVAL_NOT_ALLOWED);
+    check!(context.lst_contract.propose_operator_action(&context.manager, update_weights));
+    check!(context.lst_contract.approve_operator_action(&context.manager, 0), lst::ERR_ALREADY_APPROVED);
+    check!(context.lst_contract.approve_operator_action(&context.bob, 0), lst::ERR_APPROVAL_NOT_ALLOWED);
+    check!(context.lst_contract.execute_operator_action(&context.bob, 0), lst::ERR_NOT_ENOUGH_APPROVALS);
+    let proposals = context.lst_contract.get_operator_proposals().await.unwrap();
+    assert_eq!(proposals.len(), 1);
+    assert_eq!(proposals[0].approvals, vec![context.manager.id().clone()]);
+
+    // the approval meeting the threshold runs the action
+    check!(context.lst_contract.approve_operator_action(&context.alice, 0));
+    assert_eq!(context.lst_contract.get_validator(v1.0.id()).await.unwrap().unwrap().weight, 5);
+    assert!(context.lst_contract.get_operator_proposals().await.unwrap().is_empty());
+
+    let remove_validator = json!({ "RemoveValidator": { "validator_id": v2.0.id() } });
+    check!(context.lst_contract.propose_operator_action(&context.manager, remove_validator.clone()));
+    check!(context.lst_contract.cancel_operator_action(&context.alice, 1), lst::ERR_APPROVAL_NOT_ALLOWED);
+    check!(context.lst_contract.cancel_operator_action(&context.root, 1));
+    check!(context.lst_contract.approve_operator_action(&context.alice, 1), lst::ERR_APPROVAL_PROPOSAL_NOT_EXIST);
+
+    check!(context.lst_contract.propose_operator_action(&context.manager, remove_validator));
+    check!(context.lst_contract.approve_operator_action(&context.root, 2));
+    assert_eq!(context.lst_contract.get_validators(None, None).await.unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn test_operator_proposal_expires() {
+    let worker = near_workspaces::sandbox().await.unwrap();
+    let (context, validators) = Context::with_validators(&worker, &[10]).await;
+    let v1 = &validators[0];
+    check!(context.lst_contract.acl_grant_role(&context.root, "OpManager".to_string(), context.manager.id()));
+    check!(context.lst_contract.set_approval_config(&context.root, 2, 1));
+
+    check!(context.lst_contract.propose_operator_action(
+        &context.manager,
+        json!({ "RemoveValidator": { "validator_id": v1.0.id() } })
+    ));
+    worker.fast_forward(100).await.unwrap();
+
+    assert!(context.lst_contract.get_operator_proposals().await.unwrap().is_empty());
+    check!(context.lst_contract.approve_operator_action(&context.root, 0), lst::ERR_APPROVAL_PROPOSAL_EXPIRED);
+    // anyone can clean up an expired proposal
+    check!(context.lst_contract.cancel_operator_action(&context.bob, 0));
+    assert_eq!(context.lst_contract.get_validators(None, None).await.unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn test_approved_weights_wait_for_timelock() {
+    let worker = near_workspaces::sandbox().await.unwrap();
+    let (context, validators) = Context::with_validators(&worker, &[10]).await;
+    let v1 = &validators[0];
+    check!(context.lst_contract.acl_grant_role(&context.root, "OpManager".to_string(), context.manager.id()));
+    check!(context.lst_contract.set_approval_config(&context.root, 2, 3600));
+    check!(context.lst_contract.set_timelock_delay(&context.root, "UpdateWeights", 10));
+
+    // the approved update is queued instead of run
+    let update_weights = json!({ "UpdateWeights": { "validator_ids": [v1.0.id()], "weights": [5] } });
+    check!(context.lst_contract.propose_operator_action(&context.manager, update_weights));
+    check!(context.lst_contract.approve_operator_action(&context.root, 0));
+    assert_eq!(context.lst_contract.get_validator(v1.0.id()).await.unwrap().unwrap().weight, 10);
+    let queue = context.lst_contract.get_timelock_queue().await.unwrap();
+    assert_eq!(queue.len(), 1);
+    assert_eq!(&queue[0].proposer_id, context.manager.id());
+    assert_eq!(queue[0].eta.0, queue[0].queued_at.0 + 10 * 1_000_000_000);
+
+    check!(context.lst_contract.execute_timelock_action(&context.bob, 0), lst::ERR_TIMELOCK_NOT_READY);
+    worker.fast_forward(100).await.unwrap();
+    check!(context.lst_contract.execute_timelock_action(&context.bob, 0));
+    assert_eq!(context.lst_contract.get_validator(v1.0.id()).await.unwrap().unwrap().weight, 5);
+}
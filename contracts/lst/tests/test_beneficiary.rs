@@ -0,0 +1,60 @@
+mod setup;
+use setup::*;
+
+#[tokio::test]
+async fn test_beneficiary_near_payout() {
+    let worker = near_workspaces::sandbox().await.unwrap();
+    let (context, validators) = Context::with_validators(&worker, &[10]).await;
+    let v1 = &validators[0];
+    context.register_and_stake(60).await;
+    context.op_epoch_stake_all().await;
+
+    check!(context.lst_contract.set_timelock_delay(&context.root, "SetBeneficiary", 0));
+    check!(context.lst_contract.set_beneficiary(&context.root, context.manager.id(), 1000));
+    check!(context.lst_contract.set_beneficiary_payout(&context.bob, "Near"), lst::ERR_NOT_BENEFICIARY);
+    check!(context.lst_contract.set_beneficiary_payout(&context.manager, "Near"));
+
+    // 10% of the rewards are unstaked for the beneficiary
+    check!(v1.add_reward(context.lst_contract.0.as_account(), NearToken::from_near(2).as_yoctonear()));
+    check!(context.lst_contract.epoch_update_rewards(&context.root, v1.0.id()));
+    let reward_amount = NearToken::from_millinear(200).as_yoctonear();
+    let earnings = context.lst_contract.get_beneficiary_earnings(context.manager.id()).await.unwrap().unwrap();
+    assert!(earnings.earned_amount.0 <= reward_amount && earnings.earned_amount.0 > reward_amount - 10);
+    assert_eq!(earnings.unstaked_amount, earnings.earned_amount);
+    assert_eq!(earnings.claimable_amount.0, 0);
+    assert_eq!(context.lst_contract.ft_balance_of(context.manager.id()).await.unwrap().0, 0);
+    assert_eq!(
+        context.lst_contract.get_total_staked_balance().await.unwrap().0,
+        NearToken::from_near(72).as_yoctonear() - earnings.earned_amount.0
+    );
+
+    let history = context.lst_contract.get_beneficiary_payout_history(None).await.unwrap();
+    assert_eq!(history.len(), 1);
+    assert_eq!(&history[0].account_id, context.manager.id());
+    assert!(history[0].payout == lst::BeneficiaryPayoutKind::Near);
+    assert!(context.lst_contract.get_beneficiary_payout_history(Some(context.alice.id())).await.unwrap().is_empty());
+
+    // claimable once the unstake is settled
+    check!(context.lst_contract.claim_beneficiary_payout(&context.manager), lst::ERR_UNSTAKED_BALANCE_NOT_AVAILABLE);
+    let epoch = context.lst_contract.read_epoch_height().await.unwrap();
+    check!(context.lst_contract.set_epoch_height(&context.root, epoch + 1));
+    context.op_epoch_unstake_all().await;
+    let earnings = context.lst_contract.get_beneficiary_earnings(context.manager.id()).await.unwrap().unwrap();
+    assert_eq!(earnings.claimable_amount, earnings.unstaked_amount);
+
+    let balance_before = context.manager.view_account().await.unwrap().balance;
+    check!(context.lst_contract.claim_beneficiary_payout(&context.manager));
+    let balance_after = context.manager.view_account().await.unwrap().balance;
+    assert!(balance_after.as_yoctonear() > balance_before.as_yoctonear() + NearToken::from_millinear(190).as_yoctonear());
+    let claimed = context.lst_contract.get_beneficiary_earnings(context.manager.id()).await.unwrap().unwrap();
+    assert_eq!(claimed.claimed_amount, earnings.earned_amount);
+    assert_eq!(claimed.unstaked_amount.0, 0);
+    check!(context.lst_contract.claim_beneficiary_payout(&context.manager), lst::ERR_NOTHING_TO_CLAIM);
+
+    // switching back to LST
+    check!(context.lst_contract.set_beneficiary_payout(&context.manager, "Lst"));
+    check!(v1.add_reward(context.lst_contract.0.as_account(), NearToken::from_near(2).as_yoctonear()));
+    check!(context.lst_contract.epoch_update_rewards(&context.root, v1.0.id()));
+    assert!(context.lst_contract.ft_balance_of(context.manager.id()).await.unwrap().0 > 0);
+    assert_eq!(context.lst_contract.get_beneficiary_payout_history(Some(context.manager.id())).await.unwrap().len(), 2);
+}
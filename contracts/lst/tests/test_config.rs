@@ -0,0 +1,72 @@
+mod setup;
+use setup::*;
+
+const ERR_PERM: &str = "Smart contract panicked: Insufficient permissions for method";
+
+fn near(amount: u128) -> u128 {
+    NearToken::from_near(amount).as_yoctonear()
+}
+
+#[tokio::test]
+async fn test_config_bounds() {
+    let worker = near_workspaces::sandbox().await.unwrap();
+    let context = Context::new(&worker, None).await;
+
+    let config = context.lst_contract.get_config().await.unwrap();
+    assert_eq!(config.num_epochs_to_unlock, NUM_EPOCHS_TO_UNLOCK);
+    assert_eq!(config.min_amount_to_perform_stake.0, near(1));
+    assert_eq!(config.max_beneficiaries, 10);
+    assert_eq!(config.gas.epoch_stake.as_tgas(), 75);
+
+    check!(context.lst_contract.set_config(&context.alice, &config), ERR_PERM);
+
+    // unstaked NEAR is not released earlier than 4 epochs
+    let mut invalid_config = config;
+    invalid_config.num_epochs_to_unlock = 3;
+    check!(context.lst_contract.set_config(&context.root, &invalid_config), lst::ERR_INVALID_CONFIG);
+
+    let mut invalid_config = config;
+    invalid_config.contract_min_reserve_balance = U128(0);
+    check!(context.lst_contract.set_config(&context.root, &invalid_config), lst::ERR_INVALID_CONFIG);
+
+    // epoch stake with its cross contract calls must fit in a transaction
+    let mut gas = config.gas;
+    gas.epoch_stake = Gas::from_tgas(150);
+    check!(context.lst_contract.set_gas_config(&context.root, &gas), lst::ERR_INVALID_CONFIG);
+    check!(context.lst_contract.set_gas_config(&context.alice, &config.gas), ERR_PERM);
+
+    gas.epoch_stake = Gas::from_tgas(90);
+    check!(context.lst_contract.set_gas_config(&context.root, &gas));
+    let new_config = context.lst_contract.get_config().await.unwrap();
+    assert_eq!(new_config.gas.epoch_stake.as_tgas(), 90);
+    assert_eq!(new_config.num_epochs_to_unlock, NUM_EPOCHS_TO_UNLOCK);
+}
+
+#[tokio::test]
+async fn test_config_takes_effect() {
+    let worker = near_workspaces::sandbox().await.unwrap();
+    let (context, validators) = Context::with_validators(&worker, &[10]).await;
+    let v1 = &validators[0];
+
+    // the initial 10 NEAR is below the min stake amount
+    let mut config = context.lst_contract.get_config().await.unwrap();
+    config.min_amount_to_perform_stake = U128(near(20));
+    check!(context.lst_contract.set_config(&context.root, &config));
+    context.op_epoch_stake_all().await;
+    context.check_validator_amount(&v1, 0, 0, None, None).await;
+
+    config.min_amount_to_perform_stake = U128(near(1));
+    check!(context.lst_contract.set_config(&context.root, &config));
+    context.op_epoch_stake_all().await;
+    context.check_validator_amount(&v1, near(10), 0, None, None).await;
+
+    // the beneficiary limit can't go below the existing beneficiaries
+    check!(context.lst_contract.set_timelock_delay(&context.root, "SetBeneficiary", 0));
+    check!(context.lst_contract.set_beneficiary(&context.root, context.alice.id(), 1000));
+    check!(context.lst_contract.set_beneficiary(&context.root, context.bob.id(), 1000));
+    config.max_beneficiaries = 1;
+    check!(context.lst_contract.set_config(&context.root, &config), lst::ERR_INVALID_CONFIG);
+    config.max_beneficiaries = 2;
+    check!(context.lst_contract.set_config(&context.root, &config));
+    check!(context.lst_contract.set_beneficiary(&context.root, context.manager.id(), 1000), lst::ERR_TOO_MANY_BENEFICIARIES);
+}
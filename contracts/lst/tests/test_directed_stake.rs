@@ -0,0 +1,71 @@
+mod setup;
+use setup::*;
+
+#[tokio::test]
+async fn test_directed_stake() {
+    let worker = near_workspaces::sandbox().await.unwrap();
+    let (context, validators) = Context::with_validators(&worker, &[10, 10]).await;
+    let (v1, v2) = (&validators[0], &validators[1]);
+    context.register_and_stake(90).await;
+    check!(
+        context.lst_contract.deposit_and_stake_with_preference(&context.bob, 10, context.alice.id()),
+        lst::ERR_VALIDATOR_NOT_EXIST
+    );
+    check!(context.lst_contract.deposit_and_stake_with_preference(&context.bob, 10, v2.0.id()));
+
+    let preference = context.lst_contract.get_validator_preference(context.bob.id()).await.unwrap().unwrap();
+    assert_eq!(&preference.validator_id, v2.0.id());
+    assert_eq!(preference.directed_shares.0, NearToken::from_near(10).as_yoctonear());
+    assert_eq!(preference.directed_stake_amount.0, NearToken::from_near(10).as_yoctonear());
+
+    // directed stake is reserved on v2 before the rest is split by weights
+    context.op_epoch_stake_all().await;
+    context.check_validator_amount(&v1, NearToken::from_near(50).as_yoctonear(), 0, None, None).await;
+    context.check_validator_amount(&v2, NearToken::from_near(60).as_yoctonear(), 0, None, None).await;
+
+    // preference follows the LST balance
+    check!(context.lst_contract.ft_transfer(&context.bob, context.alice.id(), NearToken::from_near(4).as_yoctonear()));
+    let validator = context.lst_contract.get_validator(v2.0.id()).await.unwrap().unwrap();
+    assert_eq!(validator.directed_shares.0, NearToken::from_near(6).as_yoctonear());
+    assert_eq!(validator.target_stake_amount.0, NearToken::from_near(58).as_yoctonear());
+
+    // clearing the preference falls back to the pool weights
+    check!(context.lst_contract.set_validator_preference(&context.bob, None, 0));
+    assert!(context.lst_contract.get_validator_preference(context.bob.id()).await.unwrap().is_none());
+    let validator = context.lst_contract.get_validator(v2.0.id()).await.unwrap().unwrap();
+    assert_eq!(validator.directed_shares.0, 0);
+    assert_eq!(validator.target_stake_amount.0, NearToken::from_near(55).as_yoctonear());
+
+    // a new preference pays for its storage
+    check!(context.lst_contract.set_validator_preference(&context.bob, Some(v1.0.id()), 0), lst::ERR_INSUFFICIENT_STORAGE_DEPOSIT);
+    check!(context.lst_contract.set_validator_preference(&context.bob, Some(v1.0.id()), FT_STORAGE_DEPOSIT));
+    assert_eq!(&context.lst_contract.get_validator_preference(context.bob.id()).await.unwrap().unwrap().validator_id, v1.0.id());
+}
+
+#[tokio::test]
+async fn test_directed_stake_cap() {
+    let worker = near_workspaces::sandbox().await.unwrap();
+    let (context, validators) = Context::with_validators(&worker, &[10, 0]).await;
+    let v2 = &validators[1];
+    context.register_and_stake(40).await;
+
+    // validators with zero weight can't take directed stake
+    check!(
+        context.lst_contract.deposit_and_stake_with_preference(&context.bob, 50, v2.0.id()),
+        lst::ERR_VALIDATOR_NO_WEIGHT
+    );
+    check!(context.lst_contract.update_weight(&context.root, v2.0.id(), 10));
+
+    // only 10% of the total supply can be directed to a single validator
+    check!(context.lst_contract.deposit_and_stake_with_preference(&context.bob, 50, v2.0.id()));
+    let preference = context.lst_contract.get_validator_preference(context.bob.id()).await.unwrap().unwrap();
+    assert_eq!(preference.directed_shares.0, NearToken::from_near(10).as_yoctonear());
+
+    // the cap is a config parameter
+    let mut config = context.lst_contract.get_config().await.unwrap();
+    config.max_validator_directed_stake_bps = 2_000;
+    check!(context.lst_contract.set_config(&context.root, &config));
+    check!(context.lst_contract.ft_transfer(&context.alice, context.bob.id(), NearToken::from_near(1).as_yoctonear()));
+    let preference = context.lst_contract.get_validator_preference(context.bob.id()).await.unwrap().unwrap();
+    assert_eq!(preference.directed_shares.0, NearToken::from_near(20).as_yoctonear());
+}
@@ -0,0 +1,66 @@
+mod setup;
+use setup::*;
+
+const ERR_PERM: &str = "Smart contract panicked: Insufficient permissions for method";
+
+#[tokio::test]
+async fn test_emergency_unwind_and_withdraw() {
+    let worker = near_workspaces::sandbox().await.unwrap();
+    let (context, validators) = Context::with_validators(&worker, &[10, 10]).await;
+    let v1 = &validators[0];
+    context.register_and_stake(50).await;
+    context.op_epoch_stake_all().await;
+
+    // only DAO can enable emergency mode
+    check!(context.lst_contract.acl_grant_role(&context.root, "OpManager".to_string(), context.manager.id()));
+    check!(context.lst_contract.enable_emergency_mode(&context.manager), ERR_PERM);
+    check!(context.lst_contract.emergency_unwind(&context.bob, None), lst::ERR_NOT_IN_EMERGENCY_MODE);
+    check!(context.lst_contract.enable_emergency_mode(&context.root));
+    check!(context.lst_contract.enable_emergency_mode(&context.root), lst::ERR_EMERGENCY_MODE);
+
+    let status = context.lst_contract.get_emergency_status().await.unwrap();
+    assert!(status.enabled);
+    assert_eq!(status.num_validators_staked, 2);
+    assert_eq!(status.staked_amount.0, NearToken::from_near(60).as_yoctonear());
+    assert_eq!(status.validators_to_unstake.len(), 2);
+    assert_eq!(context.lst_contract.get_validator(v1.0.id()).await.unwrap().unwrap().weight, 0);
+
+    // deposits, stakes and unstakes are blocked
+    check!(context.lst_contract.deposit_and_stake(&context.alice, 10), lst::ERR_EMERGENCY_MODE);
+    check!(context.lst_contract.unstake(&context.alice, 10), lst::ERR_EMERGENCY_MODE);
+
+    // the unwind goes on while everything else is paused
+    check!(context.lst_contract.pa_pause_feature(&context.root, "ALL".to_string()));
+
+    // keepers drain validators one by one
+    check!(context.lst_contract.emergency_unwind(&context.bob, None));
+    check!(context.lst_contract.emergency_unwind(&context.bob, None));
+    let status = context.lst_contract.get_emergency_status().await.unwrap();
+    assert_eq!(status.num_validators_staked, 0);
+    assert_eq!(status.num_validators_draining, 2);
+    assert_eq!(status.unstaked_amount.0, NearToken::from_near(60).as_yoctonear());
+    assert!(status.validators_to_withdraw.is_empty());
+
+    // funds have not arrived yet
+    check!(context.lst_contract.emergency_withdraw(&context.alice, None), lst::ERR_EMERGENCY_FUNDS_NOT_AVAILABLE);
+
+    context.epoch_height_fast_forward(None).await;
+    check!(context.lst_contract.emergency_unwind(&context.bob, None));
+    check!(context.lst_contract.emergency_unwind(&context.bob, None));
+    let status = context.lst_contract.get_emergency_status().await.unwrap();
+    assert_eq!(status.num_validators_drained, 2);
+    assert_eq!(status.unstaked_amount.0, 0);
+    assert_eq!(status.unwound_amount.0, NearToken::from_near(60).as_yoctonear());
+    assert_eq!(status.available_amount.0, NearToken::from_near(60).as_yoctonear());
+
+    // LST holders withdraw their share of the unwound NEAR
+    let balance_before = context.alice.view_account().await.unwrap().balance;
+    check!(context.lst_contract.emergency_withdraw(&context.alice, None));
+    let balance_after = context.alice.view_account().await.unwrap().balance;
+    assert!(balance_after > balance_before.saturating_add(NearToken::from_near(49)));
+    assert_eq!(context.lst_contract.ft_balance_of(context.alice.id()).await.unwrap().0, 0);
+
+    let status = context.lst_contract.get_emergency_status().await.unwrap();
+    assert_eq!(status.paid_amount.0, NearToken::from_near(50).as_yoctonear());
+    assert_eq!(status.available_amount.0, NearToken::from_near(10).as_yoctonear());
+}
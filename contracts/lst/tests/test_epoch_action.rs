@@ -357,6 +357,7 @@ async fn test_epoch_rewards() {
         None).await;
     
     // set beneficiary
+    check!(context.lst_contract.set_timelock_delay(&context.root, "SetBeneficiary", 0));
     check!(context.lst_contract.set_beneficiary(&context.root, context.manager.id(), 1000));
 
     // generate more rewards
@@ -501,4 +502,311 @@ async fn test_epoch_withdraw() {
     context.check_validator_amount(&v1, NearToken::from_near(20).as_yoctonear(), 0, None, None).await;
     context.check_validator_amount(&v2, NearToken::from_millinear(32500).as_yoctonear(), 0, None, None).await;
     context.check_validator_amount(&v3, NearToken::from_millinear(37500).as_yoctonear(), 0, None, None).await;
-}
\ No newline at end of file
+}
+
+#[tokio::test]
+async fn test_withdrawal_reserve() {
+    let worker = near_workspaces::sandbox().await.unwrap();
+    let context = Context::new(&worker, None).await;
+    check!(context.mock_whitelist.allow_all(&context.root));
+
+    let v1 = context.create_validator("v1").await;
+    let v2 = context.create_validator("v2").await;
+    let v3 = context.create_validator("v3").await;
+
+    check!(context.lst_contract.add_validator(&context.root, v1.0.id(), 10));
+    check!(context.lst_contract.add_validator(&context.root, v2.0.id(), 20));
+    check!(context.lst_contract.add_validator(&context.root, v3.0.id(), 30));
+
+    check!(context.lst_contract.storage_deposit(&context.alice, None, FT_STORAGE_DEPOSIT));
+    check!(context.lst_contract.deposit_and_stake(&context.alice, 110));
+    context.op_epoch_stake_all().await;
+
+    // fast-forward
+    check!(context.lst_contract.set_epoch_height(&context.root, 11));
+
+    // user unstake
+    check!(context.lst_contract.unstake(&context.alice, 30));
+    context.op_epoch_unstake_all().await;
+    assert_eq!(context.lst_contract.get_summary().await.unwrap().withdrawal_reserve.0, 0);
+
+    // fast-forward 4 epochs and withdraw from validators
+    check!(context.lst_contract.set_epoch_height(&context.root, 15));
+    check!(context.lst_contract.epoch_withdraw(&context.root, v2.0.id()));
+    check!(context.lst_contract.epoch_withdraw(&context.root, v3.0.id()));
+
+    // withdrawn NEAR is reserved for alice
+    let summary = context.lst_contract.get_summary().await.unwrap();
+    assert_eq!(summary.withdrawal_reserve.0, NearToken::from_near(30).as_yoctonear());
+    assert_eq!(summary.withdrawal_reserve_shortfall.0, 0);
+
+    // new stake doesn't touch the reserve
+    check!(context.lst_contract.storage_deposit(&context.bob, None, FT_STORAGE_DEPOSIT));
+    check!(context.lst_contract.deposit_and_stake(&context.bob, 10));
+    context.op_epoch_stake_all().await;
+    assert_eq!(context.lst_contract.get_summary().await.unwrap().withdrawal_reserve.0, NearToken::from_near(30).as_yoctonear());
+
+    // NEAR deposited without staking is held until it is withdrawn
+    check!(context.lst_contract.deposit(&context.bob, 2));
+    assert_eq!(context.lst_contract.get_summary().await.unwrap().withdrawal_reserve.0, NearToken::from_near(32).as_yoctonear());
+    check!(context.lst_contract.withdraw(&context.bob, 2));
+
+    // withdraw drains the reserve
+    check!(context.lst_contract.withdraw(&context.alice, 30));
+    let summary = context.lst_contract.get_summary().await.unwrap();
+    assert_eq!(summary.withdrawal_reserve.0, 0);
+    assert_eq!(summary.withdrawal_reserve_advance.0, 0);
+}
+
+#[tokio::test]
+async fn test_epoch_run() {
+    let worker = near_workspaces::sandbox().await.unwrap();
+    let context = Context::new(&worker, None).await;
+    check!(context.mock_whitelist.allow_all(&context.root));
+
+    let v1 = context.create_validator("v1").await;
+    let v2 = context.create_validator("v2").await;
+    check!(context.lst_contract.add_validator(&context.root, v1.0.id(), 10));
+    check!(context.lst_contract.add_validator(&context.root, v2.0.id(), 10));
+
+    check!(context.lst_contract.storage_deposit(&context.alice, None, FT_STORAGE_DEPOSIT));
+    check!(context.lst_contract.deposit_and_stake(&context.alice, 50));
+
+    // a single stake fits in the gas limit
+    let outcome = context.lst_contract.epoch_run(&context.alice, None).await.unwrap();
+    assert!(outcome.is_success() && outcome.receipt_failures().is_empty());
+    let result = outcome.json::<lst::EpochRunResult>().unwrap();
+    assert_eq!(result.executed_actions.len(), 1);
+    assert!(matches!(result.executed_actions[0], lst::EpochActionView::Stake { .. }));
+    assert_eq!(result.stake_amount_to_settle.0, NearToken::from_near(30).as_yoctonear());
+
+    let outcome = context.lst_contract.epoch_run(&context.alice, None).await.unwrap();
+    assert!(outcome.is_success() && outcome.receipt_failures().is_empty());
+    let result = outcome.json::<lst::EpochRunResult>().unwrap();
+    assert_eq!(result.stake_amount_to_settle.0, 0);
+    context.check_validator_amount(&v1, NearToken::from_near(30).as_yoctonear(), 0, None, None).await;
+    context.check_validator_amount(&v2, NearToken::from_near(30).as_yoctonear(), 0, None, None).await;
+
+    // rewards of both validators are stale in the next epoch
+    check!(context.lst_contract.set_epoch_height(&context.root, 11));
+    let outcome = context.lst_contract.epoch_run(&context.alice, Some(1)).await.unwrap();
+    let result = outcome.json::<lst::EpochRunResult>().unwrap();
+    assert_eq!(result.executed_actions.len(), 1);
+    assert!(matches!(result.executed_actions[0], lst::EpochActionView::UpdateRewards { .. }));
+    assert_eq!(result.validators_to_update_rewards.len(), 1);
+
+    // ping runs the rest
+    check!(context.root.call(context.lst_contract.0.id(), "ping").max_gas().transact());
+    let outcome = context.lst_contract.epoch_run(&context.alice, None).await.unwrap();
+    let result = outcome.json::<lst::EpochRunResult>().unwrap();
+    assert!(result.executed_actions.is_empty());
+    assert!(result.validators_to_update_rewards.is_empty());
+    assert!(result.validators_to_withdraw.is_empty());
+}
+
+#[tokio::test]
+async fn test_epoch_run_reserve_shortfall() {
+    let worker = near_workspaces::sandbox().await.unwrap();
+    let (context, validators) = Context::with_validators(&worker, &[10]).await;
+    let v1 = &validators[0];
+    context.register_and_stake(800).await;
+    context.op_epoch_stake_all().await;
+
+    // fast-forward
+    check!(context.lst_contract.set_epoch_height(&context.root, 11));
+    check!(context.lst_contract.unstake(&context.alice, 500));
+    context.op_epoch_unstake_all().await;
+
+    // the unstaked NEAR is staked again before it is withdrawn from the validator,
+    // so the contract balance can't fund the stake yet
+    check!(context.lst_contract.set_epoch_height(&context.root, 12));
+    check!(context.lst_contract.stake(&context.alice, 500));
+    check!(context.lst_contract.set_epoch_height(&context.root, 15));
+    check!(context.lst_contract.epoch_stake(&context.root), lst::ERR_MIN_RESERVE);
+
+    // epoch_run skips the stake and goes on with the other steps
+    let outcome = context.lst_contract.epoch_run(&context.alice, None).await.unwrap();
+    assert!(outcome.is_success() && outcome.receipt_failures().is_empty());
+    let result = outcome.json::<lst::EpochRunResult>().unwrap();
+    assert!(matches!(result.executed_actions[..], [lst::EpochActionView::UpdateRewards { .. }]));
+    assert_eq!(result.stake_amount_to_settle.0, NearToken::from_near(500).as_yoctonear());
+
+    let outcome = context.lst_contract.epoch_run(&context.alice, None).await.unwrap();
+    assert!(outcome.is_success() && outcome.receipt_failures().is_empty());
+    let result = outcome.json::<lst::EpochRunResult>().unwrap();
+    assert!(matches!(result.executed_actions[..], [lst::EpochActionView::Withdraw { .. }]));
+    context.check_validator_amount(v1, NearToken::from_near(310).as_yoctonear(), 0, None, None).await;
+
+    // the withdrawn NEAR funds the stake of the next run
+    let outcome = context.lst_contract.epoch_run(&context.alice, None).await.unwrap();
+    let result = outcome.json::<lst::EpochRunResult>().unwrap();
+    assert!(matches!(result.executed_actions[..], [lst::EpochActionView::Stake { .. }]));
+    assert_eq!(result.stake_amount_to_settle.0, 0);
+}
+
+#[tokio::test]
+async fn test_epoch_update_rewards_shortfall() {
+    let worker = near_workspaces::sandbox().await.unwrap();
+    let (context, validators) = Context::with_validators(&worker, &[10]).await;
+    let v1 = &validators[0];
+
+    // the validator loses 5 NEAR on stake
+    check!(v1.set_balance_delta(&context.root, NearToken::from_near(5).as_yoctonear(), 0));
+    context.register_and_stake(50).await;
+    context.op_epoch_stake_all().await;
+    check!(v1.set_balance_delta(&context.root, 0, 0));
+    let staked_amount = context.lst_contract.get_validator(v1.0.id()).await.unwrap().unwrap().staked_amount.0;
+    let total_staked = context.lst_contract.get_summary().await.unwrap().total_staked_near_amount.0;
+
+    // the shortfall is reported and the known balance is kept
+    let epoch = context.lst_contract.read_epoch_height().await.unwrap();
+    check!(context.lst_contract.set_epoch_height(&context.root, epoch + 1));
+    check!(context.lst_contract.epoch_update_rewards(&context.root, v1.0.id()));
+    let validator = context.lst_contract.get_validator(v1.0.id()).await.unwrap().unwrap();
+    assert!(validator.executing.is_none());
+    assert_eq!(validator.staked_amount.0, staked_amount);
+    assert!(!context.lst_contract.preview_rewards(v1.0.id()).await.unwrap().need_rewards_update);
+    assert_eq!(context.lst_contract.get_summary().await.unwrap().total_staked_near_amount.0, total_staked);
+
+    // later rewards make up for the shortfall first
+    check!(v1.add_reward(context.lst_contract.0.as_account(), NearToken::from_near(7).as_yoctonear()));
+    check!(context.lst_contract.set_epoch_height(&context.root, epoch + 2));
+    check!(context.lst_contract.epoch_update_rewards(&context.root, v1.0.id()));
+    let validator = context.lst_contract.get_validator(v1.0.id()).await.unwrap().unwrap();
+    assert_eq!(validator.staked_amount.0, staked_amount + NearToken::from_near(2).as_yoctonear());
+    assert_eq!(
+        context.lst_contract.get_summary().await.unwrap().total_staked_near_amount.0,
+        total_staked + NearToken::from_near(2).as_yoctonear()
+    );
+}
+
+#[tokio::test]
+async fn test_epoch_run_multiple_stakes() {
+    let worker = near_workspaces::sandbox().await.unwrap();
+    let (context, validators) = Context::with_validators(&worker, &[10, 10]).await;
+    let (v1, v2) = (&validators[0], &validators[1]);
+
+    // with less gas per stake, a single run stakes on both validators
+    let mut config = context.lst_contract.get_config().await.unwrap();
+    config.gas.ext_deposit_and_stake = Gas::from_tgas(20);
+    config.gas.sync_balance = Gas::from_tgas(40);
+    check!(context.lst_contract.set_config(&context.root, &config));
+    context.register_and_stake(50).await;
+
+    let outcome = context.lst_contract.epoch_run(&context.alice, None).await.unwrap();
+    assert!(outcome.is_success() && outcome.receipt_failures().is_empty());
+    let result = outcome.json::<lst::EpochRunResult>().unwrap();
+    assert_eq!(result.executed_actions.len(), 2);
+    assert!(result.executed_actions.iter().all(|action| matches!(action, lst::EpochActionView::Stake { .. })));
+    assert_eq!(result.stake_amount_to_settle.0, 0);
+    context.check_validator_amount(&v1, NearToken::from_near(30).as_yoctonear(), 0, None, None).await;
+    context.check_validator_amount(&v2, NearToken::from_near(30).as_yoctonear(), 0, None, None).await;
+}
+
+#[tokio::test]
+async fn test_epoch_previews() {
+    let worker = near_workspaces::sandbox().await.unwrap();
+    let context = Context::new(&worker, None).await;
+    check!(context.mock_whitelist.allow_all(&context.root));
+
+    let v1 = context.create_validator("v1").await;
+    let v2 = context.create_validator("v2").await;
+    check!(context.lst_contract.add_validator(&context.root, v1.0.id(), 10));
+    check!(context.lst_contract.add_validator(&context.root, v2.0.id(), 10));
+
+    check!(context.lst_contract.storage_deposit(&context.alice, None, FT_STORAGE_DEPOSIT));
+    check!(context.lst_contract.deposit_and_stake(&context.alice, 50));
+
+    // previews have no side effects
+    let preview = context.lst_contract.preview_epoch_stake().await.unwrap();
+    assert!(preview.reason.is_none());
+    assert_eq!(preview.amount.0, NearToken::from_near(30).as_yoctonear());
+    assert_eq!(preview.stake_amount_to_settle.0, NearToken::from_near(30).as_yoctonear());
+    let preview_again = context.lst_contract.preview_epoch_stake().await.unwrap();
+    assert_eq!(preview_again.validator_id, preview.validator_id);
+    let preview_unstake = context.lst_contract.preview_epoch_unstake().await.unwrap();
+    assert_eq!(preview_unstake.reason.unwrap(), "no need to unstake, amount to settle is zero");
+
+    // the stake runs as previewed
+    let validator_id = preview.validator_id.unwrap();
+    check!(context.lst_contract.epoch_stake(&context.root));
+    assert_eq!(context.lst_contract.get_validator(&validator_id).await.unwrap().unwrap().staked_amount, preview.amount);
+
+    context.op_epoch_stake_all().await;
+    let preview = context.lst_contract.preview_epoch_stake().await.unwrap();
+    assert!(preview.validator_id.is_none());
+    assert_eq!(preview.reason.unwrap(), "no need to stake, amount to settle is zero");
+
+    // unstake requests are settled in the next epoch
+    check!(context.lst_contract.unstake(&context.alice, 20));
+    check!(context.lst_contract.set_epoch_height(&context.root, 11));
+    let preview = context.lst_contract.preview_epoch_unstake().await.unwrap();
+    assert!(preview.reason.is_none());
+    assert!(preview.validator_id.is_some());
+    assert_eq!(
+        preview.amount.0 + preview.unstake_amount_to_settle.0,
+        NearToken::from_near(20).as_yoctonear()
+    );
+    let summary = context.lst_contract.get_summary().await.unwrap();
+    assert_eq!(summary.unstake_amount_to_settle.0, 0);
+
+    // the unstake runs as previewed
+    let validator_id = preview.validator_id.unwrap();
+    let staked_amount = context.lst_contract.get_validator(&validator_id).await.unwrap().unwrap().staked_amount.0;
+    check!(context.lst_contract.epoch_unstake(&context.root));
+    assert_eq!(context.lst_contract.get_validator(&validator_id).await.unwrap().unwrap().staked_amount.0, staked_amount - preview.amount.0);
+
+    // paused actions are reported
+    check!(context.lst_contract.pa_pause_feature(&context.root, "epoch_unstake".to_string()));
+    let preview = context.lst_contract.preview_epoch_unstake().await.unwrap();
+    assert_eq!(preview.reason.unwrap(), "epoch_unstake is paused");
+
+    // rewards refresh
+    let preview = context.lst_contract.preview_rewards(v1.0.id()).await.unwrap();
+    assert_eq!(preview.total_balance.0, NearToken::from_near(30).as_yoctonear());
+    assert_eq!(preview.current_epoch, 11);
+    assert!(preview.need_rewards_update);
+    check!(context.lst_contract.epoch_update_rewards(&context.root, v1.0.id()));
+    let preview = context.lst_contract.preview_rewards(v1.0.id()).await.unwrap();
+    assert_eq!(preview.rewards_updated_epoch, 11);
+    assert!(!preview.need_rewards_update);
+}
+
+#[tokio::test]
+async fn test_reward_vesting() {
+    let worker = near_workspaces::sandbox().await.unwrap();
+    let context = Context::new(&worker, None).await;
+    check!(context.mock_whitelist.allow_all(&context.root));
+
+    let v1 = context.create_validator("v1").await;
+    check!(context.lst_contract.add_validator(&context.root, v1.0.id(), 10));
+
+    check!(context.lst_contract.storage_deposit(&context.alice, None, FT_STORAGE_DEPOSIT));
+    check!(context.lst_contract.deposit_and_stake(&context.alice, 60));
+    context.op_epoch_stake_all().await;
+
+    check!(context.lst_contract.set_reward_vesting_period(&context.alice, 600), "Insufficient permissions for method");
+    check!(context.lst_contract.set_reward_vesting_period(&context.root, lst::MAX_REWARD_VESTING_PERIOD_SEC + 1), lst::ERR_REWARD_VESTING_PERIOD_TOO_LONG);
+    check!(context.lst_contract.set_reward_vesting_period(&context.root, 600));
+
+    // rewards are counted in the total staked amount at once, but not in the price
+    check!(v1.add_reward(context.lst_contract.0.as_account(), NearToken::from_near(7).as_yoctonear()));
+    check!(context.lst_contract.epoch_update_rewards(&context.root, v1.0.id()));
+    assert_eq!(
+        context.lst_contract.get_total_staked_balance().await.unwrap().0,
+        NearToken::from_near(77).as_yoctonear()
+    );
+    let vesting = context.lst_contract.get_reward_vesting().await.unwrap();
+    assert_eq!(vesting.vesting_amount.0, NearToken::from_near(7).as_yoctonear());
+    assert!(vesting.unvested_amount.0 > NearToken::from_near(6).as_yoctonear());
+    let price = context.lst_contract.ft_price().await.unwrap().0;
+    assert!(price < NearToken::from_millinear(1_020).as_yoctonear());
+    let summary = context.lst_contract.get_summary().await.unwrap();
+    assert!(summary.unvested_rewards.0 > 0);
+
+    // the price rises as the rewards vest
+    worker.fast_forward(100).await.unwrap();
+    let vesting_later = context.lst_contract.get_reward_vesting().await.unwrap();
+    assert!(vesting_later.unvested_amount.0 < vesting.unvested_amount.0);
+    assert!(context.lst_contract.ft_price().await.unwrap().0 > price);
+}
@@ -0,0 +1,144 @@
+mod setup;
+use setup::*;
+
+const ERR_PERM: &str = "Smart contract panicked: Insufficient permissions for method";
+
+#[tokio::test]
+async fn test_fee_config_timelock() {
+    let worker = near_workspaces::sandbox().await.unwrap();
+    let context = Context::new(&worker, None).await;
+
+    let config = json!({
+        "protocol_fee": { "account_id": context.manager.id(), "bps": 500 },
+        "treasury_fee": { "account_id": context.bob.id(), "bps": 300 },
+        "deposit_fee_bps": 50,
+        "instant_exit_fee_bps": 100,
+    });
+    check!(context.lst_contract.announce_fee_config(&context.alice, config.clone()), ERR_PERM);
+    check!(context.lst_contract.announce_fee_config(&context.root, json!({
+        "protocol_fee": { "account_id": context.manager.id(), "bps": 3001 },
+        "deposit_fee_bps": 0,
+        "instant_exit_fee_bps": 0,
+    })), lst::ERR_FEE_TOO_HIGH);
+    check!(context.lst_contract.announce_fee_config(&context.root, json!({
+        "protocol_fee": { "account_id": context.manager.id(), "bps": 500 },
+        "deposit_fee_bps": 50,
+        "instant_exit_fee_bps": 0,
+    })), lst::ERR_NO_FEE_TREASURY);
+
+    let epoch = context.lst_contract.read_epoch_height().await.unwrap();
+    check!(context.lst_contract.announce_fee_config(&context.root, config.clone()));
+    let fees = context.lst_contract.get_fees().await.unwrap();
+    assert_eq!(fees.effective.reward_fee_bps(), 0);
+    assert_eq!(fees.pending.unwrap().effective_epoch, epoch + 4);
+    assert_eq!(context.lst_contract.get_reward_fee_fraction().await.unwrap().numerator, 0);
+    assert_eq!(context.lst_contract.get_pending_reward_fee_fraction().await.unwrap().unwrap().numerator, 800);
+    check!(context.lst_contract.apply_fee_config(&context.alice), lst::ERR_FEE_CONFIG_NOT_EFFECTIVE);
+
+    // cancel and announce again
+    check!(context.lst_contract.cancel_fee_config(&context.alice), ERR_PERM);
+    check!(context.lst_contract.cancel_fee_config(&context.root));
+    assert!(context.lst_contract.get_fees().await.unwrap().pending.is_none());
+    check!(context.lst_contract.apply_fee_config(&context.alice), lst::ERR_NO_PENDING_FEE_CONFIG);
+    check!(context.lst_contract.announce_fee_config(&context.root, config));
+
+    // the change takes effect after the delay
+    check!(context.lst_contract.set_epoch_height(&context.root, epoch + 4));
+    let fees = context.lst_contract.get_fees().await.unwrap();
+    assert_eq!(fees.effective.reward_fee_bps(), 800);
+    assert!(fees.pending.is_none());
+    assert_eq!(context.lst_contract.get_reward_fee_fraction().await.unwrap().numerator, 800);
+    assert!(context.lst_contract.get_pending_reward_fee_fraction().await.unwrap().is_none());
+    check!(context.lst_contract.cancel_fee_config(&context.root), lst::ERR_FEE_CONFIG_ALREADY_EFFECTIVE);
+    check!(context.lst_contract.apply_fee_config(&context.alice));
+
+    // beneficiaries are included in the hard max
+    check!(context.lst_contract.set_timelock_delay(&context.root, "SetBeneficiary", 0));
+    check!(context.lst_contract.set_beneficiary(&context.root, context.manager.id(), 2300), lst::ERR_FEE_TOO_HIGH);
+    check!(context.lst_contract.set_beneficiary(&context.root, context.manager.id(), 2200));
+    assert_eq!(context.lst_contract.get_reward_fee_fraction().await.unwrap().numerator, 3000);
+}
+
+#[tokio::test]
+async fn test_fee_change_delay() {
+    let worker = near_workspaces::sandbox().await.unwrap();
+    let context = Context::new(&worker, None).await;
+
+    check!(context.lst_contract.set_fee_change_delay(&context.alice, 10), ERR_PERM);
+    check!(context.lst_contract.set_fee_change_delay(&context.root, lst::MIN_FEE_CHANGE_DELAY_EPOCHS - 1), lst::ERR_FEE_CHANGE_DELAY_TOO_SHORT);
+
+    // the new delay waits for the current one
+    let epoch = context.lst_contract.read_epoch_height().await.unwrap();
+    check!(context.lst_contract.set_fee_change_delay(&context.root, 10));
+    let fees = context.lst_contract.get_fees().await.unwrap();
+    assert_eq!(fees.change_delay_epochs, 4);
+    assert_eq!(fees.pending_change_delay.unwrap().effective_epoch, epoch + 4);
+    let config = json!({
+        "protocol_fee": { "account_id": context.manager.id(), "bps": 500 },
+        "deposit_fee_bps": 0,
+        "instant_exit_fee_bps": 0,
+    });
+    check!(context.lst_contract.announce_fee_config(&context.root, config.clone()));
+    assert_eq!(context.lst_contract.get_fees().await.unwrap().pending.unwrap().effective_epoch, epoch + 4);
+
+    check!(context.lst_contract.set_epoch_height(&context.root, epoch + 4));
+    let fees = context.lst_contract.get_fees().await.unwrap();
+    assert_eq!(fees.change_delay_epochs, 10);
+    assert!(fees.pending_change_delay.is_none());
+    check!(context.lst_contract.announce_fee_config(&context.root, config));
+    assert_eq!(context.lst_contract.get_fees().await.unwrap().pending.unwrap().effective_epoch, epoch + 14);
+}
+
+#[tokio::test]
+async fn test_deposit_and_instant_exit_fees() {
+    let worker = near_workspaces::sandbox().await.unwrap();
+    let (context, _) = Context::with_validators(&worker, &[10]).await;
+
+    let epoch = context.lst_contract.read_epoch_height().await.unwrap();
+    check!(context.lst_contract.announce_fee_config(&context.root, json!({
+        "treasury_fee": { "account_id": context.bob.id(), "bps": 0 },
+        "deposit_fee_bps": 50,
+        "instant_exit_fee_bps": 100,
+    })));
+    check!(context.lst_contract.set_epoch_height(&context.root, epoch + 4));
+
+    // 0.5% of the deposit is paid to the treasury
+    check!(context.lst_contract.storage_deposit(&context.alice, None, FT_STORAGE_DEPOSIT));
+    check!(context.lst_contract.deposit_and_stake(&context.alice, 100));
+    assert_eq!(
+        context.lst_contract.ft_balance_of(context.alice.id()).await.unwrap().0,
+        NearToken::from_millinear(99_500).as_yoctonear()
+    );
+    assert_eq!(
+        context.lst_contract.ft_balance_of(context.bob.id()).await.unwrap().0,
+        NearToken::from_millinear(500).as_yoctonear()
+    );
+    assert_eq!(
+        context.lst_contract.get_total_staked_balance().await.unwrap().0,
+        NearToken::from_near(110).as_yoctonear()
+    );
+
+    // the unstake is matched against the deposit and pays 1% to the treasury
+    check!(context.lst_contract.unstake(&context.alice, 10));
+    assert_eq!(
+        context.lst_contract.get_account_unstaked_balance(context.alice.id()).await.unwrap().0,
+        NearToken::from_millinear(9_900).as_yoctonear()
+    );
+    assert_eq!(
+        context.lst_contract.ft_balance_of(context.bob.id()).await.unwrap().0,
+        NearToken::from_millinear(600).as_yoctonear()
+    );
+    assert_eq!(context.lst_contract.ft_price().await.unwrap().0, 10u128.pow(24));
+
+    // nothing is left to match after the epoch cleanup, so no fee is paid
+    context.op_epoch_stake_all().await;
+    check!(context.lst_contract.unstake(&context.alice, 10));
+    assert_eq!(
+        context.lst_contract.get_account_unstaked_balance(context.alice.id()).await.unwrap().0,
+        NearToken::from_millinear(19_900).as_yoctonear()
+    );
+    assert_eq!(
+        context.lst_contract.ft_balance_of(context.bob.id()).await.unwrap().0,
+        NearToken::from_millinear(600).as_yoctonear()
+    );
+}
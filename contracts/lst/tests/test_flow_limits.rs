@@ -0,0 +1,111 @@
+mod setup;
+use setup::*;
+
+const ERR_PERM: &str = "Smart contract panicked: Insufficient permissions for method";
+
+fn near(amount: u128) -> u128 {
+    NearToken::from_near(amount).as_yoctonear()
+}
+
+#[tokio::test]
+async fn test_deposit_limits() {
+    let worker = near_workspaces::sandbox().await.unwrap();
+    let context = Context::new(&worker, None).await;
+
+    let config = json!({
+        "tvl_cap": near(100).to_string(),
+        "epoch_deposit_limit": near(30).to_string(),
+    });
+    check!(context.lst_contract.set_flow_limits(&context.alice, config.clone()), ERR_PERM);
+    check!(context.lst_contract.set_flow_limits(&context.root, json!({
+        "epoch_deposit_limit": "0",
+    })), lst::ERR_INVALID_FLOW_LIMIT);
+    check!(context.lst_contract.set_flow_limits(&context.root, config));
+
+    let limits = context.lst_contract.get_flow_limits().await.unwrap();
+    assert_eq!(limits.tvl_headroom.unwrap().0, near(90));
+    assert_eq!(limits.deposit_headroom.unwrap().0, near(30));
+    assert!(limits.unstake_headroom.is_none());
+
+    check!(context.lst_contract.storage_deposit(&context.alice, None, FT_STORAGE_DEPOSIT));
+    check!(context.lst_contract.deposit_and_stake(&context.alice, 40), lst::ERR_EPOCH_DEPOSIT_LIMIT_EXCEEDED);
+    check!(context.lst_contract.deposit_and_stake(&context.alice, 30));
+    check!(context.lst_contract.deposit_and_stake(&context.alice, 1), lst::ERR_EPOCH_DEPOSIT_LIMIT_EXCEEDED);
+    let limits = context.lst_contract.get_flow_limits().await.unwrap();
+    assert_eq!(limits.epoch_deposited.0, near(30));
+    assert_eq!(limits.deposit_headroom.unwrap().0, 0);
+
+    // the deposit limit resets every epoch, the TVL cap does not
+    context.epoch_height_fast_forward(Some(1)).await;
+    assert_eq!(context.lst_contract.get_flow_limits().await.unwrap().deposit_headroom.unwrap().0, near(30));
+    check!(context.lst_contract.deposit_and_stake(&context.alice, 30));
+    context.epoch_height_fast_forward(Some(1)).await;
+    check!(context.lst_contract.deposit_and_stake(&context.alice, 25));
+    context.epoch_height_fast_forward(Some(1)).await;
+    check!(context.lst_contract.deposit_and_stake(&context.alice, 10), lst::ERR_TVL_CAP_EXCEEDED);
+    check!(context.lst_contract.deposit_and_stake(&context.alice, 5));
+    assert_eq!(context.lst_contract.get_flow_limits().await.unwrap().tvl_headroom.unwrap().0, 0);
+}
+
+#[tokio::test]
+async fn test_unstake_limit_defers_excess() {
+    let worker = near_workspaces::sandbox().await.unwrap();
+    let context = Context::new(&worker, None).await;
+
+    context.register_and_stake(50).await;
+    context.epoch_height_fast_forward(Some(1)).await;
+
+    check!(context.lst_contract.set_flow_limits(&context.root, json!({
+        "epoch_unstake_limit": near(20).to_string(),
+    })));
+
+    // the excess is deferred instead of failing
+    check!(context.lst_contract.unstake(&context.alice, 30));
+    let limits = context.lst_contract.get_flow_limits().await.unwrap();
+    assert_eq!(limits.epoch_unstaked.0, near(20));
+    assert_eq!(limits.unstake_headroom.unwrap().0, 0);
+    assert_eq!(limits.deferred_unstake_amount.0, near(10));
+
+    // deferred unstakes take the headroom of the next epoch first
+    context.epoch_height_fast_forward(Some(1)).await;
+    assert_eq!(context.lst_contract.get_flow_limits().await.unwrap().unstake_headroom.unwrap().0, near(10));
+    check!(context.lst_contract.unstake(&context.alice, 15));
+    let limits = context.lst_contract.get_flow_limits().await.unwrap();
+    assert_eq!(limits.epoch_unstaked.0, near(20));
+    assert_eq!(limits.deferred_unstake_amount.0, near(5));
+}
+
+#[tokio::test]
+async fn test_deferred_unstake_released_per_request() {
+    let worker = near_workspaces::sandbox().await.unwrap();
+    let context = Context::new(&worker, None).await;
+
+    context.register_and_stake(50).await;
+    check!(context.lst_contract.deposit_and_stake(&context.bob, 50));
+    context.epoch_height_fast_forward(Some(1)).await;
+    // settle the deposits first so that the unstakes are not matched
+    check!(context.lst_contract.epoch_stake(&context.root));
+
+    check!(context.lst_contract.set_flow_limits(&context.root, json!({
+        "epoch_unstake_limit": near(20).to_string(),
+    })));
+    check!(context.lst_contract.unstake(&context.alice, 30));
+    check!(context.lst_contract.unstake(&context.bob, 20));
+    assert_eq!(context.lst_contract.get_flow_limits().await.unwrap().deferred_unstake_amount.0, near(30));
+
+    // the deferred part of alice is released first
+    context.epoch_height_fast_forward(Some(1)).await;
+    check!(context.lst_contract.epoch_stake(&context.root));
+    assert_eq!(context.lst_contract.get_flow_limits().await.unwrap().deferred_unstake_amount.0, near(10));
+    assert!(context.lst_contract.get_account_details(context.alice.id()).await.unwrap().can_withdraw);
+    assert!(!context.lst_contract.get_account_details(context.bob.id()).await.unwrap().can_withdraw);
+    check!(context.lst_contract.withdraw(&context.bob, 20), lst::ERR_UNSTAKED_BALANCE_NOT_AVAILABLE);
+    check!(context.lst_contract.withdraw(&context.alice, 30));
+
+    // bob waits for the rest of his request
+    context.epoch_height_fast_forward(Some(1)).await;
+    check!(context.lst_contract.epoch_stake(&context.root));
+    assert_eq!(context.lst_contract.get_flow_limits().await.unwrap().deferred_unstake_amount.0, 0);
+    assert!(context.lst_contract.get_account_details(context.bob.id()).await.unwrap().can_withdraw);
+    check!(context.lst_contract.withdraw(&context.bob, 20));
+}
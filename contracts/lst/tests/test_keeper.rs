@@ -0,0 +1,63 @@
+mod setup;
+use setup::*;
+
+const ERR_PERM: &str = "Smart contract panicked: Insufficient permissions for method";
+
+#[tokio::test]
+async fn test_keeper_rewards() {
+    let worker = near_workspaces::sandbox().await.unwrap();
+    let (context, validators) = Context::with_validators(&worker, &[10, 10]).await;
+    let (v1, v2) = (&validators[0], &validators[1]);
+    context.register_and_stake(50).await;
+    context.op_epoch_stake_all().await;
+
+    let reward_per_action = NearToken::from_millinear(10).as_yoctonear();
+    let max_reward_per_epoch = NearToken::from_millinear(15).as_yoctonear();
+    check!(context.lst_contract.set_keeper_reward_config(&context.alice, 500, reward_per_action, max_reward_per_epoch), ERR_PERM);
+    check!(context.lst_contract.set_keeper_reward_config(&context.root, 501, reward_per_action, max_reward_per_epoch), lst::ERR_KEEPER_REWARD_BPS_TOO_HIGH);
+    check!(context.lst_contract.set_keeper_reward_config(&context.root, 500, reward_per_action, max_reward_per_epoch));
+
+    // generate rewards
+    check!(v1.add_reward(context.lst_contract.0.as_account(), NearToken::from_near(2).as_yoctonear()));
+    check!(v2.add_reward(context.lst_contract.0.as_account(), NearToken::from_near(2).as_yoctonear()));
+    check!(context.lst_contract.set_epoch_height(&context.root, 11));
+
+    // a failed action is not paid
+    check!(v1.set_panic(&context.root, true));
+    check!(context.lst_contract.epoch_update_rewards(&context.bob, v1.0.id()));
+    assert_eq!(context.lst_contract.get_keeper_earnings(context.bob.id()).await.unwrap().0, 0);
+    assert_eq!(context.lst_contract.get_keeper_reward_info().await.unwrap().reward_pool_shares.0, 0);
+    check!(v1.set_panic(&context.root, false));
+
+    // the keeper is paid once the action succeeds, out of the rewards it brought in
+    check!(context.lst_contract.epoch_update_rewards(&context.bob, v1.0.id()));
+    let earnings = context.lst_contract.get_keeper_earnings(context.bob.id()).await.unwrap().0;
+    assert!(earnings > 0);
+    assert_eq!(context.lst_contract.ft_balance_of(context.bob.id()).await.unwrap().0, earnings);
+    let info = context.lst_contract.get_keeper_reward_info().await.unwrap();
+    assert!(info.epoch_paid_amount.0 <= reward_per_action);
+    assert!(info.epoch_paid_amount.0 > reward_per_action - 10);
+    let pool_shares = info.reward_pool_shares.0;
+    assert!(pool_shares > 0);
+
+    check!(context.lst_contract.epoch_update_rewards(&context.bob, v2.0.id()));
+    let new_earnings = context.lst_contract.get_keeper_earnings(context.bob.id()).await.unwrap().0;
+    assert!(new_earnings > earnings);
+    let info = context.lst_contract.get_keeper_reward_info().await.unwrap();
+    assert!(info.reward_pool_shares.0 > pool_shares - (new_earnings - earnings));
+    let earnings = new_earnings;
+
+    // refreshing the same validator again in this epoch is not paid
+    check!(context.lst_contract.epoch_update_rewards(&context.bob, v2.0.id()));
+    assert_eq!(context.lst_contract.get_keeper_earnings(context.bob.id()).await.unwrap().0, earnings);
+
+    // payments in an epoch are capped
+    check!(v1.add_reward(context.lst_contract.0.as_account(), NearToken::from_near(2).as_yoctonear()));
+    check!(v2.add_reward(context.lst_contract.0.as_account(), NearToken::from_near(2).as_yoctonear()));
+    check!(context.lst_contract.set_epoch_height(&context.root, 12));
+    check!(context.lst_contract.epoch_run(&context.bob, None));
+    let info = context.lst_contract.get_keeper_reward_info().await.unwrap();
+    assert!(info.epoch_paid_amount.0 <= max_reward_per_epoch);
+    assert!(info.epoch_paid_amount.0 > max_reward_per_epoch - 10);
+    assert!(context.lst_contract.get_keeper_earnings(context.bob.id()).await.unwrap().0 > earnings);
+}
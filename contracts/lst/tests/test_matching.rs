@@ -0,0 +1,64 @@
+mod setup;
+use setup::*;
+
+#[tokio::test]
+async fn test_deposit_funds_queued_unstake() {
+    let worker = near_workspaces::sandbox().await.unwrap();
+    let (context, validators) = Context::with_validators(&worker, &[10]).await;
+    let v1 = &validators[0];
+    context.register_and_stake(50).await;
+    context.op_epoch_stake_all().await;
+
+    // fast-forward
+    check!(context.lst_contract.set_epoch_height(&context.root, 11));
+
+    // nothing deposited in this epoch yet, the unstake request is queued
+    check!(context.lst_contract.unstake(&context.alice, 10));
+    let details = context.lst_contract.get_account_details(context.alice.id()).await.unwrap();
+    assert_eq!(details.matched_unstaked_balance.0, 0);
+    check!(context.lst_contract.withdraw(&context.alice, 1), lst::ERR_UNSTAKED_BALANCE_NOT_AVAILABLE);
+
+    // bob's deposit funds part of alice's request
+    check!(context.lst_contract.deposit_and_stake(&context.bob, 6));
+    let details = context.lst_contract.get_account_details(context.alice.id()).await.unwrap();
+    assert_eq!(details.matched_unstaked_balance.0, NearToken::from_near(6).as_yoctonear());
+
+    // matched amounts never reach validators
+    let summary = context.lst_contract.get_summary().await.unwrap();
+    assert_eq!(summary.epoch_requested_stake_amount.0, 0);
+    assert_eq!(summary.epoch_requested_unstake_amount.0, NearToken::from_near(4).as_yoctonear());
+    assert_eq!(summary.withdrawal_reserve.0, NearToken::from_near(6).as_yoctonear());
+
+    // the matched part is withdrawable right away, the rest still waits
+    check!(context.lst_contract.withdraw(&context.alice, 6));
+    check!(context.lst_contract.withdraw(&context.alice, 4), lst::ERR_UNSTAKED_BALANCE_NOT_AVAILABLE);
+    assert_eq!(context.lst_contract.get_summary().await.unwrap().withdrawal_reserve.0, 0);
+
+    // only the unmatched part is unstaked from validators
+    context.op_epoch_unstake_all().await;
+    context.check_validator_amount(&v1, NearToken::from_near(56).as_yoctonear(), NearToken::from_near(4).as_yoctonear(), None, None).await;
+}
+
+#[tokio::test]
+async fn test_unstake_matches_epoch_deposits() {
+    let worker = near_workspaces::sandbox().await.unwrap();
+    let (context, validators) = Context::with_validators(&worker, &[10]).await;
+    let v1 = &validators[0];
+    context.register_and_stake(50).await;
+    context.op_epoch_stake_all().await;
+
+    // fast-forward
+    check!(context.lst_contract.set_epoch_height(&context.root, 11));
+
+    // bob deposits first, then alice unstakes less than that
+    check!(context.lst_contract.deposit_and_stake(&context.bob, 20));
+    check!(context.lst_contract.unstake(&context.alice, 5));
+
+    let details = context.lst_contract.get_account_details(context.alice.id()).await.unwrap();
+    assert_eq!(details.matched_unstaked_balance.0, NearToken::from_near(5).as_yoctonear());
+    check!(context.lst_contract.withdraw(&context.alice, 5));
+
+    // only the unmatched deposit is staked on validators
+    context.op_epoch_stake_all().await;
+    context.check_validator_amount(&v1, NearToken::from_near(75).as_yoctonear(), 0, None, None).await;
+}
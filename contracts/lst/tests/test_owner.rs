@@ -0,0 +1,97 @@
+mod setup;
+use setup::*;
+
+const ERR_PERM: &str = "Smart contract panicked: Insufficient permissions for method";
+
+#[tokio::test]
+async fn test_owner_transfer() {
+    let worker = near_workspaces::sandbox().await.unwrap();
+    let context = Context::new(&worker, None).await;
+    check!(context.lst_contract.set_timelock_delay(&context.root, "SetBeneficiary", 0));
+
+    check!(context.lst_contract.propose_owner(&context.alice, context.alice.id(), None), lst::ERR_NOT_OWNER);
+    check!(context.lst_contract.propose_owner(&context.root, context.root.id(), None), lst::ERR_ALREADY_OWNER);
+    check!(context.lst_contract.accept_owner(&context.alice), lst::ERR_NO_OWNER_PROPOSAL);
+
+    // the expiry is bounded, and an expired proposal can't be accepted
+    check!(context.lst_contract.propose_owner(&context.root, context.alice.id(), Some(0)), lst::ERR_INVALID_OWNER_PROPOSAL_EXPIRY);
+    check!(
+        context.lst_contract.propose_owner(&context.root, context.alice.id(), Some(lst::MAX_OWNER_PROPOSAL_EXPIRY_SEC + 1)),
+        lst::ERR_INVALID_OWNER_PROPOSAL_EXPIRY
+    );
+    check!(context.lst_contract.propose_owner(&context.root, context.alice.id(), Some(lst::MIN_OWNER_PROPOSAL_EXPIRY_SEC)));
+    worker.fast_forward(1000).await.unwrap();
+    check!(context.lst_contract.accept_owner(&context.alice), lst::ERR_OWNER_PROPOSAL_EXPIRED);
+
+    // a new proposal replaces the old one, and can be cancelled
+    check!(context.lst_contract.propose_owner(&context.root, context.bob.id(), None));
+    assert_eq!(&context.lst_contract.get_owner_proposal().await.unwrap().unwrap().new_owner_id, context.bob.id());
+    check!(context.lst_contract.cancel_owner_proposal(&context.root));
+    assert!(context.lst_contract.get_owner_proposal().await.unwrap().is_none());
+    check!(context.lst_contract.cancel_owner_proposal(&context.root), lst::ERR_NO_OWNER_PROPOSAL);
+
+    check!(context.lst_contract.propose_owner(&context.root, context.alice.id(), None));
+    check!(context.lst_contract.accept_owner(&context.bob), lst::ERR_NOT_PROPOSED_OWNER);
+    check!(context.lst_contract.accept_owner(&context.alice));
+    assert_eq!(&context.lst_contract.get_owner().await.unwrap(), context.alice.id());
+    assert!(context.lst_contract.get_owner_proposal().await.unwrap().is_none());
+
+    // the owner roles move with the ownership
+    for role in ["DAO", "OpManager", "BeneficiaryManager", "WhitelistManager"] {
+        let grantees = context.lst_contract.acl_get_grantees(role.to_string()).await.unwrap();
+        assert_eq!(grantees, vec![context.alice.id().clone()]);
+    }
+    assert!(context.lst_contract.acl_get_super_admins().await.unwrap().contains(context.alice.id()));
+    check!(context.lst_contract.set_beneficiary(&context.root, context.manager.id(), 1000), ERR_PERM);
+    check!(context.lst_contract.set_beneficiary(&context.alice, context.manager.id(), 1000));
+    check!(context.lst_contract.propose_owner(&context.root, context.root.id(), None), lst::ERR_NOT_OWNER);
+}
+
+#[tokio::test]
+async fn test_owner_method_roles() {
+    let worker = near_workspaces::sandbox().await.unwrap();
+    let context = Context::new(&worker, None).await;
+    check!(context.lst_contract.set_timelock_delay(&context.root, "SetBeneficiary", 0));
+
+    check!(context.lst_contract.set_beneficiary(&context.bob, context.manager.id(), 1000), ERR_PERM);
+    check!(context.lst_contract.set_whitelist_contract_id(&context.bob, context.manager.id()), ERR_PERM);
+
+    check!(context.lst_contract.acl_grant_role(&context.root, "BeneficiaryManager".to_string(), context.bob.id()));
+    check!(context.lst_contract.set_beneficiary(&context.bob, context.manager.id(), 1000));
+    check!(context.lst_contract.set_whitelist_contract_id(&context.bob, context.manager.id()), ERR_PERM);
+
+    check!(context.lst_contract.acl_grant_role(&context.root, "WhitelistManager".to_string(), context.bob.id()));
+    check!(context.lst_contract.set_whitelist_contract_id(&context.bob, context.manager.id()));
+}
+
+#[tokio::test]
+async fn test_contract_info() {
+    let worker = near_workspaces::sandbox().await.unwrap();
+    let context = Context::new(&worker, None).await;
+
+    check!(context.lst_contract.acl_grant_role(&context.root, "Accountant".to_string(), context.bob.id()));
+    check!(context.lst_contract.pa_pause_feature(&context.root, "deposits".to_string()));
+
+    let info = context.lst_contract.get_contract_info().await.unwrap();
+    assert_eq!(info.version, context.lst_contract.get_version().await.unwrap());
+    assert_eq!(&info.contract_id, context.lst_contract.0.id());
+    assert_eq!(&info.owner_id, context.root.id());
+    assert_eq!(info.rnear_contract_id.as_str(), "lst.rhealab.near");
+    assert_eq!(info.wnear_contract_id.as_str(), "wrap.near");
+    assert_eq!(info.burrow_contract_id.as_str(), "contract.main.burrow.near");
+    assert_eq!(info.whitelist_account_id.as_ref(), Some(context.mock_whitelist.0.id()));
+    assert!(info.pause_status.paused_keys.contains(&"deposits".to_string()));
+    assert_eq!(info.super_admins, vec![context.root.id().clone()]);
+
+    let grantees = |role: &str| {
+        info.roles
+            .iter()
+            .find(|grantees| grantees.role == role)
+            .unwrap()
+            .account_ids
+            .clone()
+    };
+    assert_eq!(grantees("DAO"), vec![context.root.id().clone()]);
+    assert_eq!(grantees("Accountant"), vec![context.bob.id().clone()]);
+    assert!(grantees("Strategist").is_empty());
+}
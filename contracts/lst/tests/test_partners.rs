@@ -0,0 +1,51 @@
+mod setup;
+use setup::*;
+
+const ERR_PERM: &str = "Smart contract panicked: Insufficient permissions for method";
+
+#[tokio::test]
+async fn test_partner_base_stake() {
+    let worker = near_workspaces::sandbox().await.unwrap();
+    let (context, validators) = Context::with_validators(&worker, &[10, 10]).await;
+    let (v1, v2) = (&validators[0], &validators[1]);
+
+    check!(context.lst_contract.register_partner(&context.alice, context.bob.id(), v1.0.id()), ERR_PERM);
+    check!(context.lst_contract.register_partner(&context.root, context.bob.id(), context.alice.id()), lst::ERR_VALIDATOR_NOT_EXIST);
+    check!(context.lst_contract.register_partner(&context.root, context.bob.id(), v1.0.id()));
+    check!(context.lst_contract.register_partner(&context.root, context.bob.id(), v2.0.id()), lst::ERR_PARTNER_ALREADY_EXIST);
+
+    check!(context.lst_contract.storage_deposit(&context.alice, None, FT_STORAGE_DEPOSIT));
+    check!(context.lst_contract.storage_deposit(&context.bob, None, FT_STORAGE_DEPOSIT));
+
+    // deposits through the partner raise the base stake of its validator
+    check!(context.lst_contract.deposit_and_stake(&context.bob, 20));
+    check!(context.lst_contract.deposit_and_stake(&context.alice, 30));
+    let partner = context.lst_contract.get_partner(context.bob.id()).await.unwrap().unwrap();
+    assert_eq!(&partner.validator_id, v1.0.id());
+    assert_eq!(partner.attributed_amount.0, NearToken::from_near(20).as_yoctonear());
+    let validator = context.lst_contract.get_validator(v1.0.id()).await.unwrap().unwrap();
+    assert_eq!(validator.base_stake_amount.0, NearToken::from_near(20).as_yoctonear());
+
+    context.op_epoch_stake_all().await;
+    context.check_validator_amount(&v1, NearToken::from_near(40).as_yoctonear(), 0, None, None).await;
+    context.check_validator_amount(&v2, NearToken::from_near(20).as_yoctonear(), 0, None, None).await;
+
+    // unstakes through the partner lower it
+    check!(context.lst_contract.unstake(&context.bob, 5));
+    let partner = context.lst_contract.get_partner(context.bob.id()).await.unwrap().unwrap();
+    assert_eq!(partner.attributed_amount.0, NearToken::from_near(15).as_yoctonear());
+    let validator = context.lst_contract.get_validator(v1.0.id()).await.unwrap().unwrap();
+    assert_eq!(validator.base_stake_amount.0, NearToken::from_near(15).as_yoctonear());
+
+    // other accounts don't affect the partner
+    check!(context.lst_contract.unstake(&context.alice, 5));
+    let validator = context.lst_contract.get_validator(v1.0.id()).await.unwrap().unwrap();
+    assert_eq!(validator.base_stake_amount.0, NearToken::from_near(15).as_yoctonear());
+
+    // deregistering releases the attributed base stake
+    check!(context.lst_contract.deregister_partner(&context.root, context.bob.id()));
+    assert!(context.lst_contract.get_partner(context.bob.id()).await.unwrap().is_none());
+    let validator = context.lst_contract.get_validator(v1.0.id()).await.unwrap().unwrap();
+    assert_eq!(validator.base_stake_amount.0, 0);
+    check!(context.lst_contract.deregister_partner(&context.root, context.bob.id()), lst::ERR_PARTNER_NOT_EXIST);
+}
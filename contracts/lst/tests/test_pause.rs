@@ -0,0 +1,103 @@
+mod setup;
+use setup::*;
+
+const ERR_PAUSED: &str = "Pausable: Method is paused";
+const ERR_PERM: &str = "Smart contract panicked: Insufficient permissions for method";
+
+#[tokio::test]
+async fn test_pause_scopes() {
+    let worker = near_workspaces::sandbox().await.unwrap();
+    let (context, validators) = Context::with_validators(&worker, &[10]).await;
+    let v1 = &validators[0];
+    context.register_and_stake(50).await;
+    assert!(!context.lst_contract.is_paused().await.unwrap());
+
+    // new deposits are frozen while exits stay open
+    check!(context.lst_contract.pa_pause_feature(&context.root, "deposits".to_string()));
+    let status = context.lst_contract.get_pause_status().await.unwrap();
+    assert!(!status.all);
+    assert_eq!(status.paused_keys, vec!["deposits".to_string()]);
+    for scope in status.scopes {
+        assert_eq!(scope.paused, scope.key == "deposits");
+    }
+    assert!(!context.lst_contract.is_paused().await.unwrap());
+    check!(context.lst_contract.deposit_and_stake(&context.alice, 10), ERR_PAUSED);
+    check!(context.lst_contract.unstake(&context.alice, 10));
+    check!(context.lst_contract.withdraw_all(&context.alice));
+
+    // epoch actions
+    check!(context.lst_contract.pa_pause_feature(&context.root, "epoch_actions".to_string()));
+    check!(context.lst_contract.epoch_run(&context.root, None), lst::ERR_SCOPE_PAUSED);
+    assert_eq!(
+        context.lst_contract.preview_epoch_stake().await.unwrap().reason.unwrap(),
+        "epoch_actions is paused"
+    );
+    check!(context.lst_contract.pa_unpause_feature(&context.root, "epoch_actions".to_string()));
+    context.op_epoch_stake_all().await;
+
+    // validator admin
+    check!(context.lst_contract.pa_pause_feature(&context.root, "validator_admin".to_string()));
+    check!(context.lst_contract.update_weight(&context.root, v1.0.id(), 20), lst::ERR_SCOPE_PAUSED);
+    check!(context.lst_contract.pa_unpause_feature(&context.root, "validator_admin".to_string()));
+    check!(context.lst_contract.update_weight(&context.root, v1.0.id(), 20));
+
+    check!(context.lst_contract.pa_unpause_feature(&context.root, "deposits".to_string()));
+    check!(context.lst_contract.deposit_and_stake(&context.alice, 10));
+    assert!(!context.lst_contract.is_paused().await.unwrap());
+    assert!(context.lst_contract.get_pause_status().await.unwrap().paused_keys.is_empty());
+}
+
+#[tokio::test]
+async fn test_exit_only_mode() {
+    let worker = near_workspaces::sandbox().await.unwrap();
+    let (context, _) = Context::with_validators(&worker, &[10]).await;
+    context.register_and_stake(50).await;
+    check!(context.lst_contract.ft_transfer(&context.alice, context.bob.id(), NearToken::from_near(10).as_yoctonear()));
+    context.op_epoch_stake_all().await;
+
+    // alice's unstake is released in a later epoch, bob's is still pending
+    check!(context.lst_contract.unstake(&context.alice, 5));
+    context.epoch_height_fast_forward(None).await;
+    context.op_epoch_unstake_all().await;
+    check!(context.lst_contract.unstake(&context.bob, 3));
+
+    // pause in the middle of the epoch
+    check!(context.lst_contract.pa_pause_feature(&context.root, "ALL".to_string()));
+    check!(context.lst_contract.withdraw(&context.alice, 5), lst::ERR_SCOPE_PAUSED);
+
+    check!(context.lst_contract.enable_exit_only_mode(&context.manager), ERR_PERM);
+    check!(context.lst_contract.enable_exit_only_mode(&context.root));
+    check!(context.lst_contract.enable_exit_only_mode(&context.root), lst::ERR_EXIT_ONLY_MODE_UNCHANGED);
+    let status = context.lst_contract.get_pause_status().await.unwrap();
+    assert!(status.all && status.exit_only);
+
+    // an explicit pause of withdrawals still holds
+    check!(context.lst_contract.pa_pause_feature(&context.root, "withdrawals".to_string()));
+    check!(context.lst_contract.withdraw(&context.alice, 5), lst::ERR_SCOPE_PAUSED);
+    check!(context.lst_contract.pa_unpause_feature(&context.root, "withdrawals".to_string()));
+    assert!(context.lst_contract.get_pause_status().await.unwrap().all);
+
+    // only matured NEAR can be withdrawn, and LST can still be transferred
+    check!(context.lst_contract.withdraw(&context.alice, 5));
+    check!(context.lst_contract.withdraw_all(&context.bob), lst::ERR_UNSTAKED_BALANCE_NOT_AVAILABLE);
+    check!(context.lst_contract.ft_transfer(&context.alice, context.bob.id(), NearToken::from_near(1).as_yoctonear()));
+
+    // everything else stays frozen
+    check!(context.lst_contract.deposit_and_stake(&context.alice, 1), ERR_PAUSED);
+    check!(context.lst_contract.unstake(&context.alice, 1), ERR_PAUSED);
+    check!(context.lst_contract.epoch_run(&context.root, None), ERR_PAUSED);
+
+    // unpause in the middle of the epoch
+    check!(context.lst_contract.pa_unpause_feature(&context.root, "ALL".to_string()));
+    check!(context.lst_contract.deposit_and_stake(&context.alice, 1));
+    check!(context.lst_contract.withdraw_all(&context.bob), lst::ERR_UNSTAKED_BALANCE_NOT_AVAILABLE);
+
+    check!(context.lst_contract.disable_exit_only_mode(&context.root));
+    assert!(!context.lst_contract.get_pause_status().await.unwrap().exit_only);
+    check!(context.lst_contract.pa_pause_feature(&context.root, "withdrawals".to_string()));
+    context.epoch_height_fast_forward(None).await;
+    context.op_epoch_unstake_all().await;
+    check!(context.lst_contract.withdraw_all(&context.bob), lst::ERR_SCOPE_PAUSED);
+    check!(context.lst_contract.pa_unpause_feature(&context.root, "withdrawals".to_string()));
+    check!(context.lst_contract.withdraw_all(&context.bob));
+}
@@ -0,0 +1,42 @@
+mod setup;
+use setup::*;
+
+const ONE_NEAR: u128 = 10u128.pow(24);
+
+#[tokio::test]
+async fn test_price_history() {
+    let worker = near_workspaces::sandbox().await.unwrap();
+    let (context, validators) = Context::with_validators(&worker, &[10]).await;
+    let v1 = &validators[0];
+    context.register_and_stake(90).await;
+    context.op_epoch_stake_all().await;
+
+    // the epoch cleanup records the first sample
+    let history = context.lst_contract.get_price_history(None).await.unwrap();
+    let num_samples = history.len();
+    assert!(num_samples > 0);
+    assert_eq!(history[0].ft_price.0, ONE_NEAR);
+    assert_eq!(history[0].total_staked.0, NearToken::from_near(100).as_yoctonear());
+    assert_eq!(context.lst_contract.get_twap(3600).await.unwrap().0, ONE_NEAR);
+
+    // each reward update records a sample
+    worker.fast_forward(100).await.unwrap();
+    check!(v1.add_reward(context.lst_contract.0.as_account(), NearToken::from_near(10).as_yoctonear()));
+    check!(context.lst_contract.epoch_update_rewards(&context.root, v1.0.id()));
+    let history = context.lst_contract.get_price_history(None).await.unwrap();
+    assert_eq!(history.len(), num_samples + 1);
+    assert_eq!(history[0].ft_price, context.lst_contract.ft_price().await.unwrap());
+    assert_eq!(history[0].ft_price.0, ONE_NEAR * 11 / 10);
+    assert!(history[0].timestamp.0 > history[1].timestamp.0);
+    assert_eq!(context.lst_contract.get_price_history(Some(1)).await.unwrap().len(), 1);
+
+    // the twap is between the old and the new price
+    worker.fast_forward(100).await.unwrap();
+    let twap = context.lst_contract.get_twap(7 * 24 * 3600).await.unwrap().0;
+    assert!(twap > ONE_NEAR && twap < ONE_NEAR * 11 / 10);
+
+    let apr = context.lst_contract.get_apr(None).await.unwrap().unwrap();
+    assert_eq!(apr.from.ft_price.0, ONE_NEAR);
+    assert_eq!(apr.to.ft_price.0, ONE_NEAR * 11 / 10);
+    assert!(apr.apr_bps > 0);
+}
@@ -0,0 +1,84 @@
+mod setup;
+use setup::*;
+
+const ERR_PERM: &str = "Smart contract panicked: Insufficient permissions for method";
+
+#[tokio::test]
+async fn test_redelegation() {
+    let worker = near_workspaces::sandbox().await.unwrap();
+    let (context, validators) = Context::with_validators(&worker, &[10, 10]).await;
+    let (v1, v2) = (&validators[0], &validators[1]);
+    context.register_and_stake(50).await;
+    context.op_epoch_stake_all().await;
+    context.check_validator_amount(&v1, NearToken::from_near(30).as_yoctonear(), 0, None, None).await;
+    context.check_validator_amount(&v2, NearToken::from_near(30).as_yoctonear(), 0, None, None).await;
+
+    // fast-forward
+    check!(context.lst_contract.set_epoch_height(&context.root, 11));
+
+    let amount = NearToken::from_near(10).as_yoctonear();
+    check!(context.lst_contract.redelegate(&context.alice, v1.0.id(), v2.0.id(), amount), ERR_PERM);
+    check!(context.lst_contract.redelegate(&context.root, v1.0.id(), v1.0.id(), amount), lst::ERR_SAME_REDELEGATION_VALIDATOR);
+
+    // unstaked from v1 right away
+    check!(context.lst_contract.redelegate(&context.root, v1.0.id(), v2.0.id(), amount));
+    let redelegation = context.lst_contract.get_redelegation(0).await.unwrap().unwrap();
+    assert!(matches!(redelegation.status, lst::RedelegationStatus::Unstaked));
+    context.check_validator_amount(&v1, NearToken::from_near(20).as_yoctonear(), amount, None, None).await;
+    check!(context.lst_contract.cancel_redelegation(&context.root, 0), lst::ERR_BAD_REDELEGATION_STATUS);
+
+    // v1 is locked, the next redelegation stays pending and can be cancelled
+    check!(context.lst_contract.redelegate(&context.root, v1.0.id(), v2.0.id(), amount));
+    let redelegation = context.lst_contract.get_redelegation(1).await.unwrap().unwrap();
+    assert!(matches!(redelegation.status, lst::RedelegationStatus::Pending));
+    check!(context.lst_contract.cancel_redelegation(&context.root, 1));
+    assert!(context.lst_contract.get_redelegation(1).await.unwrap().is_none());
+
+    // withdraw after the unlock
+    check!(context.lst_contract.redelegation_withdraw(&context.alice, 0), lst::ERR_VALIDATOR_WITHDRAW_WHEN_LOCKED);
+    check!(context.lst_contract.set_epoch_height(&context.root, 15));
+    check!(context.lst_contract.redelegation_withdraw(&context.alice, 0));
+    let redelegation = context.lst_contract.get_redelegation(0).await.unwrap().unwrap();
+    assert!(matches!(redelegation.status, lst::RedelegationStatus::Withdrawn));
+    let summary = context.lst_contract.get_summary().await.unwrap();
+    assert_eq!(summary.redelegation_in_transit_amount.0, amount);
+    context.check_validator_amount(&v1, NearToken::from_near(20).as_yoctonear(), 0, None, None).await;
+
+    // stake on v2
+    check!(context.lst_contract.redelegation_stake(&context.alice, 0));
+    assert!(context.lst_contract.get_redelegation(0).await.unwrap().is_none());
+    let summary = context.lst_contract.get_summary().await.unwrap();
+    assert_eq!(summary.redelegation_in_transit_amount.0, 0);
+    assert_eq!(summary.total_staked_near_amount.0, NearToken::from_near(60).as_yoctonear());
+    context.check_validator_amount(&v2, NearToken::from_near(40).as_yoctonear(), 0, None, None).await;
+}
+
+#[tokio::test]
+async fn test_redelegation_moves_target() {
+    let worker = near_workspaces::sandbox().await.unwrap();
+    let (context, validators) = Context::with_validators(&worker, &[10, 10]).await;
+    let (v1, v2) = (&validators[0], &validators[1]);
+    check!(context.lst_contract.update_base_stake_amounts(&context.root, vec![v1.0.id()], vec![NearToken::from_near(10).as_yoctonear()]));
+    context.register_and_stake(50).await;
+    context.op_epoch_stake_all().await;
+    context.check_validator_amount(&v1, NearToken::from_near(35).as_yoctonear(), 0, None, None).await;
+    context.check_validator_amount(&v2, NearToken::from_near(25).as_yoctonear(), 0, None, None).await;
+
+    check!(context.lst_contract.set_epoch_height(&context.root, 11));
+    let amount = NearToken::from_near(10).as_yoctonear();
+    check!(context.lst_contract.redelegate(&context.root, v1.0.id(), v2.0.id(), amount));
+    check!(context.lst_contract.set_epoch_height(&context.root, 15));
+    check!(context.lst_contract.redelegation_withdraw(&context.alice, 0));
+    check!(context.lst_contract.redelegation_stake(&context.alice, 0));
+
+    // the base stake follows the redelegated stake
+    context.check_validator_amount(&v1, NearToken::from_near(25).as_yoctonear(), 0, Some(0), Some(NearToken::from_near(25).as_yoctonear())).await;
+    context.check_validator_amount(&v2, NearToken::from_near(35).as_yoctonear(), 0, Some(amount), Some(NearToken::from_near(35).as_yoctonear())).await;
+
+    // the next unstake is not taken from the destination
+    check!(context.lst_contract.set_epoch_height(&context.root, 16));
+    check!(context.lst_contract.unstake(&context.alice, 10));
+    check!(context.lst_contract.epoch_run(&context.root, None));
+    context.check_validator_amount(&v1, NearToken::from_near(15).as_yoctonear(), amount, None, None).await;
+    context.check_validator_amount(&v2, NearToken::from_near(35).as_yoctonear(), 0, None, None).await;
+}
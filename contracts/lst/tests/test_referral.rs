@@ -0,0 +1,69 @@
+mod setup;
+use setup::*;
+
+#[tokio::test]
+async fn test_referral() {
+    let worker = near_workspaces::sandbox().await.unwrap();
+    let (context, validators) = Context::with_validators(&worker, &[10]).await;
+    let v1 = &validators[0];
+
+    // only registered accounts can be referrers
+    check!(context.lst_contract.register_referrer(&context.bob), lst::ERR_ACCOUNT_NOT_REGISTERED);
+    check!(context.lst_contract.storage_deposit(&context.bob, None, FT_STORAGE_DEPOSIT));
+    check!(context.lst_contract.register_referrer(&context.bob));
+    check!(context.lst_contract.register_referrer(&context.bob), lst::ERR_REFERRER_ALREADY_EXIST);
+
+    // an unknown referrer is ignored
+    check!(context.lst_contract.storage_deposit(&context.alice, None, FT_STORAGE_DEPOSIT));
+    check!(context.lst_contract.deposit_and_stake_with_referrer(&context.alice, 10, context.manager.id()));
+    assert!(context.lst_contract.get_referral(context.alice.id()).await.unwrap().is_none());
+
+    // only the first referral binds the account
+    check!(context.lst_contract.storage_deposit(&context.manager, None, FT_STORAGE_DEPOSIT));
+    check!(context.lst_contract.register_referrer(&context.manager));
+    check!(context.lst_contract.deposit_and_stake_with_referrer(&context.alice, 50, context.bob.id()));
+    check!(context.lst_contract.deposit_and_stake_with_referrer(&context.alice, 20, context.manager.id()));
+    assert_eq!(&context.lst_contract.get_referral(context.alice.id()).await.unwrap().unwrap(), context.bob.id());
+
+    let referrer = context.lst_contract.get_referrer(context.bob.id()).await.unwrap().unwrap();
+    assert_eq!(referrer.referred_accounts, 1);
+    assert_eq!(referrer.total_referred_amount.0, NearToken::from_near(70).as_yoctonear());
+    assert_eq!(referrer.active_shares.0, NearToken::from_near(80).as_yoctonear());
+    let referrer = context.lst_contract.get_referrer(context.manager.id()).await.unwrap().unwrap();
+    assert_eq!(referrer.referred_accounts, 0);
+    assert_eq!(referrer.active_shares.0, 0);
+
+    // active shares follow the balance of the referred account
+    check!(context.lst_contract.unstake(&context.alice, 30));
+    let referrer = context.lst_contract.get_referrer(context.bob.id()).await.unwrap().unwrap();
+    assert_eq!(referrer.active_shares.0, NearToken::from_near(50).as_yoctonear());
+    assert_eq!(referrer.total_referred_amount.0, NearToken::from_near(70).as_yoctonear());
+    context.op_epoch_stake_all().await;
+
+    // half of the 10% protocol fee goes to referrers
+    let epoch = context.lst_contract.read_epoch_height().await.unwrap();
+    check!(context.lst_contract.announce_fee_config(&context.root, json!({
+        "protocol_fee": { "account_id": context.manager.id(), "bps": 1000 },
+        "deposit_fee_bps": 0,
+        "instant_exit_fee_bps": 0,
+        "referral_share_bps": 5000,
+    })));
+    check!(context.lst_contract.set_epoch_height(&context.root, epoch + 4));
+
+    check!(context.lst_contract.claim_referral_rewards(&context.bob), lst::ERR_NOTHING_TO_CLAIM);
+    check!(v1.add_reward(context.lst_contract.0.as_account(), NearToken::from_near(2).as_yoctonear()));
+    check!(context.lst_contract.epoch_update_rewards(&context.root, v1.0.id()));
+
+    let referrer = context.lst_contract.get_referrer(context.bob.id()).await.unwrap().unwrap();
+    let protocol_shares = context.lst_contract.ft_balance_of(context.manager.id()).await.unwrap().0;
+    assert!(referrer.unclaimed_shares.0 > 0);
+    assert!(referrer.unclaimed_shares.0 + 10 >= protocol_shares);
+    assert!(referrer.unclaimed_shares.0 <= protocol_shares + 10);
+
+    check!(context.lst_contract.claim_referral_rewards(&context.bob));
+    assert_eq!(context.lst_contract.ft_balance_of(context.bob.id()).await.unwrap(), referrer.unclaimed_shares);
+    let referrer = context.lst_contract.get_referrer(context.bob.id()).await.unwrap().unwrap();
+    assert_eq!(referrer.unclaimed_shares.0, 0);
+    assert!(referrer.claimed_shares.0 > 0);
+    check!(context.lst_contract.claim_referral_rewards(&context.alice), lst::ERR_REFERRER_NOT_EXIST);
+}
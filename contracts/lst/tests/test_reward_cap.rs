@@ -0,0 +1,56 @@
+mod setup;
+use setup::*;
+
+const ERR_PERM: &str = "Smart contract panicked: Insufficient permissions for method";
+
+#[tokio::test]
+async fn test_reward_cap() {
+    let worker = near_workspaces::sandbox().await.unwrap();
+    let (context, validators) = Context::with_validators(&worker, &[10]).await;
+    let v1 = &validators[0];
+    context.register_and_stake(60).await;
+    context.op_epoch_stake_all().await;
+    check!(context.lst_contract.epoch_update_rewards(&context.root, v1.0.id()));
+
+    check!(context.lst_contract.set_max_reward_apr(&context.alice, Some(1000)), ERR_PERM);
+    check!(context.lst_contract.set_max_reward_apr(&context.root, Some(0)), lst::ERR_INVALID_MAX_REWARD_APR);
+    check!(context.lst_contract.set_max_reward_apr(&context.root, Some(1000)));
+    assert_eq!(context.lst_contract.get_reward_cap().await.unwrap().max_apr_bps, Some(1000));
+
+    // rewards above 10% APR for one epoch are quarantined
+    let epoch = context.lst_contract.read_epoch_height().await.unwrap();
+    check!(context.lst_contract.set_epoch_height(&context.root, epoch + 1));
+    let total_staked = context.lst_contract.get_total_staked_balance().await.unwrap().0;
+    check!(v1.add_reward(context.lst_contract.0.as_account(), NearToken::from_near(1).as_yoctonear()));
+    check!(context.lst_contract.epoch_update_rewards(&context.root, v1.0.id()));
+
+    let reward_cap = context.lst_contract.get_reward_cap().await.unwrap();
+    let quarantined = reward_cap.quarantined_rewards.get(v1.0.id()).unwrap().0;
+    assert_eq!(reward_cap.total_quarantined.0, quarantined);
+    assert!(quarantined > NearToken::from_millinear(980).as_yoctonear());
+    let credited = context.lst_contract.get_total_staked_balance().await.unwrap().0 - total_staked;
+    assert!(credited > 0);
+    assert_eq!(credited + quarantined, NearToken::from_near(1).as_yoctonear());
+
+    // an Accountant reviews the quarantined rewards
+    check!(context.lst_contract.release_quarantined_rewards(&context.alice, v1.0.id(), quarantined), ERR_PERM);
+    check!(context.lst_contract.acl_grant_role(&context.root, "Accountant".to_string(), context.manager.id()));
+    check!(
+        context.lst_contract.release_quarantined_rewards(&context.manager, v1.0.id(), quarantined + 1),
+        lst::ERR_INVALID_QUARANTINED_AMOUNT
+    );
+    let released = quarantined / 2;
+    check!(context.lst_contract.release_quarantined_rewards(&context.manager, v1.0.id(), released));
+    assert_eq!(
+        context.lst_contract.get_total_staked_balance().await.unwrap().0,
+        total_staked + credited + released
+    );
+    check!(context.lst_contract.discard_quarantined_rewards(&context.manager, v1.0.id(), quarantined - released));
+    let reward_cap = context.lst_contract.get_reward_cap().await.unwrap();
+    assert!(reward_cap.quarantined_rewards.is_empty());
+    assert_eq!(reward_cap.total_quarantined.0, 0);
+    check!(
+        context.lst_contract.discard_quarantined_rewards(&context.manager, v1.0.id(), 1),
+        lst::ERR_NO_QUARANTINED_REWARDS
+    );
+}
@@ -0,0 +1,54 @@
+mod setup;
+use setup::*;
+
+const ERR_PERM: &str = "Smart contract panicked: Insufficient permissions for method";
+
+#[tokio::test]
+async fn test_timelock() {
+    let worker = near_workspaces::sandbox().await.unwrap();
+    let context = Context::new(&worker, None).await;
+
+    // setting a beneficiary is delayed by default
+    let delays = context.lst_contract.get_timelock_delays().await.unwrap();
+    assert!(delays.iter().any(|d| d.kind == lst::TimelockActionKind::SetBeneficiary && d.delay_sec == lst::DEFAULT_SET_BENEFICIARY_DELAY_SEC));
+    check!(context.lst_contract.set_beneficiary(&context.root, context.manager.id(), 1000), lst::ERR_TIMELOCK_REQUIRED);
+
+    check!(context.lst_contract.set_timelock_delay(&context.alice, "SetBeneficiary", 10), ERR_PERM);
+    check!(
+        context.lst_contract.set_timelock_delay(&context.root, "SetBeneficiary", 31 * 24 * 3600),
+        lst::ERR_TIMELOCK_DELAY_TOO_LONG
+    );
+    check!(context.lst_contract.set_timelock_delay(&context.root, "SetBeneficiary", 10));
+    let delays = context.lst_contract.get_timelock_delays().await.unwrap();
+    assert_eq!(delays.len(), 5);
+    assert!(delays.iter().any(|d| d.kind == lst::TimelockActionKind::SetBeneficiary && d.delay_sec == 10));
+
+    // a delayed action can only go through the queue
+    check!(context.lst_contract.set_beneficiary(&context.root, context.manager.id(), 1000), lst::ERR_TIMELOCK_REQUIRED);
+    let action = json!({ "SetBeneficiary": { "account_id": context.manager.id(), "bps": 1000 } });
+    check!(context.lst_contract.queue_timelock_action(&context.alice, action.clone()), lst::ERR_TIMELOCK_NOT_ALLOWED);
+    check!(context.lst_contract.queue_timelock_action(&context.root, action));
+    check!(context.lst_contract.queue_timelock_action(
+        &context.root,
+        json!({ "RemoveBeneficiary": { "account_id": context.manager.id() } })
+    ));
+    let queue = context.lst_contract.get_timelock_queue().await.unwrap();
+    assert_eq!(queue.len(), 2);
+    assert_eq!(queue[0].id, 0);
+    assert_eq!(&queue[0].proposer_id, context.root.id());
+    assert_eq!(queue[0].eta.0, queue[0].queued_at.0 + 10 * 1_000_000_000);
+    // actions without a delay can be executed at once
+    assert_eq!(queue[1].eta, queue[1].queued_at);
+
+    check!(context.lst_contract.execute_timelock_action(&context.bob, 0), lst::ERR_TIMELOCK_NOT_READY);
+    check!(context.lst_contract.cancel_timelock_action(&context.alice, 1), ERR_PERM);
+    check!(context.lst_contract.cancel_timelock_action(&context.root, 1));
+    check!(context.lst_contract.execute_timelock_action(&context.bob, 1), lst::ERR_TIMELOCK_PROPOSAL_NOT_EXIST);
+
+    // anyone can execute after the delay
+    worker.fast_forward(100).await.unwrap();
+    check!(context.lst_contract.execute_timelock_action(&context.bob, 0));
+    assert_eq!(context.lst_contract.get_beneficiaries().await.unwrap().get(context.manager.id()), Some(&1000));
+    assert!(context.lst_contract.get_timelock_queue().await.unwrap().is_empty());
+    check!(context.lst_contract.execute_timelock_action(&context.bob, 0), lst::ERR_TIMELOCK_PROPOSAL_NOT_EXIST);
+}
@@ -44,3 +44,72 @@ async fn test_upgrade() {
         .up_deploy_code(&context.root, staged_code_hash));
     check!(view context.lst_contract.get_version());
 }
+
+#[tokio::test]
+async fn test_upgrade_from_previous_release() {
+    let worker = near_workspaces::sandbox().await.unwrap();
+    let context = Context::new(&worker, Some("../../res/lst_previous.wasm")).await;
+    check!(context.mock_whitelist.allow_all(&context.root));
+
+    // state written by the previous release
+    let v1 = context.create_validator("v1").await;
+    check!(context.lst_contract.add_validator(&context.root, v1.0.id(), 10));
+    check!(context.lst_contract.storage_deposit(&context.alice, None, FT_STORAGE_DEPOSIT));
+    check!(context.lst_contract.deposit_and_stake(&context.alice, 50));
+    context.op_epoch_stake_all().await;
+    check!(context.lst_contract.unstake(&context.alice, 5));
+
+    check!(context.lst_contract.up_stage_code(&context.root, "../../res/lst.wasm"));
+    let staged_code_hash = context.lst_contract.up_staged_code_hash().await.unwrap().unwrap();
+    check!(context.lst_contract.up_deploy_code(&context.root, staged_code_hash));
+    assert_eq!(context.lst_contract.get_version().await.unwrap(), env!("CARGO_PKG_VERSION"));
+
+    // accounts and validators of the old layout are still readable
+    let details = context.lst_contract.get_account_details(context.alice.id()).await.unwrap();
+    assert_eq!(details.unstaked_balance.0, NearToken::from_near(5).as_yoctonear());
+    assert_eq!(details.last_unstake_request_epoch_height, 15);
+    assert_eq!(details.matched_unstaked_balance.0, 0);
+    assert!(!details.can_withdraw);
+    context.check_validator_amount(&v1, NearToken::from_near(60).as_yoctonear(), 0, None, None).await;
+
+    // the owner keeps the roles of the former owner-only methods
+    for role in ["BeneficiaryManager", "WhitelistManager"] {
+        let grantees = context.lst_contract.acl_get_grantees(role.to_string()).await.unwrap();
+        assert!(grantees.contains(context.root.id()));
+    }
+    let set_beneficiary = json!({ "SetBeneficiary": { "account_id": context.bob.id(), "bps": 100 } });
+    check!(context.lst_contract.queue_timelock_action(&context.root, set_beneficiary));
+
+    // the new layout is used from here on
+    check!(context.lst_contract.storage_deposit(&context.bob, None, FT_STORAGE_DEPOSIT));
+    check!(context.lst_contract.deposit_and_stake(&context.bob, 10));
+    let details = context.lst_contract.get_account_details(context.bob.id()).await.unwrap();
+    assert_eq!(details.staked_balance.0, NearToken::from_near(10).as_yoctonear());
+
+    context.epoch_height_fast_forward(Some(NUM_EPOCHS_TO_UNLOCK + 1)).await;
+    check!(context.lst_contract.withdraw(&context.alice, 5));
+}
+
+#[tokio::test]
+async fn test_upgrade_grants_owner_roles() {
+    let worker = near_workspaces::sandbox().await.unwrap();
+    let context = Context::new(&worker, Some("../../res/lst_previous.wasm")).await;
+
+    check!(context.lst_contract.up_stage_code(&context.root, "../../res/lst.wasm"));
+    let staged_code_hash = context.lst_contract.up_staged_code_hash().await.unwrap().unwrap();
+    check!(context.lst_contract.up_deploy_code(&context.root, staged_code_hash));
+
+    // the owner of the previous release gets the roles of its owner-only methods
+    for role in ["BeneficiaryManager", "WhitelistManager"] {
+        let grantees = context.lst_contract.acl_get_grantees(role.to_string()).await.unwrap();
+        assert_eq!(grantees, vec![context.root.id().clone()]);
+    }
+    check!(context.lst_contract.set_whitelist_contract_id(&context.root, context.mock_whitelist.0.id()));
+
+    // migrating the current state again grants nothing more
+    check!(context.lst_contract.acl_revoke_role(&context.root, "WhitelistManager".to_string(), context.root.id()));
+    check!(context.lst_contract.up_stage_code(&context.root, "../../res/lst.wasm"));
+    let staged_code_hash = context.lst_contract.up_staged_code_hash().await.unwrap().unwrap();
+    check!(context.lst_contract.up_deploy_code(&context.root, staged_code_hash));
+    assert!(context.lst_contract.acl_get_grantees("WhitelistManager".to_string()).await.unwrap().is_empty());
+}
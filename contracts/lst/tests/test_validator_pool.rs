@@ -156,4 +156,49 @@ async fn test_vpool_update_weight() {
     // update foo and bar
     check!(context.lst_contract.update_weights(&context.root, vec![&v1, &v2], vec![100, 150]));
     assert_eq!(context.lst_contract.get_total_weight().await.unwrap(), 250);
-}
\ No newline at end of file
+}
+
+#[tokio::test]
+async fn test_vpool_reset_stuck_validator() {
+    let worker = near_workspaces::sandbox().await.unwrap();
+    let context = Context::new(&worker, None).await;
+    check!(context.mock_whitelist.allow_all(&context.root));
+
+    let v1 = context.create_validator("v1").await;
+    check!(context.lst_contract.add_validator(&context.root, v1.0.id(), 10));
+
+    // the validator loses 5 NEAR on stake, and the following rewards
+    // callback runs out of gas and leaves the validator executing
+    check!(v1.set_balance_delta(&context.root, NearToken::from_near(5).as_yoctonear(), 0));
+    check!(context.lst_contract.storage_deposit(&context.alice, None, FT_STORAGE_DEPOSIT));
+    check!(context.lst_contract.deposit_and_stake(&context.alice, 50));
+    context.op_epoch_stake_all().await;
+    let mut config = context.lst_contract.get_config().await.unwrap();
+    config.gas.cb_validator_get_balance = Gas::from_tgas(1);
+    check!(context.lst_contract.set_config(&context.root, &config));
+    let outcome = context.lst_contract.epoch_update_rewards(&context.root, v1.0.id()).await.unwrap();
+    assert!(!outcome.receipt_failures().is_empty());
+
+    let validator = context.lst_contract.get_validator(v1.0.id()).await.unwrap().unwrap();
+    assert!(matches!(validator.executing.unwrap().kind, lst::ExecutionKind::GetBalance));
+    assert!(context.lst_contract.get_stuck_validators().await.unwrap().is_empty());
+    check!(context.lst_contract.reset_stuck_validator(&context.root, v1.0.id()), lst::ERR_VALIDATOR_NOT_STUCK);
+
+    let config = context.lst_contract.get_config().await.unwrap();
+    worker.fast_forward(config.execution_timeout_blocks).await.unwrap();
+    let stuck_validators = context.lst_contract.get_stuck_validators().await.unwrap();
+    assert_eq!(stuck_validators.len(), 1);
+    assert_eq!(&stuck_validators[0].account_id, v1.0.id());
+
+    // only operators can reset a validator, and balances are synced before the flag is cleared
+    check!(context.lst_contract.reset_stuck_validator(&context.alice, v1.0.id()), "Insufficient permissions for method reset_stuck_validator");
+    check!(context.lst_contract.reset_stuck_validator(&context.root, v1.0.id()));
+    let validator = context.lst_contract.get_validator(v1.0.id()).await.unwrap().unwrap();
+    assert!(validator.executing.is_none());
+    assert_eq!(validator.staked_amount.0, NearToken::from_near(55).as_yoctonear());
+    assert!(context.lst_contract.get_stuck_validators().await.unwrap().is_empty());
+
+    // the loss found by the reset is taken out of the total staked NEAR
+    let summary = context.lst_contract.get_summary().await.unwrap();
+    assert_eq!(summary.total_staked_near_amount.0, NearToken::from_near(55).as_yoctonear());
+}